@@ -5,11 +5,12 @@ pub mod scene;
 
 use std::{
     env,
+    path::{Path, PathBuf},
     process::ExitCode,
     sync::{Arc, Mutex, mpsc},
 };
 
-use caustic_core::{Camera, Color, Node, RenderContext, random_new};
+use caustic_core::{Camera, Color, Node, RenderContext, SeededRandom, light::Lights, random_new};
 use indicatif::{ProgressBar, ProgressStyle};
 use scene::Scene;
 use thiserror::Error;
@@ -25,9 +26,23 @@ pub enum CliError {
 pub type Result<T> = core::result::Result<T, CliError>;
 
 const BLOCK_SIZE: u32 = 10;
+const DEFAULT_OUTPUT_FILENAME: &str = "../../target/out.png";
 
 fn main() -> ExitCode {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // `--seed <n>` makes the render reproducible, bit for bit, across runs
+    let mut seed = None;
+    if let Some(i) = args.iter().position(|arg| arg == "--seed") {
+        match args.get(i + 1).map(|value| value.parse::<u64>()) {
+            Some(Ok(value)) => seed = Some(value),
+            _ => {
+                eprintln!("--seed requires an unsigned integer value");
+                return ExitCode::from(1);
+            }
+        }
+        args.drain(i..i + 2);
+    }
 
     let mut scene = Scene::ThreeSpheres;
     if let Some(scene_name) = args.get(1) {
@@ -61,8 +76,17 @@ fn main() -> ExitCode {
         }
     }
 
+    // .exr and .hdr outputs get linear radiance, anything else is tone mapped 8-bit sRGB
+    let output_filename = args
+        .get(2)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT_FILENAME));
+
     let ctx = Arc::new(RenderContext {
-        random: random_new(),
+        random: match seed {
+            Some(seed) => Arc::new(SeededRandom::new(seed)),
+            None => random_new(),
+        },
     });
 
     let scene = match get_scene(&ctx, scene) {
@@ -74,10 +98,9 @@ fn main() -> ExitCode {
     };
 
     // render image
-    let mut img: image::ImageBuffer<
-        image::Rgb<u8>,
-        Vec<<image::Rgb<u8> as image::Pixel>::Subpixel>,
-    > = image::ImageBuffer::new(scene.camera.image_width(), scene.camera.image_height());
+    let width = scene.camera.image_width();
+    let height = scene.camera.image_height();
+    let mut img = vec![Color::BLACK; (width * height) as usize];
 
     // generate work
     let mut work: Vec<Work> = vec![];
@@ -90,16 +113,16 @@ fn main() -> ExitCode {
                 world: scene.world.clone(),
                 lights: scene.lights.clone(),
                 xmin: x,
-                xmax: (x + BLOCK_SIZE).min(img.width()),
+                xmax: (x + BLOCK_SIZE).min(width),
                 ymin: y,
-                ymax: (y + BLOCK_SIZE).min(img.height()),
+                ymax: (y + BLOCK_SIZE).min(height),
             });
-            if x > img.width() {
+            if x > width {
                 break;
             }
             x += BLOCK_SIZE;
         }
-        if y > img.height() {
+        if y > height {
             break;
         }
         y += BLOCK_SIZE;
@@ -135,12 +158,12 @@ fn main() -> ExitCode {
                             let mut pixels = vec![];
                             for y in item.ymin..item.ymax {
                                 for x in item.xmin..item.xmax {
-                                    let pixel_color = item.camera.render(
+                                    let pixel_color = item.camera.render_linear(
                                         &ctx,
                                         x,
                                         y,
                                         &*item.world,
-                                        item.lights.clone(),
+                                        &item.lights,
                                     );
                                     pixels.push(pixel_color);
                                 }
@@ -169,9 +192,8 @@ fn main() -> ExitCode {
                 let mut i = 0;
                 for y in result.ymin..result.ymax {
                     for x in result.xmin..result.xmax {
-                        if let Some(pixel) = img.get_pixel_mut_checked(x, y) {
-                            let pixel_color = result.pixels[i];
-                            *pixel = color_to_image_rgb(pixel_color);
+                        if x < width && y < height {
+                            img[(y * width + x) as usize] = result.pixels[i];
                             i += 1;
                         }
                     }
@@ -185,11 +207,42 @@ fn main() -> ExitCode {
         h.join().unwrap();
     }
 
-    img.save("../../target/out.png").unwrap();
+    if let Err(err) = save_image(&output_filename, &scene.camera, &img) {
+        eprintln!("failed to save image {output_filename:?}: {err}");
+        return ExitCode::from(1);
+    }
     pb.finish_with_message("Done!");
     ExitCode::SUCCESS
 }
 
+fn is_hdr_filename(filename: &Path) -> bool {
+    filename
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("exr") || ext.eq_ignore_ascii_case("hdr"))
+}
+
+/// Saves linear pixels, as full float RGB for HDR formats or as 8-bit RGB using the
+/// camera display transform otherwise.
+fn save_image(filename: &Path, camera: &Camera, pixels: &[Color]) -> image::ImageResult<()> {
+    let width = camera.image_width();
+    let height = camera.image_height();
+    let pixel = |x: u32, y: u32| pixels[(y * width + x) as usize];
+    if is_hdr_filename(filename) {
+        image::Rgb32FImage::from_fn(width, height, |x, y| color_to_image_rgb32f(pixel(x, y)))
+            .save(filename)
+    } else {
+        image::RgbImage::from_fn(width, height, |x, y| {
+            color_to_image_rgb(camera.display_transform(pixel(x, y)))
+        })
+        .save(filename)
+    }
+}
+
+fn color_to_image_rgb32f(color: Color) -> image::Rgb<f32> {
+    image::Rgb([color.r as f32, color.g as f32, color.b as f32])
+}
+
 fn color_to_image_rgb(color: Color) -> image::Rgb<u8> {
     let r = (color.r * 255.999) as u8;
     let g = (color.g * 255.999) as u8;
@@ -200,7 +253,7 @@ fn color_to_image_rgb(color: Color) -> image::Rgb<u8> {
 pub struct Work {
     pub camera: Arc<Camera>,
     pub world: Arc<dyn Node>,
-    pub lights: Option<Arc<dyn Node>>,
+    pub lights: Lights,
     pub xmin: u32,
     pub xmax: u32,
     pub ymin: u32,
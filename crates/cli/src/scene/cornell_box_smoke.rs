@@ -2,6 +2,8 @@ use std::sync::Arc;
 
 use caustic_core::{
     CameraBuilder, Color, Node, RenderContext, Vector3,
+    background::SolidBackground,
+    light::Lights,
     material::{DiffuseLight, EmptyMaterial, Lambertian},
     object::{
         BoundingVolumeHierarchy, BoxPrimitive, ConstantMedium, Group, Quad, Rotate, Translate,
@@ -108,13 +110,13 @@ pub fn create_cornell_box_smoke_scene(_ctx: &RenderContext) -> SceneData {
     camera_builder.look_from = Vector3::new(278.0, 278.0, -800.0);
     camera_builder.look_at = Vector3::new(278.0, 278.0, 0.0);
     camera_builder.up = Vector3::new(0.0, 1.0, 0.0);
-    camera_builder.background = Color::BLACK;
+    camera_builder.background = Arc::new(SolidBackground::new(Color::BLACK));
     camera_builder.defocus_angle = 0.0;
     let camera = Arc::new(camera_builder.build());
 
     SceneData {
         camera,
         world,
-        lights: Some(lights),
+        lights: Lights::from_geometry(lights),
     }
 }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use caustic_core::{
     CameraBuilder, Color, RenderContext, Vector3,
     image::ImageImage,
+    light::Lights,
     material::{Dielectric, DiffuseLight, EmptyMaterial, Lambertian, Metal},
     object::{
         BoundingVolumeHierarchy, BoxPrimitive, ConstantMedium, Group, Node, Quad, Rotate, Sphere,
@@ -167,6 +168,6 @@ pub fn create_final_scene(ctx: &RenderContext) -> SceneData {
     SceneData {
         camera,
         world,
-        lights: Some(lights),
+        lights: Lights::from_geometry(lights),
     }
 }
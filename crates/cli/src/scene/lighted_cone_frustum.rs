@@ -2,6 +2,8 @@ use std::sync::Arc;
 
 use caustic_core::{
     CameraBuilder, Color, RenderContext, Vector3,
+    background::SolidBackground,
+    light::Lights,
     material::{DiffuseLight, Lambertian},
     object::{BoundingVolumeHierarchy, ConeFrustum, Node, Quad, Sphere},
     texture::PerlinTurbulenceTexture,
@@ -61,12 +63,12 @@ pub fn create_lighted_cone_frustum_scene(ctx: &RenderContext) -> SceneData {
     camera_builder.look_at = Vector3::new(0.0, 2.0, 0.0);
     camera_builder.up = Vector3::new(0.0, 1.0, 0.0);
     camera_builder.defocus_angle = 0.0;
-    camera_builder.background = Color::new(0.0, 0.0, 0.0);
+    camera_builder.background = Arc::new(SolidBackground::new(Color::new(0.0, 0.0, 0.0)));
     let camera = Arc::new(camera_builder.build());
 
     SceneData {
         camera,
         world,
-        lights: None,
+        lights: Lights::default(),
     }
 }
@@ -2,6 +2,8 @@ use std::sync::Arc;
 
 use caustic_core::{
     CameraBuilder, Color, Node, RenderContext, Vector3,
+    background::SolidBackground,
+    light::Lights,
     material::Lambertian,
     object::{BoundingVolumeHierarchy, Quad},
 };
@@ -63,12 +65,12 @@ pub fn create_quads_scene(_ctx: &RenderContext) -> SceneData {
     camera_builder.look_at = Vector3::new(0.0, 0.0, 0.0);
     camera_builder.up = Vector3::new(0.0, 1.0, 0.0);
     camera_builder.defocus_angle = 0.0;
-    camera_builder.background = Color::new(0.7, 0.8, 1.0);
+    camera_builder.background = Arc::new(SolidBackground::new(Color::new(0.7, 0.8, 1.0)));
     let camera = Arc::new(camera_builder.build());
 
     SceneData {
         camera,
         world,
-        lights: None,
+        lights: Lights::default(),
     }
 }
@@ -2,6 +2,8 @@ use std::sync::Arc;
 
 use caustic_core::{
     CameraBuilder, Color, RenderContext, Vector3,
+    background::SolidBackground,
+    light::Lights,
     material::{Dielectric, Lambertian, Metal},
     object::{BoundingVolumeHierarchy, Node, Sphere},
     texture::{CheckerTexture, SolidColor},
@@ -59,12 +61,12 @@ pub fn create_three_spheres_scene(_ctx: &RenderContext) -> SceneData {
     camera_builder.max_depth = 50;
     camera_builder.defocus_angle = 0.6;
     camera_builder.focus_distance = 1.0;
-    camera_builder.background = Color::new(0.7, 0.8, 1.0);
+    camera_builder.background = Arc::new(SolidBackground::new(Color::new(0.7, 0.8, 1.0)));
     let camera = Arc::new(camera_builder.build());
 
     SceneData {
         camera,
         world,
-        lights: None,
+        lights: Lights::default(),
     }
 }
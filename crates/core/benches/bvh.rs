@@ -0,0 +1,103 @@
+//! Compares building and traversing a [`BoundingVolumeHierarchy`] split with the
+//! surface area heuristic against one split at the median.
+//!
+//! Run with `cargo bench -p caustic-core --bench bvh`.
+
+use std::{hint::black_box, sync::Arc};
+
+use caustic_core::{
+    Color, Interval, Ray, RenderContext, Vector3,
+    material::Lambertian,
+    object::{BoundingVolumeHierarchy, BvhSplitMethod, Node, Sphere},
+    random_new,
+};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+const SPLIT_METHODS: [(&str, BvhSplitMethod); 2] = [
+    ("median", BvhSplitMethod::Median),
+    ("sah", BvhSplitMethod::SurfaceAreaHeuristic),
+];
+
+/// Small spheres scattered over a ground plane with a few large ones, similar to the
+/// `random_spheres` scene but with a configurable count.
+fn create_spheres(ctx: &RenderContext, count: usize) -> Vec<Arc<dyn Node>> {
+    let material = Arc::new(Lambertian::new_from_color(Color::new(0.5, 0.5, 0.5)));
+    let extent = (count as f64).sqrt();
+    let mut spheres: Vec<Arc<dyn Node>> = vec![Arc::new(Sphere::new(
+        Vector3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        material.clone(),
+    ))];
+    for _ in 0..count {
+        let center = Vector3::new(
+            ctx.random.rand_interval(-extent, extent),
+            0.2,
+            ctx.random.rand_interval(-extent, extent),
+        );
+        spheres.push(Arc::new(Sphere::new(center, 0.2, material.clone())));
+    }
+    for x in [-4.0, 0.0, 4.0] {
+        spheres.push(Arc::new(Sphere::new(
+            Vector3::new(x, 1.0, 0.0),
+            1.0,
+            material.clone(),
+        )));
+    }
+    spheres
+}
+
+/// Rays from a camera position above the spheres towards random points on the ground.
+fn create_rays(ctx: &RenderContext, count: usize, extent: f64) -> Vec<Ray> {
+    let origin = Vector3::new(13.0, 2.0, 3.0);
+    (0..count)
+        .map(|_| {
+            let target = Vector3::new(
+                ctx.random.rand_interval(-extent, extent),
+                0.0,
+                ctx.random.rand_interval(-extent, extent),
+            );
+            Ray::new(origin, target - origin)
+        })
+        .collect()
+}
+
+fn bench_build(c: &mut Criterion) {
+    let ctx = RenderContext {
+        random: random_new(),
+    };
+    let mut group = c.benchmark_group("bvh_build");
+    for count in [500, 10_000] {
+        let spheres = create_spheres(&ctx, count);
+        for (name, split_method) in SPLIT_METHODS {
+            group.bench_with_input(BenchmarkId::new(name, count), &spheres, |b, spheres| {
+                b.iter(|| BoundingVolumeHierarchy::new_with_split_method(spheres, split_method))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_hit(c: &mut Criterion) {
+    let ctx = RenderContext {
+        random: random_new(),
+    };
+    let mut group = c.benchmark_group("bvh_hit");
+    for count in [500, 10_000] {
+        let spheres = create_spheres(&ctx, count);
+        let rays = create_rays(&ctx, 1000, (count as f64).sqrt());
+        for (name, split_method) in SPLIT_METHODS {
+            let bvh = BoundingVolumeHierarchy::new_with_split_method(&spheres, split_method);
+            group.bench_with_input(BenchmarkId::new(name, count), &rays, |b, rays| {
+                b.iter(|| {
+                    for ray in rays {
+                        black_box(bvh.hit(&ctx, ray, Interval::new(0.001, f64::INFINITY)));
+                    }
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_build, bench_hit);
+criterion_main!(benches);
@@ -257,6 +257,49 @@ impl AxisAlignedBoundingBox {
         }
     }
 
+    /// Returns the center point of the bounding box.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::{AxisAlignedBoundingBox, Vector3};
+    ///
+    /// let bbox = AxisAlignedBoundingBox::new_from_points(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(2.0, 4.0, 6.0)
+    /// );
+    /// let center = bbox.centroid();
+    /// assert_eq!(center.y, 2.0);
+    /// ```
+    pub fn centroid(&self) -> Vector3 {
+        Vector3::new(
+            (self.x.min + self.x.max) / 2.0,
+            (self.y.min + self.y.max) / 2.0,
+            (self.z.min + self.z.max) / 2.0,
+        )
+    }
+
+    /// Returns the total area of the six faces of the bounding box.
+    ///
+    /// Used by the surface area heuristic, where the probability of a random ray
+    /// hitting a box is proportional to its surface area.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::{AxisAlignedBoundingBox, Vector3};
+    ///
+    /// let bbox = AxisAlignedBoundingBox::new_from_points(
+    ///     Vector3::new(0.0, 0.0, 0.0),
+    ///     Vector3::new(1.0, 2.0, 3.0)
+    /// );
+    /// assert_eq!(bbox.surface_area(), 22.0);
+    /// ```
+    pub fn surface_area(&self) -> f64 {
+        let (x, y, z) = (self.x.size(), self.y.size(), self.z.size());
+        2.0 * (x * y + y * z + z * x)
+    }
+
     /// Adjusts the AABB to ensure no dimension is narrower than a minimum threshold.
     ///
     /// This prevents degenerate bounding boxes (like infinitely thin planes) from
@@ -0,0 +1,250 @@
+use std::{
+    f64::consts::PI,
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+use crate::{Color, Image, Random, Vector3, background::Background};
+
+/// An equirectangular (latitude/longitude) environment image surrounding the scene.
+///
+/// The top row of the image maps to +y (up) and the horizontal axis wraps around y
+/// using the same convention as the sphere texture coordinates. Directions can be
+/// importance sampled proportionally to pixel luminance, weighted by the solid angle
+/// each row covers, so bright regions such as the sun are found with few samples.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use caustic_core::{CameraBuilder, background::EnvironmentMap, image::ImageImage};
+///
+/// let image = ImageImage::load_file("sky.hdr").unwrap();
+/// let mut camera_builder = CameraBuilder::new();
+/// camera_builder.background = Arc::new(EnvironmentMap::new(image));
+/// ```
+pub struct EnvironmentMap {
+    image: Arc<dyn Image>,
+    width: usize,
+    height: usize,
+    /// Unnormalized sampling weight of each pixel, row-major
+    weights: Vec<f64>,
+    /// Sum of all entries in `weights`
+    weight_sum: f64,
+    /// Normalized cumulative distribution of each row, `width + 1` entries per row
+    conditional_cdf: Vec<f64>,
+    /// Normalized cumulative distribution over the rows, `height + 1` entries
+    marginal_cdf: Vec<f64>,
+}
+
+impl EnvironmentMap {
+    /// Creates an environment map from an equirectangular image and builds the
+    /// luminance based sampling distribution.
+    pub fn new(image: Arc<dyn Image>) -> Self {
+        let width = image.width().max(1) as usize;
+        let height = image.height().max(1) as usize;
+
+        let mut weights = vec![0.0; width * height];
+        for y in 0..height {
+            // rows near the poles cover less solid angle
+            let sin_theta = ((y as f64 + 0.5) / height as f64 * PI).sin();
+            for x in 0..width {
+                let luminance = image
+                    .get_pixel(x as u32, y as u32)
+                    .map(|c| c.luminance())
+                    .unwrap_or(0.0);
+                weights[y * width + x] = luminance.max(0.0) * sin_theta;
+            }
+        }
+
+        // a black image is sampled by solid angle alone
+        if weights.iter().all(|w| *w <= 0.0) {
+            for y in 0..height {
+                let sin_theta = ((y as f64 + 0.5) / height as f64 * PI).sin();
+                weights[y * width..(y + 1) * width].fill(sin_theta);
+            }
+        }
+
+        let mut conditional_cdf = Vec::with_capacity(height * (width + 1));
+        let mut row_sums = Vec::with_capacity(height);
+        for row in weights.chunks_exact(width) {
+            let (cdf, sum) = build_cdf(row);
+            conditional_cdf.extend(cdf);
+            row_sums.push(sum);
+        }
+        let (marginal_cdf, weight_sum) = build_cdf(&row_sums);
+
+        Self {
+            image,
+            width,
+            height,
+            weights,
+            weight_sum,
+            conditional_cdf,
+            marginal_cdf,
+        }
+    }
+
+    fn direction_to_pixel(&self, direction: &Vector3) -> (usize, usize) {
+        let (u, v) = direction_to_uv(&direction.unit());
+        let x = ((u * self.width as f64) as usize).min(self.width - 1);
+        let y = ((v * self.height as f64) as usize).min(self.height - 1);
+        (x, y)
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn value(&self, direction: &Vector3) -> Color {
+        let (x, y) = self.direction_to_pixel(direction);
+        self.image
+            .get_pixel(x as u32, y as u32)
+            .unwrap_or(Color::BLACK)
+    }
+
+    fn importance_sampled(&self) -> bool {
+        true
+    }
+
+    fn pdf_value(&self, direction: &Vector3) -> f64 {
+        let direction = direction.unit();
+        let sin_theta = (1.0 - direction.y * direction.y).max(0.0).sqrt();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+
+        let (x, y) = self.direction_to_pixel(&direction);
+        let pixel_probability = self.weights[y * self.width + x] / self.weight_sum;
+        // density over the unit square of image coordinates
+        let pdf_uv = pixel_probability * (self.width * self.height) as f64;
+        pdf_uv / (2.0 * PI * PI * sin_theta)
+    }
+
+    fn random(&self, random: &dyn Random) -> Vector3 {
+        let (r1, r2) = random.rand_2d();
+        let y = sample_cdf(&self.marginal_cdf, r1);
+        let row = &self.conditional_cdf[y * (self.width + 1)..(y + 1) * (self.width + 1)];
+        let x = sample_cdf(row, r2);
+
+        let (jitter_u, jitter_v) = random.rand_2d();
+        let u = (x as f64 + jitter_u) / self.width as f64;
+        let v = (y as f64 + jitter_v) / self.height as f64;
+        uv_to_direction(u, v)
+    }
+}
+
+impl Debug for EnvironmentMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnvironmentMap")
+            .field("image", &self.image)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish()
+    }
+}
+
+/// Converts a unit direction to equirectangular image coordinates in [0, 1], with
+/// v = 0 looking straight up.
+fn direction_to_uv(direction: &Vector3) -> (f64, f64) {
+    let theta = direction.y.clamp(-1.0, 1.0).acos();
+    let phi = (-direction.z).atan2(direction.x) + PI;
+    (phi / (2.0 * PI), theta / PI)
+}
+
+/// Inverse of [`direction_to_uv`].
+fn uv_to_direction(u: f64, v: f64) -> Vector3 {
+    let phi = u * 2.0 * PI;
+    let theta = v * PI;
+    let sin_theta = theta.sin();
+    Vector3::new(-sin_theta * phi.cos(), theta.cos(), sin_theta * phi.sin())
+}
+
+/// Builds a normalized cumulative distribution with `values.len() + 1` entries and
+/// returns it with the sum of the values. All zero values produce a uniform
+/// distribution.
+fn build_cdf(values: &[f64]) -> (Vec<f64>, f64) {
+    let mut cdf = Vec::with_capacity(values.len() + 1);
+    let mut sum = 0.0;
+    cdf.push(0.0);
+    for value in values {
+        sum += value;
+        cdf.push(sum);
+    }
+
+    let n = values.len() as f64;
+    for (i, c) in cdf.iter_mut().enumerate() {
+        *c = if sum > 0.0 { *c / sum } else { i as f64 / n };
+    }
+    (cdf, sum)
+}
+
+/// Returns the index of the bin of `cdf` containing `xi`, never a zero width bin.
+fn sample_cdf(cdf: &[f64], xi: f64) -> usize {
+    let bin = cdf.partition_point(|c| *c <= xi);
+    bin.saturating_sub(1).min(cdf.len() - 2)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{f64::consts::PI, sync::Arc};
+
+    use crate::{Color, Image, Vector3, background::Background, random_new};
+
+    use super::{EnvironmentMap, direction_to_uv, uv_to_direction};
+
+    #[derive(Debug)]
+    struct SunImage;
+
+    impl Image for SunImage {
+        fn width(&self) -> u32 {
+            8
+        }
+
+        fn height(&self) -> u32 {
+            4
+        }
+
+        fn get_pixel(&self, x: u32, y: u32) -> Option<Color> {
+            if x == 5 && y == 1 {
+                Some(Color::new(1000.0, 1000.0, 1000.0))
+            } else {
+                Some(Color::new(0.1, 0.1, 0.1))
+            }
+        }
+    }
+
+    #[test]
+    fn uv_round_trip() {
+        for (u, v) in [(0.1, 0.2), (0.5, 0.5), (0.9, 0.75)] {
+            let (u2, v2) = direction_to_uv(&uv_to_direction(u, v));
+            assert!((u - u2).abs() < 1e-9);
+            assert!((v - v2).abs() < 1e-9);
+        }
+        assert!(uv_to_direction(0.3, 0.0).y > 0.999);
+    }
+
+    #[test]
+    fn samples_favor_bright_pixels() {
+        let random = random_new();
+        let environment_map = EnvironmentMap::new(Arc::new(SunImage));
+
+        let sun_samples = (0..1000)
+            .map(|_| environment_map.random(&*random))
+            .filter(|direction| environment_map.value(direction).r > 1.0)
+            .count();
+        assert!(sun_samples > 900, "{sun_samples}");
+    }
+
+    #[test]
+    fn pdf_integrates_to_one() {
+        let random = random_new();
+        let environment_map = EnvironmentMap::new(Arc::new(SunImage));
+
+        // uniform sphere sampling estimate of the integral of the pdf
+        let n = 200_000;
+        let sum: f64 = (0..n)
+            .map(|_| environment_map.pdf_value(&Vector3::random_unit(&*random)) * 4.0 * PI)
+            .sum();
+        let integral = sum / n as f64;
+        assert!((integral - 1.0).abs() < 0.05, "{integral}");
+    }
+}
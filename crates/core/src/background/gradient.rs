@@ -0,0 +1,41 @@
+use crate::{Color, Vector3, background::Background};
+
+/// A vertical gradient blending between two colors based on the height of the ray
+/// direction, as used in "Ray Tracing in One Weekend".
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{Color, Vector3, background::{Background, GradientBackground}};
+///
+/// let background = GradientBackground::default();
+/// let up = background.value(&Vector3::new(0.0, 1.0, 0.0));
+/// assert_eq!(up, Color::new(0.5, 0.7, 1.0));
+/// ```
+#[derive(Debug)]
+pub struct GradientBackground {
+    /// Color looking straight down
+    bottom: Color,
+    /// Color looking straight up
+    top: Color,
+}
+
+impl GradientBackground {
+    pub fn new(bottom: Color, top: Color) -> Self {
+        Self { bottom, top }
+    }
+}
+
+impl Default for GradientBackground {
+    /// White fading to light blue.
+    fn default() -> Self {
+        Self::new(Color::WHITE, Color::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Background for GradientBackground {
+    fn value(&self, direction: &Vector3) -> Color {
+        let a = 0.5 * (direction.unit().y + 1.0);
+        (1.0 - a) * self.bottom + a * self.top
+    }
+}
@@ -0,0 +1,40 @@
+use std::fmt::Debug;
+
+use crate::{Color, Random, Vector3};
+
+pub mod environment_map;
+pub mod gradient;
+pub mod sky;
+pub mod solid;
+
+pub use environment_map::EnvironmentMap;
+pub use gradient::GradientBackground;
+pub use sky::SkyBackground;
+pub use solid::SolidBackground;
+
+/// Radiance arriving from infinitely far away, seen by rays that miss every object.
+///
+/// Backgrounds that contribute a significant amount of light, such as environment
+/// images, can opt into being importance sampled like a light by returning `true`
+/// from [`Background::importance_sampled`] and implementing
+/// [`Background::pdf_value`] and [`Background::random`].
+pub trait Background: Debug + Send + Sync {
+    /// Returns the radiance arriving from `direction`.
+    fn value(&self, direction: &Vector3) -> Color;
+
+    /// Returns true if the camera should sample directions towards this background.
+    fn importance_sampled(&self) -> bool {
+        false
+    }
+
+    /// Returns the probability density, with respect to solid angle, of
+    /// [`Background::random`] generating `direction`.
+    fn pdf_value(&self, _direction: &Vector3) -> f64 {
+        0.0
+    }
+
+    /// Generates a random direction towards the background.
+    fn random(&self, _random: &dyn Random) -> Vector3 {
+        Vector3::new(1.0, 0.0, 0.0)
+    }
+}
@@ -0,0 +1,145 @@
+use std::f64::consts::PI;
+
+use crate::{Color, Vector3, background::Background};
+
+/// Scale from the model's luminance in kcd/m² to scene radiance, so a clear midday
+/// zenith is roughly as bright as a white diffuse light.
+const LUMINANCE_SCALE: f64 = 0.1;
+
+/// Analytic daylight sky using the Preetham, Shirley and Smits (1999) model.
+///
+/// The sky color depends on the direction of the sun and the turbidity of the
+/// atmosphere, from about 2 for a very clear sky to 10 for a hazy one. Only the
+/// scattered skylight is modeled, the sun disc itself is not drawn. Directions below
+/// the horizon see the color at the horizon.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{Vector3, background::{Background, SkyBackground}};
+///
+/// let sky = SkyBackground::new(Vector3::new(0.0, 1.0, 1.0), 3.0);
+/// let zenith = sky.value(&Vector3::new(0.0, 1.0, 0.0));
+/// assert!(zenith.b > zenith.r);
+/// ```
+#[derive(Debug)]
+pub struct SkyBackground {
+    sun_direction: Vector3,
+    /// Perez distribution coefficients A to E for luminance Y and chromaticity x, y
+    coefficients: [[f64; 5]; 3],
+    /// Zenith Y, x and y divided by the Perez function evaluated at the zenith
+    zenith_scale: [f64; 3],
+}
+
+impl SkyBackground {
+    /// Creates a sky lit by a sun in `sun_direction`, which points from the scene
+    /// towards the sun.
+    pub fn new(sun_direction: Vector3, turbidity: f64) -> Self {
+        let sun_direction = sun_direction.unit();
+        let t = turbidity;
+        let theta_s = sun_direction.y.clamp(0.0, 1.0).acos();
+
+        let coefficients = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let zenith_luminance = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+
+        let theta2 = theta_s * theta_s;
+        let theta3 = theta2 * theta_s;
+        let t2 = t * t;
+        let zenith_x = t2 * (0.00166 * theta3 - 0.00375 * theta2 + 0.00209 * theta_s)
+            + t * (-0.02903 * theta3 + 0.06377 * theta2 - 0.03202 * theta_s + 0.00394)
+            + (0.11693 * theta3 - 0.21196 * theta2 + 0.06052 * theta_s + 0.25886);
+        let zenith_y = t2 * (0.00275 * theta3 - 0.00610 * theta2 + 0.00317 * theta_s)
+            + t * (-0.04214 * theta3 + 0.08970 * theta2 - 0.04153 * theta_s + 0.00516)
+            + (0.15346 * theta3 - 0.26756 * theta2 + 0.06670 * theta_s + 0.26688);
+
+        let zenith = [zenith_luminance.max(0.0), zenith_x, zenith_y];
+        let zenith_scale = [0, 1, 2].map(|i| zenith[i] / perez(&coefficients[i], 1.0, theta_s));
+
+        Self {
+            sun_direction,
+            coefficients,
+            zenith_scale,
+        }
+    }
+}
+
+/// The Perez sky luminance distribution for a view direction with zenith angle
+/// cosine `cos_theta` at angle `gamma` from the sun.
+fn perez(c: &[f64; 5], cos_theta: f64, gamma: f64) -> f64 {
+    (1.0 + c[0] * (c[1] / cos_theta).exp())
+        * (1.0 + c[2] * (c[3] * gamma).exp() + c[4] * gamma.cos().powi(2))
+}
+
+impl Background for SkyBackground {
+    fn value(&self, direction: &Vector3) -> Color {
+        let direction = direction.unit();
+        // keep away from the horizon where the Perez function is undefined
+        let cos_theta = direction.y.max(0.01);
+        let gamma = direction.dot(&self.sun_direction).clamp(-1.0, 1.0).acos();
+
+        let [luminance, x, y] = [0, 1, 2]
+            .map(|i| self.zenith_scale[i] * perez(&self.coefficients[i], cos_theta, gamma));
+        if y <= 0.0 {
+            return Color::BLACK;
+        }
+
+        // xyY to CIE XYZ to linear sRGB
+        let luminance = luminance * LUMINANCE_SCALE;
+        let cie_x = x / y * luminance;
+        let cie_z = (1.0 - x - y) / y * luminance;
+        Color::from_cie_xyz(cie_x, luminance, cie_z).clamp(0.0, f64::INFINITY)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Vector3, background::Background};
+
+    use super::SkyBackground;
+
+    #[test]
+    fn brighter_towards_sun() {
+        let sky = SkyBackground::new(Vector3::new(1.0, 0.5, 0.0), 3.0);
+        let towards_sun = sky.value(&Vector3::new(1.0, 0.6, 0.0));
+        let away_from_sun = sky.value(&Vector3::new(-1.0, 0.6, 0.0));
+        assert!(towards_sun.luminance() > away_from_sun.luminance());
+    }
+
+    #[test]
+    fn hazier_sky_is_less_blue() {
+        let up = Vector3::new(0.0, 1.0, 0.0);
+        let sun = Vector3::new(0.0, 1.0, 1.0);
+        let clear = sky_blueness(&SkyBackground::new(sun, 2.0), &up);
+        let hazy = sky_blueness(&SkyBackground::new(sun, 8.0), &up);
+        assert!(clear > hazy, "{clear} {hazy}");
+    }
+
+    fn sky_blueness(sky: &SkyBackground, direction: &Vector3) -> f64 {
+        let color = sky.value(direction);
+        color.b / color.r
+    }
+}
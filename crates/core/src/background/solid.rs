@@ -0,0 +1,19 @@
+use crate::{Color, Vector3, background::Background};
+
+/// A background with the same color in every direction.
+#[derive(Debug)]
+pub struct SolidBackground {
+    color: Color,
+}
+
+impl SolidBackground {
+    pub fn new(color: Color) -> Self {
+        Self { color }
+    }
+}
+
+impl Background for SolidBackground {
+    fn value(&self, _direction: &Vector3) -> Color {
+        self.color
+    }
+}
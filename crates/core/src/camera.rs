@@ -1,10 +1,27 @@
 use std::{f64, sync::Arc};
 
 use crate::{
-    Color, HittablePdf, Interval, Random, Ray, RenderContext, Vector3, material::PdfOrRay,
-    object::Node, probability_density_function::MixturePdf,
+    BackgroundPdf, Color, HittablePdf, Interval, ProbabilityDensityFunction, Random, Ray,
+    RenderContext, Sampler, SamplerType, ToneMapping, Vector3,
+    background::{Background, SolidBackground},
+    light::Lights,
+    material::PdfOrRay,
+    object::{HitRecord, Node},
+    probability_density_function::{MixturePdf, power_heuristic},
+    spectrum::{sample_visible_wavelength, wavelength_weight},
 };
 
+/// Number of bounces every path takes before Russian roulette may terminate it.
+pub const RUSSIAN_ROULETTE_MIN_BOUNCES: u32 = 3;
+
+/// Upper bound of the probability of a path surviving Russian roulette, so even
+/// paths that lose no energy, such as between perfect mirrors, end eventually.
+const RUSSIAN_ROULETTE_MAX_SURVIVAL: f64 = 0.95;
+
+/// Upper bound of the steps of the random walks through media along a path, which
+/// don't count as bounces, so a walk stuck in a medium too dense to leave ends.
+const MAX_WALK_STEPS: u32 = 100_000;
+
 /// Builder for configuring and constructing a [`Camera`].
 ///
 /// The `CameraBuilder` uses the builder pattern to configure camera parameters
@@ -14,7 +31,8 @@ use crate::{
 /// # Examples
 ///
 /// ```
-/// use caustic_core::{CameraBuilder, Vector3, Color};
+/// use std::sync::Arc;
+/// use caustic_core::{CameraBuilder, Vector3, Color, ToneMapping, background::SolidBackground};
 ///
 /// let mut camera_builder = CameraBuilder::new();
 /// camera_builder.aspect_ratio = 16.0 / 9.0;
@@ -27,7 +45,9 @@ use crate::{
 /// camera_builder.up = Vector3::new(0.0, 1.0, 0.0);
 /// camera_builder.defocus_angle = 0.6;
 /// camera_builder.focus_distance = 10.0;
-/// camera_builder.background = Color::new(0.7, 0.8, 1.0);
+/// camera_builder.background = Arc::new(SolidBackground::new(Color::new(0.7, 0.8, 1.0)));
+/// camera_builder.exposure = 0.5;
+/// camera_builder.tone_mapping = ToneMapping::AcesFilmic;
 /// let camera = camera_builder.build();
 /// ```
 #[derive(Debug)]
@@ -76,16 +96,62 @@ pub struct CameraBuilder {
     /// Higher values produce smoother, less noisy images but take longer to render.
     pub samples_per_pixel: u32,
 
+    /// Noise threshold for adaptive sampling, 0 to disable.
+    ///
+    /// When enabled each pixel stops sampling once the estimated noise of its
+    /// luminance, measured on a gamma 2 display scale where 1.0 is white, drops below
+    /// this value, so `samples_per_pixel` becomes an upper bound. Typical values are
+    /// 0.005 to 0.02.
+    pub adaptive_threshold: f64,
+
+    /// Minimum number of samples taken for each pixel before adaptive sampling may
+    /// stop early.
+    pub min_samples_per_pixel: u32,
+
+    /// Source of the sample values of each path.
+    ///
+    /// The low-discrepancy samplers spread the samples of a pixel more evenly than
+    /// independent random values, which noticeably reduces noise at low sample
+    /// counts.
+    pub sampler: SamplerType,
+
     /// Maximum number of ray bounces into scene.
     ///
     /// Limits recursion depth to prevent infinite loops and control render time.
     /// Higher values allow more light bounces but increase computation.
     pub max_depth: u32,
 
-    /// Scene background color.
+    /// Scene background.
+    ///
+    /// Provides the color for rays that don't hit any objects in the scene, for
+    /// example a solid color, a gradient, a sky model or an environment image.
+    pub background: Arc<dyn Background>,
+
+    /// Exposure adjustment in stops (EV).
+    ///
+    /// Linear radiance is multiplied by `2^exposure` before tone mapping, so +1
+    /// doubles the brightness and -1 halves it.
+    pub exposure: f64,
+
+    /// Operator used to map exposed radiance into the displayable range.
+    pub tone_mapping: ToneMapping,
+
+    /// Traces each path with a single wavelength of light, so dispersive materials
+    /// split white light into its colors.
     ///
-    /// Color returned when a ray doesn't hit any objects in the scene.
-    pub background: Color,
+    /// Paths that reach a dispersive material get the color of their wavelength,
+    /// which adds color noise to those parts of the image. Other paths are unchanged.
+    pub spectral: bool,
+
+    /// Time at which the shutter opens.
+    ///
+    /// Each ray gets a time between `shutter_open` and `shutter_close`, so objects
+    /// moving over that period, such as moving spheres and animated transforms, are
+    /// motion blurred. Equal values freeze the scene at that time.
+    pub shutter_open: f64,
+
+    /// Time at which the shutter closes, see `shutter_open`.
+    pub shutter_close: f64,
 }
 
 impl CameraBuilder {
@@ -95,27 +161,43 @@ impl CameraBuilder {
     /// - aspect_ratio: 1.0 (square)
     /// - image_width: 100 pixels
     /// - samples_per_pixel: 10
+    /// - adaptive_threshold: 0 (adaptive sampling disabled)
+    /// - min_samples_per_pixel: 16
+    /// - sampler: [`SamplerType::Random`]
     /// - max_depth: 10 bounces
-    /// - background: black (0, 0, 0)
+    /// - background: solid black (0, 0, 0)
     /// - vertical_fov: 90 degrees
     /// - look_from: (0, 0, 0)
     /// - look_at: (0, 0, -1)
     /// - up: (0, 1, 0)
     /// - defocus_angle: 0 (no depth of field)
     /// - focus_distance: 10
+    /// - exposure: 0 (no adjustment)
+    /// - tone_mapping: [`ToneMapping::None`]
+    /// - spectral: false (RGB rendering)
+    /// - shutter_open: 0
+    /// - shutter_close: 1
     pub fn new() -> Self {
         CameraBuilder {
             aspect_ratio: 1.0,
             image_width: 100,
             samples_per_pixel: 10,
+            adaptive_threshold: 0.0,
+            min_samples_per_pixel: 16,
+            sampler: SamplerType::Random,
             max_depth: 10,
-            background: Color::new(0.0, 0.0, 0.0),
+            background: Arc::new(SolidBackground::new(Color::new(0.0, 0.0, 0.0))),
             vertical_fov: 90.0,
             look_from: Vector3::new(0.0, 0.0, 0.0),
             look_at: Vector3::new(0.0, 0.0, -1.0),
             up: Vector3::new(0.0, 1.0, 0.0),
             defocus_angle: 0.0,
             focus_distance: 10.0,
+            exposure: 0.0,
+            tone_mapping: ToneMapping::None,
+            spectral: false,
+            shutter_open: 0.0,
+            shutter_close: 1.0,
         }
     }
 
@@ -174,8 +256,17 @@ impl CameraBuilder {
             defocus_angle: self.defocus_angle,
             defocus_disk_u,
             defocus_disk_v,
-            background: self.background,
+            background: self.background.clone(),
+            exposure_scale: 2.0_f64.powf(self.exposure),
+            tone_mapping: self.tone_mapping,
+            spectral: self.spectral,
+            shutter_open: self.shutter_open,
+            shutter_close: self.shutter_close,
+            adaptive_threshold: self.adaptive_threshold,
+            min_samples_per_pixel: self.min_samples_per_pixel,
+            sampler: self.sampler,
             sqrt_spp,
+            strata_stride: coprime_stride(sqrt_spp * sqrt_spp),
             reciprocal_sqrt_spp,
             pixel_samples_scale,
         }
@@ -222,142 +313,541 @@ pub struct Camera {
     defocus_disk_u: Vector3,
     /// Defocus disk vertical radius vector
     defocus_disk_v: Vector3,
-    /// Scene background color for rays that miss all objects
-    background: Color,
+    /// Scene background for rays that miss all objects
+    background: Arc<dyn Background>,
+    /// Linear radiance multiplier derived from the exposure in stops (2^exposure)
+    exposure_scale: f64,
+    /// Operator used to map exposed radiance into the displayable range
+    tone_mapping: ToneMapping,
+    /// Trace a single wavelength per path
+    spectral: bool,
+    /// Time at which the shutter opens
+    shutter_open: f64,
+    /// Time at which the shutter closes
+    shutter_close: f64,
+    /// Largest noise of the luminance of a pixel on a gamma 2 display scale, where 1.0
+    /// is white, at which adaptive sampling stops, 0 if disabled
+    adaptive_threshold: f64,
+    /// Samples taken before adaptive sampling may stop early
+    min_samples_per_pixel: u32,
+    /// Source of the sample values of each path
+    sampler: SamplerType,
     /// Square root of number of samples per pixel
     sqrt_spp: u32,
+    /// Step between the strata visited by consecutive sample indices
+    strata_stride: u32,
     /// Reciprocal of sqrt_spp (1 / sqrt_spp)
     reciprocal_sqrt_spp: f64,
 }
 
+/// A scattering vertex of a path, where next-event estimation connects to the
+/// lights.
+struct PathVertex<'a> {
+    r_in: &'a Ray,
+    hit: &'a HitRecord,
+    attenuation: Color,
+    material_pdf: &'a dyn ProbabilityDensityFunction,
+}
+
+impl PathVertex<'_> {
+    /// Returns the light scattered back along `r_in` per unit of light arriving along
+    /// `scattered`, see
+    /// [`Material::scattering`](crate::material::Material::scattering).
+    fn scattering(&self, ctx: &RenderContext, scattered: &Ray) -> Color {
+        self.hit
+            .material
+            .scattering(ctx, self.r_in, self.hit, scattered, self.attenuation)
+    }
+}
+
 impl Camera {
-    /// Traces a ray through the scene and calculates its color.
+    /// Traces a path through the scene starting with `ray` and returns the radiance
+    /// it carries back to the camera.
     ///
-    /// This method recursively traces rays through the scene, accumulating color
-    /// from emissive materials and scattered light. It uses importance sampling
-    /// with a mixture of material and light PDFs for efficient rendering.
+    /// The path is extended one bounce at a time, keeping the product of the
+    /// attenuations and sampling weights along it, the throughput, and adding the
+    /// light emitted at each vertex weighted by the throughput. At diffuse and glossy
+    /// vertices the direct light is estimated twice: shadow rays towards points
+    /// sampled on the emissive objects or the background and towards one of the light
+    /// sources (next-event estimation), and the next bounce sampled from the material
+    /// hitting an emitter. The two estimates are combined with power heuristic
+    /// weights, so each covers the cases the other samples poorly, small lights for
+    /// the first and glossy reflections of large lights for the second. Delta lights
+    /// are only reached by shadow rays. After [`RUSSIAN_ROULETTE_MIN_BOUNCES`] bounces
+    /// paths are randomly terminated with a probability that grows as the throughput
+    /// drops, and the surviving paths are weighted up to compensate, so long paths add
+    /// no bias while dim ones end early. `max_depth` only bounds the length of the
+    /// brightest paths. The steps of a random walk inside a medium, like in a
+    /// [`Subsurface`](crate::material::Subsurface) material, are not bounces: the walk
+    /// counts once, from where it enters the object to where it leaves it.
     ///
     /// # Parameters
     /// - `ctx`: Rendering context containing random number generator
-    /// - `ray`: The ray to trace
-    /// - `depth`: Remaining recursion depth
+    /// - `sampler`: Sampler of the path, the same generator as `ctx.random`
+    /// - `ray`: The camera ray
     /// - `world`: The scene geometry to test for intersections
-    /// - `lights`: Light sources for importance sampling
+    /// - `lights`: Lights sampled directly
     ///
     /// # Returns
     /// The color seen along the ray direction.
-    #[allow(clippy::only_used_in_recursion)]
     fn ray_color(
         &self,
         ctx: &RenderContext,
-        ray: Ray,
-        depth: u32,
+        sampler: &dyn Sampler,
+        mut ray: Ray,
         world: &dyn Node,
-        lights: Option<Arc<dyn Node>>,
+        lights: &Lights,
     ) -> Color {
-        // Recursion limit reached
-        if depth == 0 {
-            return Color::BLACK;
-        }
+        let mut radiance = Color::BLACK;
+        let mut throughput = Color::WHITE;
+        // material PDF of the last bounce and the light PDF at its origin, to weight
+        // the light the bounce hits against next-event estimation
+        let mut last_bounce: Option<(f64, Option<Arc<dyn ProbabilityDensityFunction>>)> = None;
+        // hero wavelength of a spectral path, and whether the path depends on it yet
+        let wavelength = ray.wavelength;
+        let mut dispersed = false;
 
-        // If the ray hits nothing, return the background color.
-        let Some(hit) = world.hit(ctx, &ray, Interval::new(0.001, f64::INFINITY)) else {
-            return self.background;
-        };
+        let mut bounce = 0;
+        let mut walk_steps = 0;
+        while bounce < self.max_depth {
+            sampler.start_vertex(bounce);
 
-        let color_from_emission = hit.material.emitted(&ray, &hit, hit.u, hit.v, hit.pt);
+            let hit = world.hit(ctx, &ray, Interval::new(0.001, f64::INFINITY));
+            let emitted = match &hit {
+                Some(hit) => hit.material.emitted(&ray, hit, hit.u, hit.v, hit.pt),
+                None => self.background.value(&ray.direction),
+            };
+            let weight = match &last_bounce {
+                Some((material_pdf_value, Some(light_pdf))) => {
+                    power_heuristic(*material_pdf_value, light_pdf.value(ctx, &ray.direction))
+                }
+                _ => 1.0,
+            };
+            radiance += throughput * emitted * weight;
 
-        match hit.material.scatter(ctx, &ray, &hit) {
-            None => color_from_emission,
-            Some(scatter_results) => match scatter_results.pdf_or_ray {
+            let Some(hit) = hit else {
+                let material_pdf_value =
+                    last_bounce.map(|(material_pdf_value, _)| material_pdf_value);
+                radiance += throughput
+                    * self.light_source_radiance(lights, &ray.direction, material_pdf_value);
+                break;
+            };
+            let Some(scatter_results) = hit.material.scatter(ctx, &ray, &hit) else {
+                break;
+            };
+            if let Some(wavelength) = wavelength
+                && !dispersed
+                && hit.material.is_dispersive()
+            {
+                // until now the path didn't depend on the wavelength, its light
+                // averaged over all wavelengths is its RGB light
+                throughput = throughput * wavelength_weight(wavelength);
+                dispersed = true;
+            }
+            match scatter_results.pdf_or_ray {
                 // Specular reflection (delta distribution)
-                PdfOrRay::Ray(ray) => {
-                    scatter_results.attenuation * self.ray_color(ctx, ray, depth - 1, world, lights)
+                PdfOrRay::Ray(scattered) => {
+                    throughput = throughput * scatter_results.attenuation;
+                    last_bounce = None;
+                    ray = scattered;
+                    ray.wavelength = wavelength;
+                    if scatter_results.walk_step {
+                        walk_steps += 1;
+                        if walk_steps >= MAX_WALK_STEPS {
+                            break;
+                        }
+                        continue;
+                    }
                 }
                 // Diffuse/glossy reflection (use importance sampling)
                 PdfOrRay::Pdf(material_pdf) => {
-                    let pdf = match &lights {
-                        Some(lights) => {
-                            let light_pdf = Arc::new(HittablePdf::new(lights.clone(), hit.pt));
-                            Arc::new(MixturePdf::new(light_pdf, material_pdf))
-                        }
-                        None => material_pdf,
+                    let vertex = PathVertex {
+                        r_in: &ray,
+                        hit: &hit,
+                        attenuation: scatter_results.attenuation,
+                        material_pdf: &*material_pdf,
                     };
+                    let light_pdf = self.light_pdf(lights.geometry.as_ref(), hit.pt);
+                    if let Some(light_pdf) = &light_pdf {
+                        radiance +=
+                            throughput * self.sample_light(ctx, world, &vertex, &**light_pdf);
+                    }
+                    radiance += throughput * self.sample_light_source(ctx, world, &vertex, lights);
 
-                    let scattered = Ray::new_with_time(hit.pt, pdf.generate(ctx), ray.time);
-                    let pdf_value = pdf.value(ctx, &scattered.direction);
-
-                    // Guard against small or invalid PDF values which can cause over exposure
-                    if pdf_value < 0.05 {
-                        return color_from_emission;
+                    let scattered =
+                        Ray::new_with_time(hit.pt, material_pdf.generate(ctx), ray.time);
+                    let pdf_value = material_pdf.value(ctx, &scattered.direction);
+                    if pdf_value.is_nan() || pdf_value <= 0.0 {
+                        break;
                     }
 
-                    let scattering_pdf = hit.material.scattering_pdf(ctx, &ray, &hit, &scattered);
+                    let scattering = hit.material.scattering(
+                        ctx,
+                        &ray,
+                        &hit,
+                        &scattered,
+                        scatter_results.attenuation,
+                    );
+                    throughput = throughput * scattering / pdf_value;
+                    last_bounce = Some((pdf_value, light_pdf));
+                    ray = scattered;
+                    ray.wavelength = wavelength;
+                }
+            }
 
-                    let sample_color = self.ray_color(ctx, scattered, depth - 1, world, lights);
-                    let color_from_scatter =
-                        (scatter_results.attenuation * scattering_pdf * sample_color) / pdf_value;
+            // Russian roulette
+            if bounce + 1 >= RUSSIAN_ROULETTE_MIN_BOUNCES {
+                let survival = throughput
+                    .max_component()
+                    .min(RUSSIAN_ROULETTE_MAX_SURVIVAL);
+                if survival <= 0.0 || ctx.random.rand() >= survival {
+                    break;
+                }
+                throughput = throughput / survival;
+            }
+            bounce += 1;
+        }
 
-                    let color = color_from_emission + color_from_scatter;
+        radiance
+    }
 
-                    // Clamp to prevent fireflies
-                    color.clamp(0.0, 10.0)
-                }
-            },
+    /// Returns the PDF sampling directions from `origin` towards the lights and the
+    /// bright parts of the background, None if there is nothing to sample.
+    fn light_pdf(
+        &self,
+        lights: Option<&Arc<dyn Node>>,
+        origin: Vector3,
+    ) -> Option<Arc<dyn ProbabilityDensityFunction>> {
+        let background_pdf = self.background.importance_sampled().then(|| {
+            Arc::new(BackgroundPdf::new(self.background.clone()))
+                as Arc<dyn ProbabilityDensityFunction>
+        });
+        let hittable_pdf = lights.map(|lights| {
+            Arc::new(HittablePdf::new(lights.clone(), origin))
+                as Arc<dyn ProbabilityDensityFunction>
+        });
+        match (hittable_pdf, background_pdf) {
+            (Some(hittable_pdf), Some(background_pdf)) => {
+                Some(Arc::new(MixturePdf::new(hittable_pdf, background_pdf)))
+            }
+            (hittable_pdf, background_pdf) => hittable_pdf.or(background_pdf),
         }
     }
 
+    /// Next-event estimation: traces a shadow ray from `vertex` in a direction sampled
+    /// from `light_pdf` and returns the light arriving along it, times the material's
+    /// scattering and the power heuristic weight, over the light PDF.
+    fn sample_light(
+        &self,
+        ctx: &RenderContext,
+        world: &dyn Node,
+        vertex: &PathVertex,
+        light_pdf: &dyn ProbabilityDensityFunction,
+    ) -> Color {
+        let shadow_ray =
+            Ray::new_with_time(vertex.hit.pt, light_pdf.generate(ctx), vertex.r_in.time);
+        let light_pdf_value = light_pdf.value(ctx, &shadow_ray.direction);
+        if light_pdf_value.is_nan() || light_pdf_value <= 0.0 {
+            return Color::BLACK;
+        }
+        let scattering = vertex.scattering(ctx, &shadow_ray);
+        if scattering.max_component() <= 0.0 {
+            return Color::BLACK;
+        }
+
+        // whatever the shadow ray hits first is the light that arrives, an occluder
+        // emits nothing
+        let emitted = match world.hit(ctx, &shadow_ray, Interval::new(0.001, f64::INFINITY)) {
+            Some(light_hit) => light_hit.material.emitted(
+                &shadow_ray,
+                &light_hit,
+                light_hit.u,
+                light_hit.v,
+                light_hit.pt,
+            ),
+            None => self.background.value(&shadow_ray.direction),
+        };
+        let weight = power_heuristic(
+            light_pdf_value,
+            vertex.material_pdf.value(ctx, &shadow_ray.direction),
+        );
+        emitted * scattering * (weight / light_pdf_value)
+    }
+
+    /// Next-event estimation for the light sources: traces a shadow ray towards one
+    /// source picked at random and returns the light arriving along it, like
+    /// [`Camera::sample_light`]. Delta lights are only found this way and take the
+    /// full weight.
+    fn sample_light_source(
+        &self,
+        ctx: &RenderContext,
+        world: &dyn Node,
+        vertex: &PathVertex,
+        lights: &Lights,
+    ) -> Color {
+        if lights.sources.is_empty() {
+            return Color::BLACK;
+        }
+        let source_count = lights.sources.len();
+        let light = &lights.sources[ctx.random.rand_int_interval(0, source_count as i64) as usize];
+        let Some(sample) = light.sample(&*ctx.random, &vertex.hit.pt) else {
+            return Color::BLACK;
+        };
+        let shadow_ray = Ray::new_with_time(vertex.hit.pt, sample.direction, vertex.r_in.time);
+        let scattering = vertex.scattering(ctx, &shadow_ray);
+        if scattering.max_component() <= 0.0 {
+            return Color::BLACK;
+        }
+        // participating media let some of the light through
+        let transmittance = world.transmittance(
+            ctx,
+            &shadow_ray,
+            Interval::new(0.001, sample.distance - 0.001),
+        );
+        if transmittance <= 0.0 {
+            return Color::BLACK;
+        }
+
+        let pdf_value = sample.pdf / source_count as f64;
+        let weight = if light.is_delta() {
+            1.0
+        } else {
+            power_heuristic(
+                pdf_value,
+                vertex.material_pdf.value(ctx, &shadow_ray.direction),
+            )
+        };
+        sample.radiance * scattering * (transmittance * weight / pdf_value)
+    }
+
+    /// Returns the radiance of the light sources seen by a ray leaving the scene in
+    /// `direction`. `material_pdf_value` is the density the last bounce sampled the
+    /// direction with, None after a specular bounce or for camera rays, which
+    /// next-event estimation can't reach.
+    fn light_source_radiance(
+        &self,
+        lights: &Lights,
+        direction: &Vector3,
+        material_pdf_value: Option<f64>,
+    ) -> Color {
+        let source_count = lights.sources.len() as f64;
+        let mut radiance = Color::BLACK;
+        for light in lights.sources.iter().filter(|light| !light.is_delta()) {
+            let weight = match material_pdf_value {
+                Some(material_pdf_value) => power_heuristic(
+                    material_pdf_value,
+                    light.pdf_value(direction) / source_count,
+                ),
+                None => 1.0,
+            };
+            radiance += light.radiance(direction) * weight;
+        }
+        radiance
+    }
+
     /// Renders a single pixel at the given coordinates.
     ///
-    /// This method performs stratified sampling over the pixel area, tracing
-    /// multiple rays per pixel and averaging the results for anti-aliasing.
+    /// This is [`Camera::render_linear`] followed by [`Camera::display_transform`],
+    /// producing a color ready for display or 8-bit output.
     ///
     /// # Parameters
     /// - `ctx`: Rendering context containing random number generator
     /// - `x`: Pixel x-coordinate (0 to image_width - 1)
     /// - `y`: Pixel y-coordinate (0 to image_height - 1)
     /// - `world`: The scene geometry to render
-    /// - `lights`: Light sources for importance sampling
+    /// - `lights`: Lights sampled directly
     ///
     /// # Returns
-    /// The final gamma-corrected color for the pixel.
+    /// The final sRGB encoded color for the pixel.
     pub fn render(
         &self,
         ctx: &RenderContext,
         x: u32,
         y: u32,
         world: &dyn Node,
-        lights: Option<Arc<dyn Node>>,
+        lights: &Lights,
     ) -> Color {
+        self.display_transform(self.render_linear(ctx, x, y, world, lights))
+    }
+
+    /// Converts linear radiance into a displayable sRGB color.
+    ///
+    /// Applies the camera exposure, then the tone mapping operator and finally the
+    /// sRGB transfer function. The result is in the range [0.0, 1.0].
+    pub fn display_transform(&self, color: Color) -> Color {
+        self.tone_mapping
+            .apply(color * self.exposure_scale)
+            .linear_to_srgb()
+    }
+
+    /// Renders a single pixel at the given coordinates without any display transform.
+    ///
+    /// This method performs stratified sampling over the pixel area, tracing
+    /// multiple rays per pixel and averaging the results for anti-aliasing. The
+    /// result is linear radiance, which is not gamma corrected or clamped to the
+    /// displayable range, suitable for HDR output and compositing.
+    ///
+    /// With adaptive sampling enabled, sampling stops early once the pixel has
+    /// converged, see [`CameraBuilder::adaptive_threshold`].
+    ///
+    /// # Parameters
+    /// - `ctx`: Rendering context containing random number generator
+    /// - `x`: Pixel x-coordinate (0 to image_width - 1)
+    /// - `y`: Pixel y-coordinate (0 to image_height - 1)
+    /// - `world`: The scene geometry to render
+    /// - `lights`: Lights sampled directly
+    ///
+    /// # Returns
+    /// The linear color for the pixel.
+    pub fn render_linear(
+        &self,
+        ctx: &RenderContext,
+        x: u32,
+        y: u32,
+        world: &dyn Node,
+        lights: &Lights,
+    ) -> Color {
+        if self.adaptive_threshold > 0.0 {
+            return self.render_linear_adaptive(ctx, x, y, world, lights).0;
+        }
+
+        let mut pixel_color = Color::new(0.0, 0.0, 0.0);
+
+        // Stratified sampling: one sample in each cell of a sqrt_spp x sqrt_spp grid
+        for i in 0..self.sqrt_spp * self.sqrt_spp {
+            pixel_color += self.render_sample(ctx, x, y, i, world, lights);
+        }
+
+        self.pixel_samples_scale * pixel_color
+    }
+
+    /// Renders a single pixel, stopping once the standard error of the mean
+    /// luminance, converted to a gamma 2 display scale, is below the adaptive
+    /// threshold.
+    ///
+    /// The strata are visited in a scrambled order so the samples taken before
+    /// stopping are spread over the whole pixel. Convergence is checked after every
+    /// `sqrt_spp` samples once `min_samples_per_pixel` have been taken.
+    ///
+    /// # Returns
+    /// The linear color for the pixel and the number of samples taken.
+    fn render_linear_adaptive(
+        &self,
+        ctx: &RenderContext,
+        x: u32,
+        y: u32,
+        world: &dyn Node,
+        lights: &Lights,
+    ) -> (Color, u32) {
+        let strata_count = self.sqrt_spp * self.sqrt_spp;
+
         let mut pixel_color = Color::new(0.0, 0.0, 0.0);
+        // running mean and sum of squared differences of the luminance (Welford)
+        let mut mean = 0.0;
+        let mut m2 = 0.0;
+
+        for i in 0..strata_count {
+            let sample = self.render_sample(ctx, x, y, i, world, lights);
+            pixel_color += sample;
+
+            let n = i + 1;
+            let luminance = sample.luminance();
+            let delta = luminance - mean;
+            mean += delta / n as f64;
+            m2 += delta * (luminance - mean);
 
-        // Stratified sampling: divide pixel into sqrt_spp x sqrt_spp grid
-        for s_y in 0..self.sqrt_spp {
-            for s_x in 0..self.sqrt_spp {
-                let r = self.get_ray(ctx, x, y, s_x, s_y);
-                let sample = self.ray_color(ctx, r, self.max_depth, world, lights.clone());
-                pixel_color += sample;
+            if n >= self.min_samples_per_pixel.max(2) && n % self.sqrt_spp == 0 {
+                let variance = m2 / (n - 1) as f64;
+                let standard_error = (variance / n as f64).sqrt();
+                // d(sqrt(L)) = dL / (2 sqrt(L))
+                if standard_error <= 2.0 * self.adaptive_threshold * mean.sqrt() {
+                    return (pixel_color / n as f64, n);
+                }
             }
         }
 
-        let pixel_color = self.pixel_samples_scale * pixel_color.nan_to_zero();
-        pixel_color.linear_to_gamma()
+        (pixel_color * self.pixel_samples_scale, strata_count)
     }
 
-    /// Constructs a camera ray originating from the defocus disk and directed at a randomly
-    /// sampled point around the pixel location (x, y).
+    /// Traces a single sample of a pixel and returns its linear color.
+    ///
+    /// `sample_index` selects the stratum of the pixel the sample is taken from.
+    /// Consecutive indices are spread over the whole pixel and every
+    /// `samples_per_pixel` consecutive indices cover each stratum once, so samples
+    /// can be accumulated over several calls, for example by a
+    /// [`crate::ProgressiveRenderer`], and still be stratified.
+    ///
+    /// If the random number generator of `ctx` can be forked, see [`Random::fork`],
+    /// the sample uses its own generator derived from the pixel and `sample_index`,
+    /// which makes seeded renders independent of the order samples are taken in.
+    ///
+    /// With a low-discrepancy sampler `sample_index` selects the point of the
+    /// pixel's sequence instead of a stratum.
     ///
     /// # Parameters
     /// - `ctx`: Rendering context containing random number generator
+    /// - `x`: Pixel x-coordinate (0 to image_width - 1)
+    /// - `y`: Pixel y-coordinate (0 to image_height - 1)
+    /// - `sample_index`: Index of the sample within the pixel
+    /// - `world`: The scene geometry to render
+    /// - `lights`: Lights sampled directly
+    pub fn render_sample(
+        &self,
+        ctx: &RenderContext,
+        x: u32,
+        y: u32,
+        sample_index: u32,
+        world: &dyn Node,
+        lights: &Lights,
+    ) -> Color {
+        let random = ctx
+            .random
+            .fork(x, y, sample_index)
+            .unwrap_or_else(|| ctx.random.clone());
+        let sampler = self.sampler.start_sample(
+            random,
+            x,
+            y,
+            sample_index,
+            self.samples_per_pixel(),
+            sequence_seed(&*ctx.random),
+        );
+        let ctx = RenderContext {
+            random: sampler.clone(),
+        };
+
+        let offset = match self.sampler {
+            SamplerType::Random => {
+                let strata_count = self.sqrt_spp * self.sqrt_spp;
+                let stratum = ((sample_index as u64 * self.strata_stride as u64)
+                    % strata_count as u64) as u32;
+                let s_x = stratum % self.sqrt_spp;
+                let s_y = stratum / self.sqrt_spp;
+                self.sample_square_stratified(&*sampler, s_x, s_y)
+            }
+            _ => {
+                let (u, v) = sampler.get_2d();
+                Vector3::new(u - 0.5, v - 0.5, 0.0)
+            }
+        };
+
+        let r = self.get_ray(&*sampler, x, y, offset);
+        self.ray_color(&ctx, &*sampler, r, world, lights)
+            .nan_to_zero()
+    }
+
+    /// Constructs a camera ray originating from the defocus disk and directed at a
+    /// point around the pixel location (x, y), carrying a wavelength in spectral mode.
+    ///
+    /// # Parameters
+    /// - `sampler`: Sampler of the path
     /// - `x`: Pixel x-coordinate
     /// - `y`: Pixel y-coordinate
-    /// - `s_x`: Stratification grid x-index
-    /// - `s_y`: Stratification grid y-index
+    /// - `offset`: Position in the pixel, relative to its center
     ///
     /// # Returns
     /// A ray from the camera through the specified pixel sample.
-    fn get_ray(&self, ctx: &RenderContext, x: u32, y: u32, s_x: u32, s_y: u32) -> Ray {
-        let offset = self.sample_square_stratified(&*ctx.random, s_x, s_y);
+    fn get_ray(&self, sampler: &dyn Sampler, x: u32, y: u32, offset: Vector3) -> Ray {
         let pixel_sample = self.pixel00_loc
             + ((x as f64 + offset.x) * self.pixel_delta_u)
             + ((y as f64 + offset.y) * self.pixel_delta_v);
@@ -365,12 +855,17 @@ impl Camera {
         let ray_origin = if self.defocus_angle <= 0.0 {
             self.center
         } else {
-            self.defocus_disk_sample(&*ctx.random)
+            self.defocus_disk_sample(sampler)
         };
         let ray_direction = pixel_sample - ray_origin;
-        let ray_time = ctx.random.rand();
+        let ray_time =
+            self.shutter_open + (self.shutter_close - self.shutter_open) * sampler.get_1d();
 
-        Ray::new_with_time(ray_origin, ray_direction, ray_time)
+        let mut ray = Ray::new_with_time(ray_origin, ray_direction, ray_time);
+        if self.spectral {
+            ray.wavelength = Some(sample_visible_wavelength(sampler.get_1d()));
+        }
+        ray
     }
 
     /// Returns the vector to a random point in the square sub-pixel specified by grid
@@ -403,6 +898,17 @@ impl Camera {
         self.image_height
     }
 
+    /// Returns the number of samples traced for each pixel by [`Camera::render`],
+    /// the largest square not above the configured samples per pixel.
+    pub fn samples_per_pixel(&self) -> u32 {
+        self.sqrt_spp * self.sqrt_spp
+    }
+
+    /// Returns true if paths carry a wavelength, see [`CameraBuilder::spectral`].
+    pub fn is_spectral(&self) -> bool {
+        self.spectral
+    }
+
     /// Returns a random point in the camera defocus disk.
     ///
     /// This is used to create depth of field effects by varying the ray origin
@@ -418,3 +924,315 @@ impl Camera {
         self.center + (pt.x * self.defocus_disk_u) + (pt.y * self.defocus_disk_v)
     }
 }
+
+/// Returns the seed scrambling the low-discrepancy sequences, which is the same for
+/// every sample of a render. Seeded renders, whose generator can be forked, get a
+/// different scrambling for every seed.
+fn sequence_seed(random: &dyn Random) -> u32 {
+    random.fork(0, 0, u32::MAX).map_or(0, |random| {
+        random.rand_int_interval(0, u32::MAX as i64) as u32
+    })
+}
+
+/// Returns a step close to `n / φ` that is coprime with `n`, so that `i * step % n`
+/// visits every index in `0..n` once while spreading consecutive indices apart.
+fn coprime_stride(n: u32) -> u32 {
+    fn gcd(a: u32, b: u32) -> u32 {
+        if b == 0 { a } else { gcd(b, a % b) }
+    }
+
+    let mut stride = ((n as f64 * 0.618_033_988_75).round() as u32).max(1);
+    while gcd(stride, n) != 1 {
+        stride += 1;
+    }
+    stride
+}
+
+#[cfg(test)]
+mod test {
+    use std::{any::Any, f64::consts::PI, sync::Arc};
+
+    use crate::{
+        Color, Ray, RenderContext, SeededRandom, Vector3,
+        background::{GradientBackground, SolidBackground},
+        light::{Lights, PointLight, SunLight},
+        material::{
+            Dielectric, DiffuseLight, Lambertian, Material, PdfOrRay, RefractiveIndex,
+            ScatterResult, Subsurface,
+        },
+        object::{Group, HitRecord, Node, Quad, Sphere},
+        random_new,
+    };
+
+    use super::{CameraBuilder, coprime_stride};
+
+    /// A glowing diffuse surface that samples its bounces itself, so the result
+    /// doesn't depend on the light sampling.
+    #[derive(Debug)]
+    struct GlowingDiffuse {
+        albedo: Color,
+        emission: Color,
+    }
+
+    impl Material for GlowingDiffuse {
+        fn scatter(
+            &self,
+            ctx: &RenderContext,
+            _r_in: &Ray,
+            hit: &HitRecord,
+        ) -> Option<ScatterResult> {
+            let direction = hit.normal + Vector3::random_unit(&*ctx.random);
+            Some(ScatterResult {
+                attenuation: self.albedo,
+                pdf_or_ray: PdfOrRay::Ray(Ray::new(hit.pt, direction)),
+                walk_step: false,
+            })
+        }
+
+        fn emitted(&self, _r_in: &Ray, _hit: &HitRecord, _u: f64, _v: f64, _pt: Vector3) -> Color {
+            self.emission
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn coprime_stride_visits_every_stratum() {
+        for n in [1, 2, 9, 16, 100, 1024] {
+            let stride = coprime_stride(n);
+            let mut visited: Vec<u32> = (0..n).map(|i| (i * stride) % n).collect();
+            visited.sort();
+            assert_eq!(visited, (0..n).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn adaptive_sampling_stops_on_converged_pixels() {
+        let ctx = RenderContext {
+            random: random_new(),
+        };
+        let mut camera_builder = CameraBuilder::new();
+        camera_builder.samples_per_pixel = 256;
+        camera_builder.adaptive_threshold = 0.01;
+        camera_builder.background = Arc::new(SolidBackground::new(Color::new(0.5, 0.5, 0.5)));
+        let camera = camera_builder.build();
+
+        let (color, samples) =
+            camera.render_linear_adaptive(&ctx, 3, 4, &Group::new(), &Lights::default());
+        assert_eq!(color, Color::new(0.5, 0.5, 0.5));
+        assert_eq!(samples, 16);
+    }
+
+    #[test]
+    fn seeded_render_is_reproducible() {
+        let mut camera_builder = CameraBuilder::new();
+        camera_builder.image_width = 4;
+        camera_builder.samples_per_pixel = 4;
+        camera_builder.defocus_angle = 10.0;
+        camera_builder.background = Arc::new(GradientBackground::new(Color::BLACK, Color::WHITE));
+        let camera = camera_builder.build();
+        let world = Group::new();
+
+        let render = |seed: u64, reverse: bool| {
+            let ctx = RenderContext {
+                random: Arc::new(SeededRandom::new(seed)),
+            };
+            let mut pixels: Vec<(u32, u32)> =
+                (0..4).flat_map(|y| (0..4).map(move |x| (x, y))).collect();
+            if reverse {
+                pixels.reverse();
+            }
+            let mut colors: Vec<_> = pixels
+                .into_iter()
+                .map(|(x, y)| {
+                    (
+                        (x, y),
+                        camera.render_linear(&ctx, x, y, &world, &Lights::default()),
+                    )
+                })
+                .collect();
+            colors.sort_by_key(|(pixel, _)| *pixel);
+            colors
+                .into_iter()
+                .map(|(_, c)| [c.r.to_bits(), c.g.to_bits(), c.b.to_bits()])
+                .collect::<Vec<_>>()
+        };
+
+        // the render order doesn't matter, only the seed does
+        assert_eq!(render(42, false), render(42, true));
+        assert_ne!(render(42, false), render(43, false));
+    }
+
+    #[test]
+    fn russian_roulette_is_unbiased() {
+        // inside a closed sphere every bounce adds the emission and keeps the albedo,
+        // so the radiance is the geometric series emission / (1 - albedo)
+        let albedo = 0.8;
+        let mut world = Group::new();
+        world.push(Arc::new(Sphere::new(
+            Vector3::ZERO,
+            10.0,
+            Arc::new(GlowingDiffuse {
+                albedo: Color::WHITE * albedo,
+                emission: Color::WHITE,
+            }),
+        )));
+
+        let mut camera_builder = CameraBuilder::new();
+        camera_builder.image_width = 1;
+        camera_builder.samples_per_pixel = 40_000;
+        camera_builder.max_depth = 1000;
+        let camera = camera_builder.build();
+
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let color = camera.render_linear(&ctx, 0, 0, &world, &Lights::default());
+        let expected = 1.0 / (1.0 - albedo);
+        assert!((color.r - expected).abs() < 0.05, "{color:?}");
+    }
+
+    #[test]
+    fn spectral_glass_keeps_white_light_white() {
+        // clear glass in front of a white background lets all the light through,
+        // dispersion only moves the colors around
+        let mut world = Group::new();
+        world.push(Arc::new(Sphere::new(
+            Vector3::new(0.0, 0.0, -3.0),
+            1.0,
+            Arc::new(Dielectric::new_from_index(RefractiveIndex::SF11)),
+        )));
+
+        let mut camera_builder = CameraBuilder::new();
+        camera_builder.image_width = 1;
+        camera_builder.samples_per_pixel = 20_000;
+        camera_builder.max_depth = 100;
+        camera_builder.background = Arc::new(SolidBackground::new(Color::WHITE));
+        camera_builder.spectral = true;
+        let camera = camera_builder.build();
+
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let color = camera.render_linear(&ctx, 0, 0, &world, &Lights::default());
+        for channel in [color.r, color.g, color.b] {
+            assert!((channel - 1.0).abs() < 0.03, "{color:?}");
+        }
+    }
+
+    #[test]
+    fn subsurface_walks_are_not_cut_at_max_depth() {
+        // a medium that scatters without absorbing, under a white background, leaves
+        // all the light going in come out, however many steps the walks take
+        let mut world = Group::new();
+        world.push(Arc::new(Sphere::new(
+            Vector3::new(0.0, 0.0, -3.0),
+            1.0,
+            Arc::new(
+                Subsurface::new(Color::new(1.0, 5.0, 20.0), Color::BLACK).with_anisotropy(0.8),
+            ),
+        )));
+
+        let mut camera_builder = CameraBuilder::new();
+        camera_builder.image_width = 1;
+        camera_builder.vertical_fov = 0.01;
+        camera_builder.samples_per_pixel = 4000;
+        camera_builder.background = Arc::new(SolidBackground::new(Color::WHITE));
+        let camera = camera_builder.build();
+        assert_eq!(camera.max_depth, 10);
+
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let color = camera.render_linear(&ctx, 0, 0, &world, &Lights::default());
+        for channel in [color.r, color.g, color.b] {
+            assert!((channel - 1.0).abs() < 0.03, "{color:?}");
+        }
+    }
+
+    #[test]
+    fn small_light_converges_to_direct_lighting() {
+        // a 0.1 x 0.1 light, 1 above the floor
+        let albedo = 0.5;
+        let emission = 100.0;
+        let (mut camera_builder, mut world) = floor_scene(albedo);
+        camera_builder.samples_per_pixel = 4096;
+        let camera = camera_builder.build();
+        let light: Arc<dyn Node> = Arc::new(Quad::new(
+            Vector3::new(-0.05, 1.0, -0.05),
+            Vector3::new(0.1, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 0.1),
+            Arc::new(DiffuseLight::new_from_color(Color::WHITE * emission)),
+        ));
+        world.push(light.clone());
+
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let color = camera.render_linear(&ctx, 0, 0, &world, &Lights::from_geometry(light));
+        // the irradiance integral of cos² / r² over the light, to second order in its size
+        let irradiance = emission * 0.01 * (1.0 - 4.0 * 0.05 * 0.05 / 3.0);
+        let expected = albedo / PI * irradiance;
+        assert!(
+            (color.r - expected).abs() < expected * 0.02,
+            "{color:?} {expected}"
+        );
+    }
+
+    /// A diffuse floor at y = 0 and a camera looking at the origin from the side,
+    /// seeing only a tiny area of the floor.
+    fn floor_scene(albedo: f64) -> (CameraBuilder, Group) {
+        let mut world = Group::new();
+        world.push(Arc::new(Quad::new(
+            Vector3::new(-10.0, 0.0, 10.0),
+            Vector3::new(20.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, -20.0),
+            Arc::new(Lambertian::new_from_color(Color::WHITE * albedo)),
+        )));
+
+        let mut camera_builder = CameraBuilder::new();
+        camera_builder.image_width = 1;
+        camera_builder.aspect_ratio = 1.0;
+        camera_builder.vertical_fov = 0.01;
+        camera_builder.look_from = Vector3::new(0.0, 0.5, 2.0);
+        camera_builder.look_at = Vector3::ZERO;
+        camera_builder.samples_per_pixel = 256;
+        (camera_builder, world)
+    }
+
+    #[test]
+    fn light_sources_light_the_floor() {
+        let albedo = 0.5;
+        let (camera_builder, world) = floor_scene(albedo);
+        let camera = camera_builder.build();
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let expected = albedo / PI;
+
+        // 4 above the floor, so the light falling on it is 1
+        let mut lights = Lights::default();
+        lights.sources.push(Arc::new(PointLight::new(
+            Vector3::new(0.0, 4.0, 0.0),
+            Color::WHITE * 16.0,
+        )));
+        let color = camera.render_linear(&ctx, 0, 0, &world, &lights);
+        assert!((color.r - expected).abs() < 1e-3, "{color:?} {expected}");
+
+        // a soft sun straight above, found by both shadow rays and bounces
+        let mut lights = Lights::default();
+        lights.sources.push(Arc::new(SunLight::new(
+            Vector3::new(0.0, 1.0, 0.0),
+            Color::WHITE,
+            10.0,
+        )));
+        let color = camera.render_linear(&ctx, 0, 0, &world, &lights);
+        let expected = expected * 5.0_f64.to_radians().cos().midpoint(1.0);
+        assert!(
+            (color.r - expected).abs() < expected * 0.01,
+            "{color:?} {expected}"
+        );
+    }
+}
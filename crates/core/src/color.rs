@@ -133,6 +133,93 @@ impl Color {
         }
     }
 
+    /// Converts linear color space to sRGB encoded color space and clamps to [0.0, 1.0].
+    ///
+    /// Applies the piecewise sRGB transfer function (a linear segment near black
+    /// followed by a 1/2.4 power curve), matching how displays decode 8-bit images.
+    /// Use [`crate::ToneMapping`] first to bring bright values into range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::Color;
+    /// use assert_eq_float::assert_eq_float;
+    ///
+    /// let srgb = Color::new(0.0, 0.214, 2.0).linear_to_srgb();
+    /// assert_eq_float!(srgb.r, 0.0);
+    /// assert!((srgb.g - 0.5).abs() < 0.001);
+    /// assert_eq_float!(srgb.b, 1.0);
+    /// ```
+    pub fn linear_to_srgb(&self) -> Self {
+        Self {
+            r: linear_to_srgb(self.r),
+            g: linear_to_srgb(self.g),
+            b: linear_to_srgb(self.b),
+        }
+    }
+
+    /// Converts CIE 1931 XYZ tristimulus values to a linear sRGB color, which may
+    /// have negative components for colors outside the sRGB gamut.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::Color;
+    ///
+    /// // the D65 white point
+    /// let white = Color::from_cie_xyz(0.9505, 1.0, 1.089);
+    /// assert!((white.r - 1.0).abs() < 1e-3 && (white.g - 1.0).abs() < 1e-3);
+    /// assert!((white.b - 1.0).abs() < 1e-3);
+    /// ```
+    pub fn from_cie_xyz(x: f64, y: f64, z: f64) -> Self {
+        Color::new(
+            3.2406 * x - 1.5372 * y - 0.4986 * z,
+            -0.9689 * x + 1.8758 * y + 0.0415 * z,
+            0.0557 * x - 0.2040 * y + 1.0570 * z,
+        )
+    }
+
+    /// Returns the relative luminance of a linear color using the Rec. 709 weights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::Color;
+    /// use assert_eq_float::assert_eq_float;
+    ///
+    /// assert_eq_float!(Color::WHITE.luminance(), 1.0);
+    /// assert!(Color::new(0.0, 1.0, 0.0).luminance() > Color::new(1.0, 0.0, 1.0).luminance());
+    /// ```
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    /// Returns the largest of the red, green and blue components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::Color;
+    ///
+    /// assert_eq!(Color::new(0.2, 0.7, 0.5).max_component(), 0.7);
+    /// ```
+    pub fn max_component(&self) -> f64 {
+        self.r.max(self.g).max(self.b)
+    }
+
+    /// Returns the average of the red, green and blue components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::Color;
+    ///
+    /// assert_eq!(Color::new(0.2, 0.7, 0.6).average(), 0.5);
+    /// ```
+    pub fn average(&self) -> f64 {
+        (self.r + self.g + self.b) / 3.0
+    }
+
     /// Replaces any NaN (Not a Number) components with 0.0.
     ///
     /// This is useful for handling edge cases in rendering calculations where
@@ -176,6 +263,18 @@ fn linear_to_gamma(v: f64) -> f64 {
     if v > 0.0 { v.sqrt() } else { 0.0 }
 }
 
+/// Converts a linear color component to the sRGB transfer curve.
+///
+/// Values are clamped to [0.0, 1.0] before encoding.
+fn linear_to_srgb(v: f64) -> f64 {
+    let v = v.clamp(0.0, 1.0);
+    if v <= 0.0031308 {
+        12.92 * v
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
 // Operator Implementations
 
 /// Multiplies each color component by a scalar value.
@@ -59,6 +59,18 @@ pub mod image_crate {
             if !self.image.in_bounds(x, y) {
                 return None;
             }
+            // keep the full range of HDR formats such as .hdr and .exr
+            match &self.image {
+                DynamicImage::ImageRgb32F(image) => {
+                    let p = image.get_pixel(x, y);
+                    return Some(Color::new(p.0[0] as f64, p.0[1] as f64, p.0[2] as f64));
+                }
+                DynamicImage::ImageRgba32F(image) => {
+                    let p = image.get_pixel(x, y);
+                    return Some(Color::new(p.0[0] as f64, p.0[1] as f64, p.0[2] as f64));
+                }
+                _ => {}
+            }
             let p = self.image.get_pixel(x, y).to_rgb();
             let r = p.0[0] as f64 / 255.0;
             let g = p.0[1] as f64 / 255.0;
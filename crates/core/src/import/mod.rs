@@ -0,0 +1,116 @@
+//! Importers that turn model files into scene nodes, and voxel files into density
+//! grids.
+//!
+//! Importers never touch the file system directly. Every file they need, including
+//! files referenced from other files such as OBJ material libraries and textures, is
+//! requested through an [`ImportSource`] so the same code works natively and in the
+//! browser.
+
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{Image, Matrix3x3, image::ImageError, material::Material};
+
+pub mod obj;
+pub mod off;
+pub mod stl;
+pub mod vol;
+
+pub use obj::load_obj;
+pub use off::load_off;
+pub use stl::load_stl;
+pub use vol::load_vol;
+
+/// Options controlling how an imported model is converted into scene nodes.
+#[derive(Debug)]
+pub struct ImportOptions {
+    /// Material used for surfaces the model file does not assign a material to
+    pub default_material: Arc<dyn Material>,
+    /// Linear transform applied to every position and normal, for example to
+    /// convert between coordinate systems. Normals are transformed by the same
+    /// matrix so it should be a rotation, possibly combined with a uniform scale.
+    pub transform: Matrix3x3,
+}
+
+impl ImportOptions {
+    pub fn new(default_material: Arc<dyn Material>) -> Self {
+        Self {
+            default_material,
+            transform: Matrix3x3::IDENTITY,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(String),
+    Parse(String),
+    Image(ImageError),
+}
+
+/// Provides the files needed by an importer.
+///
+/// Filenames are passed exactly as they should be resolved by the implementation,
+/// references inside a model file have already been made relative to the file that
+/// contains them (see [`resolve_relative_filename`]).
+pub trait ImportSource {
+    /// Returns the raw contents of a file.
+    fn get_bytes(&self, filename: &str) -> Result<Vec<u8>, ImportError>;
+
+    /// Loads and decodes an image file.
+    fn get_image(&self, filename: &str) -> Result<Arc<dyn Image>, ImageError>;
+}
+
+/// Resolves `filename` relative to the directory containing `base`.
+///
+/// Absolute filenames are returned unchanged. Backslashes, as written by some
+/// Windows exporters, are treated as path separators.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::import::resolve_relative_filename;
+///
+/// assert_eq!(resolve_relative_filename("models/car.obj", "car.mtl"), "models/car.mtl");
+/// assert_eq!(resolve_relative_filename("car.obj", "tex\\paint.png"), "tex/paint.png");
+/// ```
+pub fn resolve_relative_filename(base: &str, filename: &str) -> String {
+    let filename = filename.replace('\\', "/");
+    if filename.starts_with('/') {
+        return filename;
+    }
+    match base.rfind(['/', '\\']) {
+        Some(idx) => format!("{}/{filename}", &base[..idx]),
+        None => filename,
+    }
+}
+
+fn read_text(source: &dyn ImportSource, filename: &str) -> Result<String, ImportError> {
+    let bytes = source.get_bytes(filename)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn parse_error(filename: &str, line: usize, message: impl AsRef<str>) -> ImportError {
+    ImportError::Parse(format!("{filename}:{line}: {}", message.as_ref()))
+}
+
+fn parse_numbers(
+    args: &[&str],
+    min_count: usize,
+    filename: &str,
+    line_number: usize,
+) -> Result<Vec<f64>, ImportError> {
+    if args.len() < min_count {
+        return Err(parse_error(
+            filename,
+            line_number,
+            format!("expected at least {min_count} values"),
+        ));
+    }
+    args.iter()
+        .map(|arg| {
+            arg.parse::<f64>().map_err(|_| {
+                parse_error(filename, line_number, format!("invalid number \"{arg}\""))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,593 @@
+//! Wavefront OBJ and MTL import.
+//!
+//! Supports the geometry subset written by common modelling tools: positions
+//! (`v`), texture coordinates (`vt`), normals (`vn`) and polygonal faces (`f`) with
+//! positive or negative (relative) indices. Polygons are triangulated as fans.
+//! Objects, groups and smoothing groups are ignored; faces are grouped by the
+//! material assigned with `usemtl` and each group becomes one [`TriangleMesh`]
+//! sharing a single set of vertex buffers.
+//!
+//! MTL materials are mapped onto the closest built-in material:
+//!
+//! | MTL                                                   | Material                       |
+//! |-------------------------------------------------------|--------------------------------|
+//! | non-black `Ke`                                        | [`DiffuseLight`]               |
+//! | `d` < 1, `Tr` > 0 or `illum` 4, 6, 7 or 9             | [`Dielectric`] using `Ni`      |
+//! | `Pr` or `Pm` (PBR extension)                          | [`MetallicRoughness`] using `map_Kd` or `Kd` |
+//! | `illum` 3, or black `Kd` and non-black `Ks`           | [`Metal`] using `Ks` and `Ns`  |
+//! | anything else                                         | [`Lambertian`] using `map_Kd` or `Kd` |
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    Color, Node, Vector3,
+    import::{
+        ImportError, ImportOptions, ImportSource, parse_error, parse_numbers, read_text,
+        resolve_relative_filename,
+    },
+    material::{Dielectric, DiffuseLight, Lambertian, Material, Metal, MetallicRoughness},
+    object::{MeshData, MeshFace, TriangleMesh},
+    texture::{ImageTexture, SolidColor, Texture},
+};
+
+/// Refraction index used for transparent materials that do not specify `Ni`.
+const DEFAULT_REFRACTION_INDEX: f64 = 1.5;
+
+/// Loads an OBJ file, along with its material libraries and textures, from `source`.
+///
+/// Returns one [`TriangleMesh`] per material used by the file.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use caustic_core::{
+///     Color, Image,
+///     image::ImageError,
+///     import::{ImportError, ImportOptions, ImportSource, load_obj},
+///     material::Lambertian,
+/// };
+///
+/// struct Triangle;
+///
+/// impl ImportSource for Triangle {
+///     fn get_bytes(&self, _filename: &str) -> Result<Vec<u8>, ImportError> {
+///         Ok(b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".to_vec())
+///     }
+///
+///     fn get_image(&self, filename: &str) -> Result<Arc<dyn Image>, ImageError> {
+///         Err(ImageError::Other(format!("no image {filename}")))
+///     }
+/// }
+///
+/// let options = ImportOptions::new(Arc::new(Lambertian::new_from_color(Color::WHITE)));
+/// let nodes = load_obj(&Triangle, "triangle.obj", &options).unwrap();
+/// assert_eq!(nodes.len(), 1);
+/// ```
+pub fn load_obj(
+    source: &dyn ImportSource,
+    filename: &str,
+    options: &ImportOptions,
+) -> Result<Vec<Arc<dyn Node>>, ImportError> {
+    let obj = parse_obj(&read_text(source, filename)?, filename)?;
+
+    let mut mtl_materials = HashMap::new();
+    for library in &obj.material_libraries {
+        let library_filename = resolve_relative_filename(filename, library);
+        let text = read_text(source, &library_filename)?;
+        parse_mtl(&text, &library_filename, &mut mtl_materials)?;
+    }
+
+    let data = Arc::new(MeshData {
+        positions: obj
+            .positions
+            .into_iter()
+            .map(|p| &options.transform * p)
+            .collect(),
+        normals: obj
+            .normals
+            .into_iter()
+            .map(|n| (&options.transform * n).unit())
+            .collect(),
+        uvs: obj.uvs,
+    });
+
+    let mut materials: HashMap<String, Arc<dyn Material>> = HashMap::new();
+    let mut nodes: Vec<Arc<dyn Node>> = vec![];
+    for group in obj.groups {
+        if group.faces.is_empty() {
+            continue;
+        }
+
+        let material = match group.material.and_then(|name| mtl_materials.get(&name)) {
+            Some(mtl) => match materials.get(&mtl.name) {
+                Some(material) => material.clone(),
+                None => {
+                    let material = mtl.to_material(source)?;
+                    materials.insert(mtl.name.clone(), material.clone());
+                    material
+                }
+            },
+            None => options.default_material.clone(),
+        };
+
+        nodes.push(Arc::new(TriangleMesh::new(
+            data.clone(),
+            group.faces,
+            material,
+        )));
+    }
+
+    Ok(nodes)
+}
+
+/// Faces sharing the same `usemtl` material.
+#[derive(Debug)]
+struct ObjGroup {
+    material: Option<String>,
+    faces: Vec<MeshFace>,
+}
+
+#[derive(Debug, Default)]
+struct ObjFile {
+    positions: Vec<Vector3>,
+    normals: Vec<Vector3>,
+    uvs: Vec<(f64, f64)>,
+    groups: Vec<ObjGroup>,
+    material_libraries: Vec<String>,
+}
+
+fn parse_obj(text: &str, filename: &str) -> Result<ObjFile, ImportError> {
+    let mut obj = ObjFile {
+        groups: vec![ObjGroup {
+            material: None,
+            faces: vec![],
+        }],
+        ..Default::default()
+    };
+    let mut current_group = 0;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => {
+                let v = parse_numbers(&args, 3, filename, line_number)?;
+                obj.positions.push(Vector3::new(v[0], v[1], v[2]));
+            }
+            "vn" => {
+                let v = parse_numbers(&args, 3, filename, line_number)?;
+                obj.normals.push(Vector3::new(v[0], v[1], v[2]));
+            }
+            "vt" => {
+                let v = parse_numbers(&args, 1, filename, line_number)?;
+                obj.uvs.push((v[0], v.get(1).copied().unwrap_or(0.0)));
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(parse_error(
+                        filename,
+                        line_number,
+                        "face requires at least 3 vertices",
+                    ));
+                }
+                let vertices = args
+                    .iter()
+                    .map(|arg| parse_face_vertex(arg, &obj, filename, line_number))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let has_uvs = vertices.iter().all(|v| v.uv.is_some());
+                let has_normals = vertices.iter().all(|v| v.normal.is_some());
+                for i in 1..vertices.len() - 1 {
+                    let corners = [&vertices[0], &vertices[i], &vertices[i + 1]];
+                    obj.groups[current_group].faces.push(MeshFace {
+                        positions: corners.map(|v| v.position),
+                        normals: has_normals.then(|| corners.map(|v| v.normal.unwrap_or(0))),
+                        uvs: has_uvs.then(|| corners.map(|v| v.uv.unwrap_or(0))),
+                    });
+                }
+            }
+            "usemtl" => {
+                let name = args.join(" ");
+                current_group = match obj
+                    .groups
+                    .iter()
+                    .position(|g| g.material.as_deref() == Some(name.as_str()))
+                {
+                    Some(idx) => idx,
+                    None => {
+                        obj.groups.push(ObjGroup {
+                            material: Some(name),
+                            faces: vec![],
+                        });
+                        obj.groups.len() - 1
+                    }
+                };
+            }
+            "mtllib" => {
+                if args.is_empty() {
+                    return Err(parse_error(
+                        filename,
+                        line_number,
+                        "mtllib requires a filename",
+                    ));
+                }
+                obj.material_libraries.push(args.join(" "));
+            }
+            // objects, groups, smoothing groups and free-form geometry are ignored
+            _ => {}
+        }
+    }
+
+    Ok(obj)
+}
+
+#[derive(Debug)]
+struct ObjFaceVertex {
+    position: usize,
+    uv: Option<usize>,
+    normal: Option<usize>,
+}
+
+fn parse_face_vertex(
+    arg: &str,
+    obj: &ObjFile,
+    filename: &str,
+    line_number: usize,
+) -> Result<ObjFaceVertex, ImportError> {
+    let mut parts = arg.split('/');
+    let mut next_index = |count: usize| -> Result<Option<usize>, ImportError> {
+        match parts.next() {
+            None | Some("") => Ok(None),
+            Some(part) => resolve_index(part, count, filename, line_number).map(Some),
+        }
+    };
+
+    let position = next_index(obj.positions.len())?.ok_or_else(|| {
+        parse_error(
+            filename,
+            line_number,
+            format!("invalid face vertex \"{arg}\""),
+        )
+    })?;
+    let uv = next_index(obj.uvs.len())?;
+    let normal = next_index(obj.normals.len())?;
+    Ok(ObjFaceVertex {
+        position,
+        uv,
+        normal,
+    })
+}
+
+/// Converts a one based, possibly negative (relative to the end), OBJ index into a
+/// zero based index.
+fn resolve_index(
+    part: &str,
+    count: usize,
+    filename: &str,
+    line_number: usize,
+) -> Result<usize, ImportError> {
+    let index: i64 = part
+        .parse()
+        .map_err(|_| parse_error(filename, line_number, format!("invalid index \"{part}\"")))?;
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        count as i64 + index
+    };
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(parse_error(
+            filename,
+            line_number,
+            format!("index {index} out of range"),
+        ));
+    }
+    Ok(resolved as usize)
+}
+
+/// Material properties read from an MTL file.
+#[derive(Debug)]
+struct MtlMaterial {
+    name: String,
+    filename: String,
+    diffuse: Color,
+    specular: Color,
+    emission: Color,
+    specular_exponent: f64,
+    refraction_index: Option<f64>,
+    dissolve: f64,
+    illumination_model: u32,
+    diffuse_map: Option<String>,
+    roughness: Option<f64>,
+    metallic: Option<f64>,
+}
+
+impl MtlMaterial {
+    fn new(name: String, filename: &str) -> Self {
+        Self {
+            name,
+            filename: filename.to_owned(),
+            diffuse: Color::new(0.8, 0.8, 0.8),
+            specular: Color::BLACK,
+            emission: Color::BLACK,
+            specular_exponent: 0.0,
+            refraction_index: None,
+            dissolve: 1.0,
+            illumination_model: 2,
+            diffuse_map: None,
+            roughness: None,
+            metallic: None,
+        }
+    }
+
+    fn to_material(&self, source: &dyn ImportSource) -> Result<Arc<dyn Material>, ImportError> {
+        if !is_black(self.emission) {
+            return Ok(Arc::new(DiffuseLight::new_from_color(self.emission)));
+        }
+
+        if self.dissolve < 1.0 || matches!(self.illumination_model, 4 | 6 | 7 | 9) {
+            let refraction_index = self
+                .refraction_index
+                .filter(|n| *n > 1.0)
+                .unwrap_or(DEFAULT_REFRACTION_INDEX);
+            return Ok(Arc::new(Dielectric::new(refraction_index)));
+        }
+
+        if self.roughness.is_some() || self.metallic.is_some() {
+            return Ok(Arc::new(MetallicRoughness::new(
+                self.diffuse_texture(source)?,
+                Arc::new(SolidColor::new(Color::WHITE * self.metallic.unwrap_or(0.0))),
+                Arc::new(SolidColor::new(
+                    Color::WHITE * self.roughness.unwrap_or(1.0),
+                )),
+            )));
+        }
+
+        if self.illumination_model == 3
+            || (is_black(self.diffuse) && self.diffuse_map.is_none() && !is_black(self.specular))
+        {
+            // common approximation of roughness from a Phong specular exponent
+            let fuzz = (2.0 / (self.specular_exponent + 2.0))
+                .sqrt()
+                .clamp(0.0, 1.0);
+            return Ok(Arc::new(Metal::new(self.specular, fuzz)));
+        }
+
+        Ok(Arc::new(Lambertian::new(self.diffuse_texture(source)?)))
+    }
+
+    /// Returns the `map_Kd` image, or `Kd` if there is none.
+    fn diffuse_texture(&self, source: &dyn ImportSource) -> Result<Arc<dyn Texture>, ImportError> {
+        let Some(diffuse_map) = &self.diffuse_map else {
+            return Ok(Arc::new(SolidColor::new(self.diffuse)));
+        };
+        let image_filename = resolve_relative_filename(&self.filename, diffuse_map);
+        let image = source
+            .get_image(&image_filename)
+            .map_err(ImportError::Image)?;
+        Ok(Arc::new(ImageTexture::new(image)))
+    }
+}
+
+fn is_black(color: Color) -> bool {
+    color.r <= 0.0 && color.g <= 0.0 && color.b <= 0.0
+}
+
+fn parse_mtl(
+    text: &str,
+    filename: &str,
+    materials: &mut HashMap<String, MtlMaterial>,
+) -> Result<(), ImportError> {
+    let mut current: Option<MtlMaterial> = None;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if let Some(material) = current.take() {
+                materials.insert(material.name.clone(), material);
+            }
+            current = Some(MtlMaterial::new(args.join(" "), filename));
+            continue;
+        }
+
+        let Some(material) = current.as_mut() else {
+            return Err(parse_error(
+                filename,
+                line_number,
+                format!("\"{keyword}\" before newmtl"),
+            ));
+        };
+
+        match keyword {
+            "Kd" => material.diffuse = parse_mtl_color(&args, filename, line_number)?,
+            "Ks" => material.specular = parse_mtl_color(&args, filename, line_number)?,
+            "Ke" => material.emission = parse_mtl_color(&args, filename, line_number)?,
+            "Ns" => material.specular_exponent = parse_numbers(&args, 1, filename, line_number)?[0],
+            "Ni" => {
+                material.refraction_index = Some(parse_numbers(&args, 1, filename, line_number)?[0])
+            }
+            "d" => material.dissolve = parse_numbers(&args, 1, filename, line_number)?[0],
+            "Tr" => material.dissolve = 1.0 - parse_numbers(&args, 1, filename, line_number)?[0],
+            "Pr" => material.roughness = Some(parse_numbers(&args, 1, filename, line_number)?[0]),
+            "Pm" => material.metallic = Some(parse_numbers(&args, 1, filename, line_number)?[0]),
+            "illum" => {
+                material.illumination_model = args
+                    .first()
+                    .and_then(|arg| arg.parse().ok())
+                    .ok_or_else(|| parse_error(filename, line_number, "invalid illum"))?;
+            }
+            "map_Kd" => {
+                // texture options such as "-s 1 1 1" precede the filename
+                let filename = args.last().ok_or_else(|| {
+                    parse_error(filename, line_number, "map_Kd requires a filename")
+                })?;
+                material.diffuse_map = Some(filename.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(material) = current.take() {
+        materials.insert(material.name.clone(), material);
+    }
+
+    Ok(())
+}
+
+fn parse_mtl_color(
+    args: &[&str],
+    filename: &str,
+    line_number: usize,
+) -> Result<Color, ImportError> {
+    if matches!(args.first(), Some(&"spectral") | Some(&"xyz")) {
+        return Err(parse_error(
+            filename,
+            line_number,
+            "only RGB colors are supported",
+        ));
+    }
+    let v = parse_numbers(args, 1, filename, line_number)?;
+    if v.len() >= 3 {
+        Ok(Color::new(v[0], v[1], v[2]))
+    } else {
+        Ok(Color::new(v[0], v[0], v[0]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, sync::Arc};
+
+    use crate::{
+        Color, Image,
+        image::ImageError,
+        import::{ImportError, ImportOptions, ImportSource, load_obj},
+        material::Lambertian,
+        object::TriangleMesh,
+    };
+
+    use super::{parse_mtl, parse_obj};
+
+    #[derive(Default)]
+    struct MemorySource {
+        files: HashMap<String, String>,
+    }
+
+    impl ImportSource for MemorySource {
+        fn get_bytes(&self, filename: &str) -> Result<Vec<u8>, ImportError> {
+            self.files
+                .get(filename)
+                .map(|contents| contents.as_bytes().to_vec())
+                .ok_or_else(|| ImportError::Io(format!("missing {filename}")))
+        }
+
+        fn get_image(&self, filename: &str) -> Result<Arc<dyn Image>, ImageError> {
+            Err(ImageError::Io(format!("missing {filename}")))
+        }
+    }
+
+    #[test]
+    fn parse_face_formats() {
+        let obj = parse_obj(
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n\
+             vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+             vn 0 0 1\n\
+             f 1/1/1 2/2/1 3/3/1 4/4/1\n\
+             f -4//-1 -3//-1 -2//-1\n\
+             f 1 2 3 # comment\n",
+            "test.obj",
+        )
+        .unwrap();
+
+        let faces = &obj.groups[0].faces;
+        assert_eq!(faces.len(), 4);
+        assert_eq!(faces[0].positions, [0, 1, 2]);
+        assert_eq!(faces[1].positions, [0, 2, 3]);
+        assert_eq!(faces[1].uvs, Some([0, 2, 3]));
+        assert_eq!(faces[1].normals, Some([0, 0, 0]));
+        assert_eq!(faces[2].positions, [0, 1, 2]);
+        assert_eq!(faces[2].uvs, None);
+        assert_eq!(faces[2].normals, Some([0, 0, 0]));
+        assert_eq!(faces[3].normals, None);
+    }
+
+    #[test]
+    fn parse_index_out_of_range() {
+        let err = parse_obj("v 0 0 0\nv 1 0 0\nf 1 2 3\n", "test.obj").unwrap_err();
+        assert!(matches!(err, ImportError::Parse(message) if message.starts_with("test.obj:3:")));
+    }
+
+    #[test]
+    fn parse_mtl_materials() {
+        let mut materials = HashMap::new();
+        parse_mtl(
+            "newmtl glass\nKd 0 0 0\nNi 1.45\nd 0.2\n\
+             newmtl light\nKe 4 4 4\n\
+             newmtl chrome\nillum 3\nKs 0.9\nNs 1000\n\
+             newmtl paint\nKd 0.1 0.2 0.3\nmap_Kd -s 1 1 1 textures/paint.png\n\
+             newmtl gold\nKd 1 0.78 0.34\nPm 1\nPr 0.3\n",
+            "models/scene.mtl",
+            &mut materials,
+        )
+        .unwrap();
+
+        let source = MemorySource::default();
+        let glass = format!("{:?}", materials["glass"].to_material(&source).unwrap());
+        assert!(glass.starts_with("Dielectric"), "{glass}");
+        assert!(glass.contains("1.45"), "{glass}");
+        let light = format!("{:?}", materials["light"].to_material(&source).unwrap());
+        assert!(light.starts_with("DiffuseLight"), "{light}");
+        let chrome = format!("{:?}", materials["chrome"].to_material(&source).unwrap());
+        assert!(chrome.starts_with("Metal"), "{chrome}");
+        let gold = format!("{:?}", materials["gold"].to_material(&source).unwrap());
+        assert!(gold.starts_with("MetallicRoughness"), "{gold}");
+        assert_eq!(
+            materials["paint"].diffuse_map.as_deref(),
+            Some("textures/paint.png")
+        );
+        assert!(matches!(
+            materials["paint"].to_material(&source),
+            Err(ImportError::Image(ImageError::Io(message))) if message == "missing models/textures/paint.png"
+        ));
+    }
+
+    #[test]
+    fn load_one_mesh_per_material() {
+        let mut source = MemorySource::default();
+        source.files.insert(
+            "models/two.obj".to_owned(),
+            "mtllib two.mtl\nv 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n\
+             usemtl red\nf 1 2 3\nusemtl blue\nf 1 3 4\nusemtl red\nf 3 2 1\n"
+                .to_owned(),
+        );
+        source.files.insert(
+            "models/two.mtl".to_owned(),
+            "newmtl red\nKd 1 0 0\nnewmtl blue\nKd 0 0 1\n".to_owned(),
+        );
+
+        let options = ImportOptions::new(Arc::new(Lambertian::new_from_color(Color::WHITE)));
+        let nodes = load_obj(&source, "models/two.obj", &options).unwrap();
+        let face_counts: Vec<usize> = nodes
+            .iter()
+            .map(|node| {
+                node.as_any()
+                    .downcast_ref::<TriangleMesh>()
+                    .unwrap()
+                    .face_count()
+            })
+            .collect();
+        assert_eq!(face_counts, vec![2, 1]);
+    }
+}
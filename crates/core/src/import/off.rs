@@ -0,0 +1,200 @@
+//! Object File Format (OFF) import.
+//!
+//! Reads the 3D variants of the format (`OFF`, `COFF`, `NOFF`, `CNOFF`, ...). Vertex
+//! normals, colors and texture coordinates are ignored. Polygonal faces are
+//! triangulated as fans.
+
+use std::sync::Arc;
+
+use crate::{
+    Vector3,
+    import::{ImportError, ImportOptions, ImportSource, parse_error, parse_numbers, read_text},
+    object::{MeshData, MeshFace, TriangleMesh},
+};
+
+/// Loads an OFF file from `source` as a single [`TriangleMesh`] using
+/// [`ImportOptions::default_material`].
+pub fn load_off(
+    source: &dyn ImportSource,
+    filename: &str,
+    options: &ImportOptions,
+) -> Result<TriangleMesh, ImportError> {
+    let (positions, faces) = parse_off(&read_text(source, filename)?, filename)?;
+
+    let data = Arc::new(MeshData {
+        positions: positions
+            .into_iter()
+            .map(|p| &options.transform * p)
+            .collect(),
+        ..Default::default()
+    });
+
+    Ok(TriangleMesh::new(
+        data,
+        faces,
+        options.default_material.clone(),
+    ))
+}
+
+fn parse_off(text: &str, filename: &str) -> Result<(Vec<Vector3>, Vec<MeshFace>), ImportError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(line_index, line)| {
+            (
+                line_index + 1,
+                line.split('#')
+                    .next()
+                    .unwrap_or("")
+                    .split_whitespace()
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .filter(|(_, tokens)| !tokens.is_empty());
+
+    let (line_number, mut tokens) = lines
+        .next()
+        .ok_or_else(|| parse_error(filename, 1, "missing OFF header"))?;
+    let header = tokens[0];
+    if !header.ends_with("OFF") {
+        return Err(parse_error(filename, line_number, "missing OFF header"));
+    }
+    if header.contains('4') || header.contains('n') {
+        return Err(parse_error(
+            filename,
+            line_number,
+            format!("unsupported OFF variant \"{header}\""),
+        ));
+    }
+
+    // the counts may follow the header on the same line
+    tokens.remove(0);
+    let (line_number, tokens) = if tokens.is_empty() {
+        lines
+            .next()
+            .ok_or_else(|| parse_error(filename, line_number, "missing vertex and face counts"))?
+    } else {
+        (line_number, tokens)
+    };
+    if tokens.len() < 2 {
+        return Err(parse_error(
+            filename,
+            line_number,
+            "expected at least 2 values",
+        ));
+    }
+    let [vertex_count, face_count] = [tokens[0], tokens[1]].map(|token| {
+        token
+            .parse::<usize>()
+            .map_err(|_| parse_error(filename, line_number, format!("invalid count \"{token}\"")))
+    });
+    let (vertex_count, face_count) = (vertex_count?, face_count?);
+    // each vertex and face takes a line, so larger counts can't be right
+    if vertex_count
+        .checked_add(face_count)
+        .is_none_or(|count| count > lines.clone().count())
+    {
+        return Err(parse_error(
+            filename,
+            line_number,
+            format!(
+                "expected {vertex_count} vertices and {face_count} faces but file is truncated"
+            ),
+        ));
+    }
+
+    let mut positions = vec![];
+    for _ in 0..vertex_count {
+        let (line_number, tokens) = lines
+            .next()
+            .ok_or_else(|| parse_error(filename, line_number, "unexpected end of file"))?;
+        let v = parse_numbers(&tokens[..tokens.len().min(3)], 3, filename, line_number)?;
+        positions.push(Vector3::new(v[0], v[1], v[2]));
+    }
+
+    let mut faces = vec![];
+    for _ in 0..face_count {
+        let (line_number, tokens) = lines
+            .next()
+            .ok_or_else(|| parse_error(filename, line_number, "unexpected end of file"))?;
+        let n: usize = tokens[0]
+            .parse()
+            .map_err(|_| parse_error(filename, line_number, "invalid face vertex count"))?;
+        if n < 3 || tokens.len() < n + 1 {
+            return Err(parse_error(filename, line_number, "invalid face"));
+        }
+        // any values after the indices are a face color
+        let indices = tokens[1..=n]
+            .iter()
+            .map(|token| {
+                token
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|i| *i < positions.len())
+                    .ok_or_else(|| {
+                        parse_error(filename, line_number, format!("invalid index \"{token}\""))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for i in 1..n - 1 {
+            faces.push(MeshFace::new([indices[0], indices[i], indices[i + 1]]));
+        }
+    }
+
+    Ok((positions, faces))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::import::ImportError;
+
+    use super::parse_off;
+
+    #[test]
+    fn parse_quad_with_colors() {
+        let (positions, faces) = parse_off(
+            "OFF\n\
+             # a unit square\n\
+             4 1 0\n\
+             0 0 0\n1 0 0\n1 1 0\n0 1 0\n\
+             4 0 1 2 3 255 0 0\n",
+            "square.off",
+        )
+        .unwrap();
+        assert_eq!(positions.len(), 4);
+        assert_eq!(faces.len(), 2);
+        assert_eq!(faces[0].positions, [0, 1, 2]);
+        assert_eq!(faces[1].positions, [0, 2, 3]);
+    }
+
+    #[test]
+    fn parse_counts_on_header_line() {
+        let (positions, faces) =
+            parse_off("OFF 3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n", "t.off").unwrap();
+        assert_eq!(positions.len(), 3);
+        assert_eq!(faces.len(), 1);
+    }
+
+    #[test]
+    fn parse_index_out_of_range() {
+        let err = parse_off("OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 3\n", "t.off").unwrap_err();
+        assert!(matches!(err, ImportError::Parse(message) if message.starts_with("t.off:6:")));
+    }
+
+    #[test]
+    fn parse_invalid_counts() {
+        for text in [
+            "OFF\n1e30 0 0\n",
+            "OFF\n-1 0 0\n",
+            "OFF\n1.5 0 0\n",
+            "OFF\n18446744073709551615 1 0\n",
+            "OFF\n3 1000000000 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n",
+        ] {
+            let err = parse_off(text, "t.off").unwrap_err();
+            assert!(
+                matches!(&err, ImportError::Parse(message) if message.starts_with("t.off:2:")),
+                "{text:?} {err:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! STL import.
+//!
+//! Both the ASCII and the binary variant are supported. The variant is detected from
+//! the file size recorded in the binary header, since binary files written by some
+//! exporters also start with `solid`. Facet normals stored in the file are ignored;
+//! the winding order of the vertices determines the front face.
+
+use std::sync::Arc;
+
+use crate::{
+    Vector3,
+    import::{ImportError, ImportOptions, ImportSource, parse_error, parse_numbers},
+    object::{MeshData, MeshFace, TriangleMesh},
+};
+
+/// Size of the binary STL header including the triangle count.
+const BINARY_HEADER_SIZE: usize = 84;
+
+/// Size of a single binary STL triangle record.
+const BINARY_TRIANGLE_SIZE: usize = 50;
+
+/// Loads an ASCII or binary STL file from `source` as a single [`TriangleMesh`]
+/// using [`ImportOptions::default_material`].
+pub fn load_stl(
+    source: &dyn ImportSource,
+    filename: &str,
+    options: &ImportOptions,
+) -> Result<TriangleMesh, ImportError> {
+    let bytes = source.get_bytes(filename)?;
+    let triangles = if is_binary(&bytes) {
+        parse_binary(&bytes, filename)?
+    } else {
+        parse_ascii(&String::from_utf8_lossy(&bytes), filename)?
+    };
+
+    let positions = triangles
+        .iter()
+        .flatten()
+        .map(|p| &options.transform * *p)
+        .collect();
+    let faces = (0..triangles.len())
+        .map(|i| MeshFace::new([i * 3, i * 3 + 1, i * 3 + 2]))
+        .collect();
+    let data = Arc::new(MeshData {
+        positions,
+        ..Default::default()
+    });
+
+    Ok(TriangleMesh::new(
+        data,
+        faces,
+        options.default_material.clone(),
+    ))
+}
+
+/// Returns the triangle count recorded in a binary header.
+fn binary_count(bytes: &[u8]) -> usize {
+    u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize
+}
+
+/// Returns the size of the triangle records of a binary file, or `None` if it
+/// doesn't fit in memory, which an untrusted count can ask for on 32-bit targets.
+fn binary_records_size(count: usize) -> Option<usize> {
+    count.checked_mul(BINARY_TRIANGLE_SIZE)
+}
+
+fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.len() >= BINARY_HEADER_SIZE
+        && binary_records_size(binary_count(bytes))
+            .is_some_and(|size| bytes.len() - BINARY_HEADER_SIZE == size)
+    {
+        return true;
+    }
+    !bytes.trim_ascii_start().starts_with(b"solid")
+}
+
+fn parse_binary(bytes: &[u8], filename: &str) -> Result<Vec<[Vector3; 3]>, ImportError> {
+    if bytes.len() < BINARY_HEADER_SIZE {
+        return Err(ImportError::Parse(format!(
+            "{filename}: binary STL header truncated"
+        )));
+    }
+    let count = binary_count(bytes);
+    let records = &bytes[BINARY_HEADER_SIZE..];
+    if binary_records_size(count).is_none_or(|size| records.len() < size) {
+        return Err(ImportError::Parse(format!(
+            "{filename}: expected {count} triangles but file is truncated"
+        )));
+    }
+
+    let read_f32 = |offset: usize| {
+        f32::from_le_bytes([
+            records[offset],
+            records[offset + 1],
+            records[offset + 2],
+            records[offset + 3],
+        ]) as f64
+    };
+    let read_vector =
+        |offset: usize| Vector3::new(read_f32(offset), read_f32(offset + 4), read_f32(offset + 8));
+
+    Ok((0..count)
+        .map(|i| {
+            // each record is a normal, three vertices and a two byte attribute count
+            let offset = i * BINARY_TRIANGLE_SIZE + 12;
+            [
+                read_vector(offset),
+                read_vector(offset + 12),
+                read_vector(offset + 24),
+            ]
+        })
+        .collect())
+}
+
+fn parse_ascii(text: &str, filename: &str) -> Result<Vec<[Vector3; 3]>, ImportError> {
+    let mut triangles = vec![];
+    let mut polygon: Vec<Vector3> = vec![];
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "vertex" => {
+                let v = parse_numbers(&args, 3, filename, line_number)?;
+                polygon.push(Vector3::new(v[0], v[1], v[2]));
+            }
+            "endloop" => {
+                if polygon.len() < 3 {
+                    return Err(parse_error(
+                        filename,
+                        line_number,
+                        "facet requires at least 3 vertices",
+                    ));
+                }
+                for i in 1..polygon.len() - 1 {
+                    triangles.push([polygon[0], polygon[i], polygon[i + 1]]);
+                }
+                polygon.clear();
+            }
+            "solid" | "facet" | "outer" | "endfacet" | "endsolid" => {}
+            other => {
+                return Err(parse_error(
+                    filename,
+                    line_number,
+                    format!("unexpected \"{other}\""),
+                ));
+            }
+        }
+    }
+
+    Ok(triangles)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ImportError, is_binary, parse_ascii, parse_binary};
+
+    fn binary_stl(header: &[u8], triangles: &[[[f32; 3]; 3]]) -> Vec<u8> {
+        let mut bytes = header.to_vec();
+        bytes.resize(80, 0);
+        bytes.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+        for triangle in triangles {
+            bytes.extend_from_slice(&[0; 12]);
+            for vertex in triangle {
+                for value in vertex {
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            bytes.extend_from_slice(&[0; 2]);
+        }
+        bytes
+    }
+
+    #[test]
+    fn parse_ascii_facets() {
+        let triangles = parse_ascii(
+            "solid cube\n\
+             facet normal 0 0 1\n\
+               outer loop\n\
+                 vertex 0 0 0\n\
+                 vertex 1 0 0\n\
+                 vertex 1 1 0\n\
+               endloop\n\
+             endfacet\n\
+             endsolid cube\n",
+            "cube.stl",
+        )
+        .unwrap();
+        assert_eq!(triangles.len(), 1);
+        assert_eq!(triangles[0][2].x, 1.0);
+        assert_eq!(triangles[0][2].y, 1.0);
+    }
+
+    #[test]
+    fn detect_binary_starting_with_solid() {
+        let bytes = binary_stl(
+            b"solid exported",
+            &[[[0.0; 3], [1.0, 0.0, 0.0], [0.0, 2.0, 0.0]]],
+        );
+        assert!(is_binary(&bytes));
+        assert!(!is_binary(b"solid cube\nendsolid cube\n"));
+
+        let triangles = parse_binary(&bytes, "cube.stl").unwrap();
+        assert_eq!(triangles.len(), 1);
+        assert_eq!(triangles[0][1].x, 1.0);
+        assert_eq!(triangles[0][2].y, 2.0);
+    }
+
+    #[test]
+    fn parse_binary_truncated() {
+        let mut bytes = binary_stl(b"", &[[[0.0; 3]; 3]]);
+        bytes.truncate(100);
+        assert!(parse_binary(&bytes, "cube.stl").is_err());
+    }
+
+    #[test]
+    fn parse_binary_huge_count() {
+        // a header count of u32::MAX overflows the record size on 32-bit targets
+        let mut bytes = binary_stl(b"solid huge", &[[[0.0; 3]; 3]]);
+        bytes[80..84].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(!is_binary(&bytes));
+        assert!(matches!(
+            parse_binary(&bytes, "huge.stl"),
+            Err(ImportError::Parse(message)) if message.contains("truncated")
+        ));
+    }
+}
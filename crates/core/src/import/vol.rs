@@ -0,0 +1,152 @@
+//! Voxel grid import from Mitsuba `.vol` files.
+//!
+//! The format is a 48 byte header followed by the raw voxels, all little endian:
+//! the bytes `VOL` and the version 3, the encoding (1 for `f32` voxels, 3 for `u8`
+//! voxels mapping 0..255 to 0..1), the resolution along x, y and z, the number of
+//! channels, and the bounding box as its minimum and maximum points in `f32`. Voxels
+//! follow with x varying fastest, then y, then z, and the channels of each voxel
+//! together. The density of a voxel is the average of its channels.
+
+use crate::{
+    AxisAlignedBoundingBox, Vector3,
+    import::{ImportError, ImportSource},
+    volume::VoxelGrid,
+};
+
+/// Size of the header, up to the first voxel.
+const HEADER_SIZE: usize = 48;
+
+/// Loads a Mitsuba `.vol` file from `source` as a density grid.
+pub fn load_vol(source: &dyn ImportSource, filename: &str) -> Result<VoxelGrid, ImportError> {
+    parse_vol(&source.get_bytes(filename)?, filename)
+}
+
+fn parse_vol(bytes: &[u8], filename: &str) -> Result<VoxelGrid, ImportError> {
+    let error = |message: &str| ImportError::Parse(format!("{filename}: {message}"));
+    if bytes.len() < HEADER_SIZE {
+        return Err(error("header truncated"));
+    }
+    if &bytes[..3] != b"VOL" || bytes[3] != 3 {
+        return Err(error("not a version 3 VOL file"));
+    }
+
+    let read_u32 = |offset: usize| {
+        u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ])
+    };
+    let read_f32 = |offset: usize| f32::from_bits(read_u32(offset)) as f64;
+
+    let encoding = read_u32(4);
+    let voxel_size = match encoding {
+        1 => 4,
+        3 => 1,
+        _ => return Err(error(&format!("unsupported encoding {encoding}"))),
+    };
+    let resolution = [
+        read_u32(8) as usize,
+        read_u32(12) as usize,
+        read_u32(16) as usize,
+    ];
+    let channels = read_u32(20) as usize;
+    if resolution.contains(&0) || channels == 0 {
+        return Err(error("empty grid"));
+    }
+    let bbox = AxisAlignedBoundingBox::new_from_points(
+        Vector3::new(read_f32(24), read_f32(28), read_f32(32)),
+        Vector3::new(read_f32(36), read_f32(40), read_f32(44)),
+    );
+
+    // the header is untrusted, the size of a huge grid mustn't overflow
+    let data = &bytes[HEADER_SIZE..];
+    if resolution
+        .iter()
+        .chain([&channels, &voxel_size])
+        .try_fold(1usize, |size, n| size.checked_mul(*n))
+        .is_none_or(|size| data.len() < size)
+    {
+        let [x, y, z] = resolution;
+        return Err(error(&format!(
+            "expected {x}x{y}x{z} voxels but file is truncated"
+        )));
+    }
+    let count = resolution.iter().product::<usize>();
+
+    let read_value = |index: usize| {
+        if encoding == 1 {
+            read_f32(HEADER_SIZE + index * 4)
+        } else {
+            data[index] as f64 / 255.0
+        }
+    };
+    let values = (0..count)
+        .map(|voxel| {
+            (0..channels)
+                .map(|channel| read_value(voxel * channels + channel))
+                .sum::<f64>()
+                / channels as f64
+        })
+        .collect();
+    Ok(VoxelGrid::new(resolution, bbox, values))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Axis, Vector3, volume::DensityField};
+
+    use super::parse_vol;
+
+    fn vol(encoding: u32, resolution: [u32; 3], channels: u32, voxels: &[u8]) -> Vec<u8> {
+        let mut bytes = b"VOL\x03".to_vec();
+        for value in [
+            encoding,
+            resolution[0],
+            resolution[1],
+            resolution[2],
+            channels,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [0.0f32, 0.0, 0.0, 2.0, 1.0, 1.0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(voxels);
+        bytes
+    }
+
+    #[test]
+    fn parse_float_voxels() {
+        let voxels: Vec<u8> = [0.0f32, 1.0, 2.0, 4.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        // two voxels of two channels
+        let grid = parse_vol(&vol(1, [2, 1, 1], 2, &voxels), "smoke.vol").unwrap();
+        assert_eq!(grid.resolution(), [2, 1, 1]);
+        assert_eq!(grid.bounding_box().axis_interval(Axis::X).max, 2.0);
+        assert_eq!(grid.density(Vector3::new(0.5, 0.5, 0.5)), 0.5);
+        assert_eq!(grid.density(Vector3::new(1.5, 0.5, 0.5)), 3.0);
+        assert_eq!(grid.max_density(), 3.0);
+    }
+
+    #[test]
+    fn parse_byte_voxels() {
+        let grid = parse_vol(&vol(3, [2, 1, 1], 1, &[0, 255]), "smoke.vol").unwrap();
+        assert_eq!(grid.density(Vector3::new(1.5, 0.5, 0.5)), 1.0);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse_vol(b"VOL\x03", "smoke.vol").is_err());
+        assert!(parse_vol(&vol(2, [2, 1, 1], 1, &[0; 4]), "smoke.vol").is_err());
+        assert!(parse_vol(&vol(3, [2, 2, 1], 1, &[0; 3]), "smoke.vol").is_err());
+        assert!(parse_vol(&vol(3, [65536, 65536, 65536], 1, &[0]), "smoke.vol").is_err());
+        assert!(parse_vol(&vol(1, [u32::MAX; 3], u32::MAX, &[0; 4]), "smoke.vol").is_err());
+        let mut bytes = vol(3, [1, 1, 1], 1, &[0]);
+        bytes[0] = b'X';
+        assert!(parse_vol(&bytes, "smoke.vol").is_err());
+    }
+}
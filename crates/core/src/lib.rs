@@ -1,18 +1,26 @@
 pub mod axis;
 pub mod axis_aligned_bounding_box;
+pub mod background;
 pub mod camera;
 pub mod color;
 pub mod image;
+pub mod import;
 pub mod interval;
+pub mod light;
 pub mod material;
 pub mod matrix;
 pub mod object;
 pub mod probability_density_function;
+pub mod progressive_renderer;
 pub mod random;
 pub mod ray;
+pub mod sampler;
+pub mod spectrum;
 pub mod texture;
+pub mod tone_mapping;
 pub mod utils;
 pub mod vector;
+pub mod volume;
 
 use std::sync::Arc;
 
@@ -22,13 +30,17 @@ pub use camera::{Camera, CameraBuilder};
 pub use color::Color;
 pub use image::Image;
 pub use interval::Interval;
-pub use matrix::Matrix3x3;
+use light::Lights;
+pub use matrix::{Matrix3x3, Matrix4x4};
 pub use object::Node;
 pub use probability_density_function::{
-    CosinePdf, HittablePdf, ProbabilityDensityFunction, SpherePdf,
+    BackgroundPdf, CosinePdf, HittablePdf, ProbabilityDensityFunction, SpherePdf,
 };
-pub use random::{Random, random_new};
+pub use progressive_renderer::ProgressiveRenderer;
+pub use random::{Random, SeededRandom, random_new};
 pub use ray::Ray;
+pub use sampler::{Sampler, SamplerType};
+pub use tone_mapping::ToneMapping;
 pub use vector::Vector3;
 
 pub struct RenderContext {
@@ -39,7 +51,7 @@ pub struct RenderContext {
 pub struct SceneData {
     pub camera: Arc<Camera>,
     pub world: Arc<dyn Node>,
-    pub lights: Option<Arc<dyn Node>>,
+    pub lights: Lights,
 }
 
 pub fn line_number_at_offset(text: &str, offset: usize) -> usize {
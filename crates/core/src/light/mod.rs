@@ -0,0 +1,88 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{Color, Node, Random, Vector3};
+
+pub mod point;
+pub mod spot;
+pub mod sun;
+
+pub use point::PointLight;
+pub use spot::SpotLight;
+pub use sun::SunLight;
+
+/// A light without geometry, sampled directly by the camera at every diffuse or
+/// glossy bounce.
+///
+/// Point and spot lights, and a sun without angular diameter, are delta lights:
+/// all their light arrives from a single direction, so no ray sampled from a
+/// material can find them and [`Light::is_delta`] returns true. A sun with an
+/// angular diameter covers a small cone of directions, which rays leaving the scene
+/// see through [`Light::radiance`].
+pub trait Light: Debug + Send + Sync {
+    /// Samples a direction from `origin` towards the light, None if no light arrives
+    /// at `origin`.
+    fn sample(&self, random: &dyn Random, origin: &Vector3) -> Option<LightSample>;
+
+    /// Returns true if the light arrives from a single direction.
+    fn is_delta(&self) -> bool {
+        true
+    }
+
+    /// Returns the probability density, with respect to solid angle, of
+    /// [`Light::sample`] generating `direction`. Always 0 for delta lights.
+    fn pdf_value(&self, _direction: &Vector3) -> f64 {
+        0.0
+    }
+
+    /// Returns the radiance arriving from the light along a ray leaving the scene in
+    /// `direction`.
+    fn radiance(&self, _direction: &Vector3) -> Color {
+        Color::BLACK
+    }
+}
+
+/// A direction sampled towards a [`Light`].
+#[derive(Debug, Clone, Copy)]
+pub struct LightSample {
+    /// Unit direction from the shaded point towards the light
+    pub direction: Vector3,
+    /// Distance to the light, infinite for lights far away like the sun
+    pub distance: f64,
+    /// Radiance arriving along `direction`. For delta lights this is the light
+    /// falling on a surface facing the light, there is no density to divide by.
+    pub radiance: Color,
+    /// Probability density, with respect to solid angle, of the direction. 1 for
+    /// delta lights.
+    pub pdf: f64,
+}
+
+/// The lights of a scene that are sampled directly.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use caustic_core::{Color, Vector3, light::{Lights, PointLight}};
+///
+/// let mut lights = Lights::default();
+/// lights.sources.push(Arc::new(PointLight::new(Vector3::new(0.0, 5.0, 0.0), Color::WHITE)));
+/// assert!(lights.geometry.is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Lights {
+    /// Emissive objects of the world, sampled through [`Node::pdf_value`] and
+    /// [`Node::random`]
+    pub geometry: Option<Arc<dyn Node>>,
+    /// Lights without geometry
+    pub sources: Vec<Arc<dyn Light>>,
+}
+
+impl Lights {
+    /// Creates the lights of a scene whose only lights are emissive objects.
+    pub fn from_geometry(geometry: Arc<dyn Node>) -> Self {
+        Self {
+            geometry: Some(geometry),
+            sources: vec![],
+        }
+    }
+}
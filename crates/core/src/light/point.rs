@@ -0,0 +1,50 @@
+use crate::{
+    Color, Random, Vector3,
+    light::{Light, LightSample},
+};
+
+/// A light emitting equally in every direction from a single point.
+///
+/// `intensity` is the radiant intensity, the light falling on a surface facing the
+/// light at distance 1. It falls off with the square of the distance.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{Color, Vector3, light::{Light, PointLight}, random_new};
+///
+/// let light = PointLight::new(Vector3::new(0.0, 2.0, 0.0), Color::WHITE);
+/// let sample = light.sample(&*random_new(), &Vector3::ZERO).unwrap();
+/// assert_eq!(sample.distance, 2.0);
+/// assert_eq!(sample.radiance, Color::WHITE / 4.0);
+/// ```
+#[derive(Debug)]
+pub struct PointLight {
+    position: Vector3,
+    intensity: Color,
+}
+
+impl PointLight {
+    pub fn new(position: Vector3, intensity: Color) -> Self {
+        Self {
+            position,
+            intensity,
+        }
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, _random: &dyn Random, origin: &Vector3) -> Option<LightSample> {
+        let to_light = self.position - *origin;
+        let distance_squared = to_light.length_squared();
+        if distance_squared <= 0.0 {
+            return None;
+        }
+        Some(LightSample {
+            direction: to_light.unit(),
+            distance: distance_squared.sqrt(),
+            radiance: self.intensity / distance_squared,
+            pdf: 1.0,
+        })
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    Color, Random, Vector3,
+    light::{Light, LightSample},
+};
+
+/// A point light emitting in a cone.
+///
+/// The full `intensity` is emitted within `falloff_start` degrees of `direction`,
+/// it then fades out smoothly to 0 at `cutoff` degrees.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{Color, Vector3, light::{Light, SpotLight}, random_new};
+///
+/// let light = SpotLight::new(
+///     Vector3::new(0.0, 1.0, 0.0),
+///     Vector3::new(0.0, -1.0, 0.0),
+///     Color::WHITE,
+///     30.0,
+///     20.0,
+/// );
+/// let random = random_new();
+/// assert!(light.sample(&*random, &Vector3::ZERO).is_some());
+/// assert!(light.sample(&*random, &Vector3::new(5.0, 0.0, 0.0)).is_none());
+/// ```
+#[derive(Debug)]
+pub struct SpotLight {
+    position: Vector3,
+    direction: Vector3,
+    intensity: Color,
+    cos_cutoff: f64,
+    cos_falloff_start: f64,
+}
+
+impl SpotLight {
+    /// Creates a spot light at `position` pointing in `direction`, with the cone
+    /// angles measured from `direction` in degrees.
+    pub fn new(
+        position: Vector3,
+        direction: Vector3,
+        intensity: Color,
+        cutoff: f64,
+        falloff_start: f64,
+    ) -> Self {
+        let cos_cutoff = cutoff.to_radians().cos();
+        Self {
+            position,
+            direction: direction.unit(),
+            intensity,
+            cos_cutoff,
+            cos_falloff_start: falloff_start.min(cutoff).to_radians().cos(),
+        }
+    }
+
+    /// Returns the fraction of the intensity emitted at angle `cos_theta` from the
+    /// direction of the spot.
+    fn falloff(&self, cos_theta: f64) -> f64 {
+        if cos_theta >= self.cos_falloff_start {
+            return 1.0;
+        }
+        if cos_theta <= self.cos_cutoff {
+            return 0.0;
+        }
+        let t = (cos_theta - self.cos_cutoff) / (self.cos_falloff_start - self.cos_cutoff);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, _random: &dyn Random, origin: &Vector3) -> Option<LightSample> {
+        let to_light = self.position - *origin;
+        let distance_squared = to_light.length_squared();
+        if distance_squared <= 0.0 {
+            return None;
+        }
+        let direction = to_light.unit();
+        let falloff = self.falloff(-direction.dot(&self.direction));
+        if falloff <= 0.0 {
+            return None;
+        }
+        Some(LightSample {
+            direction,
+            distance: distance_squared.sqrt(),
+            radiance: self.intensity * falloff / distance_squared,
+            pdf: 1.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Color, Vector3};
+
+    use super::SpotLight;
+
+    #[test]
+    fn falloff_is_smooth() {
+        let light = SpotLight::new(
+            Vector3::ZERO,
+            Vector3::new(0.0, -1.0, 0.0),
+            Color::WHITE,
+            40.0,
+            20.0,
+        );
+        let falloff = |degrees: f64| light.falloff(degrees.to_radians().cos());
+        assert_eq!(falloff(10.0), 1.0);
+        assert!(falloff(30.0) > 0.2 && falloff(30.0) < 0.8);
+        assert_eq!(falloff(40.0), 0.0);
+        assert_eq!(falloff(90.0), 0.0);
+
+        let mut previous = 1.0;
+        for degrees in 20..=40 {
+            let value = falloff(degrees as f64);
+            assert!(value <= previous);
+            previous = value;
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::f64::consts::TAU;
+
+use crate::{
+    Color, Random, Vector3,
+    light::{Light, LightSample},
+    utils::OrthonormalBasis,
+};
+
+/// Light from a distant disc, like the sun, arriving from the same direction
+/// everywhere in the scene.
+///
+/// `irradiance` is the light falling on a surface facing the sun. The sun's disc
+/// covers `angular_diameter` degrees of the sky, which softens the shadows, the real
+/// sun measures about 0.53 degrees. A diameter of 0 gives perfectly sharp shadows.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{Color, Vector3, light::{Light, SunLight}, random_new};
+///
+/// let sun = SunLight::new(Vector3::new(1.0, 1.0, 0.0), Color::WHITE * 3.0, 0.53);
+/// let sample = sun.sample(&*random_new(), &Vector3::ZERO).unwrap();
+/// assert!(sample.distance.is_infinite());
+/// assert!(sun.radiance(&sample.direction).r > 0.0);
+/// ```
+#[derive(Debug)]
+pub struct SunLight {
+    direction: Vector3,
+    irradiance: Color,
+    cos_theta_max: f64,
+    /// Solid angle of the disc, 0 for a delta light
+    solid_angle: f64,
+}
+
+impl SunLight {
+    /// Creates a sun in `direction`, which points from the scene towards the sun.
+    pub fn new(direction: Vector3, irradiance: Color, angular_diameter: f64) -> Self {
+        let direction = direction.unit();
+        let cos_theta_max = (angular_diameter.max(0.0) / 2.0).to_radians().cos();
+        Self {
+            direction,
+            irradiance,
+            cos_theta_max,
+            solid_angle: TAU * (1.0 - cos_theta_max),
+        }
+    }
+}
+
+impl Light for SunLight {
+    fn sample(&self, random: &dyn Random, _origin: &Vector3) -> Option<LightSample> {
+        if self.is_delta() {
+            return Some(LightSample {
+                direction: self.direction,
+                distance: f64::INFINITY,
+                radiance: self.irradiance,
+                pdf: 1.0,
+            });
+        }
+
+        // uniform in the cone of the disc
+        let (r1, r2) = random.rand_2d();
+        let cos_theta = 1.0 - r1 * (1.0 - self.cos_theta_max);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = TAU * r2;
+        let direction = OrthonormalBasis::new(self.direction).transform_to_local(Vector3::new(
+            phi.cos() * sin_theta,
+            phi.sin() * sin_theta,
+            cos_theta,
+        ));
+        Some(LightSample {
+            direction,
+            distance: f64::INFINITY,
+            radiance: self.irradiance / self.solid_angle,
+            pdf: 1.0 / self.solid_angle,
+        })
+    }
+
+    fn is_delta(&self) -> bool {
+        self.solid_angle <= 0.0
+    }
+
+    fn pdf_value(&self, direction: &Vector3) -> f64 {
+        if !self.is_delta() && direction.unit().dot(&self.direction) >= self.cos_theta_max {
+            1.0 / self.solid_angle
+        } else {
+            0.0
+        }
+    }
+
+    fn radiance(&self, direction: &Vector3) -> Color {
+        if self.pdf_value(direction) > 0.0 {
+            self.irradiance / self.solid_angle
+        } else {
+            Color::BLACK
+        }
+    }
+}
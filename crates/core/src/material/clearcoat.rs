@@ -0,0 +1,348 @@
+use std::{any::Any, sync::Arc};
+
+use crate::{
+    Color, ProbabilityDensityFunction, Ray, RenderContext, Vector3,
+    material::{
+        Material, PdfOrRay, ScatterResult,
+        microfacet::{TrowbridgeReitz, fresnel_dielectric},
+    },
+    object::HitRecord,
+    utils::OrthonormalBasis,
+};
+
+/// Smallest probability of sampling either the coat or the base, so neither is
+/// left out even when the Fresnel reflectance is extreme.
+const MIN_LAYER_PROBABILITY: f64 = 0.2;
+
+/// Material with a thin dielectric coat over a base material, like car paint or
+/// varnished wood.
+///
+/// The coat reflects following the Fresnel equations of a dielectric with a GGX
+/// microfacet distribution, so it barely shows facing the viewer and turns into a
+/// mirror at grazing angles. The light it transmits reaches the base, tinted by
+/// the coat on the way in and out, and the base itself is unchanged. Inter-reflections
+/// between the coat and the base are ignored, and hits from inside the object go
+/// straight to the base.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use caustic_core::{Color, material::{Clearcoat, Lambertian}};
+///
+/// let paint = Arc::new(Lambertian::new_from_color(Color::new(0.0, 0.3, 0.8)));
+/// let car_paint = Clearcoat::new(paint, 1.5).with_roughness(0.05);
+/// ```
+#[derive(Debug)]
+pub struct Clearcoat {
+    base: Arc<dyn Material>,
+    /// Refractive index of the coat
+    refraction_index: f64,
+    roughness: f64,
+    distribution: TrowbridgeReitz,
+    /// Fraction of each color channel the coat lets through at normal incidence, on
+    /// the way in and out together
+    tint: Color,
+}
+
+impl Clearcoat {
+    pub fn new(base: Arc<dyn Material>, refraction_index: f64) -> Self {
+        Self {
+            base,
+            refraction_index,
+            roughness: 0.0,
+            distribution: TrowbridgeReitz::from_roughness(0.0),
+            tint: Color::WHITE,
+        }
+    }
+
+    /// Makes the coat rough, from smooth at 0 to very rough at 1.
+    pub fn with_roughness(mut self, roughness: f64) -> Self {
+        self.roughness = roughness;
+        self.distribution = TrowbridgeReitz::from_roughness(roughness);
+        self
+    }
+
+    /// Tints the coat, `tint` being the color of white light going through it and back
+    /// at normal incidence. The tint gets stronger at grazing angles where the light
+    /// goes through more of the coat.
+    pub fn with_tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    /// Returns the material under the coat.
+    pub fn base(&self) -> &Arc<dyn Material> {
+        &self.base
+    }
+
+    pub fn refraction_index(&self) -> f64 {
+        self.refraction_index
+    }
+
+    pub fn roughness(&self) -> f64 {
+        self.roughness
+    }
+
+    pub fn tint(&self) -> Color {
+        self.tint
+    }
+
+    fn fresnel(&self, cos_theta: f64) -> f64 {
+        fresnel_dielectric(cos_theta, 1.0 / self.refraction_index)
+    }
+
+    /// Returns the fraction of the light that goes through the coat to the base and
+    /// back, for directions in the local frame of the surface. `wi` may point inside
+    /// the object when the base transmits light.
+    fn transmittance(&self, wo: &Vector3, wi: &Vector3) -> Color {
+        let (cos_o, cos_i) = (wo.z, wi.z.abs());
+        // length of the path through the coat, relative to going straight through it,
+        // from the cosines of the refracted directions
+        let refracted_cos = |cos_theta: f64| {
+            let sin2 =
+                (1.0 - cos_theta * cos_theta) / (self.refraction_index * self.refraction_index);
+            (1.0 - sin2).max(0.0).sqrt()
+        };
+        let path_length = (1.0 / refracted_cos(cos_o) + 1.0 / refracted_cos(cos_i)) / 2.0;
+        let transmitted = (1.0 - self.fresnel(cos_o)) * (1.0 - self.fresnel(cos_i));
+        Color::new(
+            self.tint.r.powf(path_length),
+            self.tint.g.powf(path_length),
+            self.tint.b.powf(path_length),
+        ) * transmitted
+    }
+
+    /// Returns the probability of sampling the coat rather than the base.
+    fn coat_probability(&self, wo: &Vector3) -> f64 {
+        self.fresnel(wo.z)
+            .clamp(MIN_LAYER_PROBABILITY, 1.0 - MIN_LAYER_PROBABILITY)
+    }
+}
+
+impl Material for Clearcoat {
+    fn scatter(&self, ctx: &RenderContext, r_in: &Ray, hit: &HitRecord) -> Option<ScatterResult> {
+        // the coat only covers the outside, like for light leaving a translucent base
+        if !hit.front_face {
+            return self.base.scatter(ctx, r_in, hit);
+        }
+
+        let basis = OrthonormalBasis::new(hit.normal);
+        let wo = basis.transform_to_basis(-r_in.direction.unit());
+        let coat_probability = self.coat_probability(&wo);
+        let coat = CoatPdf {
+            distribution: self.distribution,
+            basis,
+            wo,
+        };
+
+        let base = self.base.scatter(ctx, r_in, hit)?;
+        match base.pdf_or_ray {
+            PdfOrRay::Pdf(base_pdf) => Some(ScatterResult {
+                // the color depends on the directions, see scattering
+                attenuation: Color::WHITE,
+                pdf_or_ray: PdfOrRay::Pdf(Arc::new(ClearcoatPdf {
+                    coat_probability,
+                    coat,
+                    base: Some(base_pdf),
+                })),
+                walk_step: false,
+            }),
+            // a specular base can't be mixed with the coat, pick one of the layers
+            PdfOrRay::Ray(scattered) => {
+                if ctx.random.rand() < coat_probability {
+                    Some(ScatterResult {
+                        attenuation: Color::WHITE,
+                        pdf_or_ray: PdfOrRay::Pdf(Arc::new(ClearcoatPdf {
+                            coat_probability,
+                            coat,
+                            base: None,
+                        })),
+                        walk_step: false,
+                    })
+                } else {
+                    let wi = coat.basis.transform_to_basis(scattered.direction.unit());
+                    let attenuation =
+                        base.attenuation * self.transmittance(&wo, &wi) / (1.0 - coat_probability);
+                    Some(ScatterResult {
+                        attenuation,
+                        pdf_or_ray: PdfOrRay::Ray(scattered),
+                        walk_step: base.walk_step,
+                    })
+                }
+            }
+        }
+    }
+
+    fn scattering(
+        &self,
+        ctx: &RenderContext,
+        r_in: &Ray,
+        hit: &HitRecord,
+        scattered: &Ray,
+        attenuation: Color,
+    ) -> Color {
+        if !hit.front_face {
+            return self.base.scattering(ctx, r_in, hit, scattered, attenuation);
+        }
+
+        let basis = OrthonormalBasis::new(hit.normal);
+        let wo = basis.transform_to_basis(-r_in.direction.unit());
+        let wi = basis.transform_to_basis(scattered.direction.unit());
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return Color::BLACK;
+        }
+
+        let h = (wo + wi).unit();
+        let coat =
+            self.fresnel(wo.dot(&h)) * self.distribution.d(&h) * self.distribution.g(&wo, &wi)
+                / (4.0 * wo.z);
+
+        // specular bases scatter nothing towards a given direction
+        let base = match self.base.scatter(ctx, r_in, hit) {
+            Some(ScatterResult {
+                attenuation,
+                pdf_or_ray: PdfOrRay::Pdf(_),
+                ..
+            }) => {
+                self.base.scattering(ctx, r_in, hit, scattered, attenuation)
+                    * self.transmittance(&wo, &wi)
+            }
+            _ => Color::BLACK,
+        };
+        Color::WHITE * coat + base
+    }
+
+    fn is_dispersive(&self) -> bool {
+        self.base.is_dispersive()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Samples the reflection on the coat through the visible normals of its microfacet
+/// distribution.
+struct CoatPdf {
+    distribution: TrowbridgeReitz,
+    basis: OrthonormalBasis,
+    /// Direction towards the viewer in the local frame
+    wo: Vector3,
+}
+
+impl CoatPdf {
+    fn value(&self, direction: &Vector3) -> f64 {
+        let wi = self.basis.transform_to_basis(direction.unit());
+        if wi.z <= 0.0 {
+            return 0.0;
+        }
+        let h = (self.wo + wi).unit();
+        self.distribution.visible_normal_pdf(&self.wo, &h) / (4.0 * self.wo.dot(&h))
+    }
+
+    fn generate(&self, ctx: &RenderContext) -> Vector3 {
+        let h = self
+            .distribution
+            .sample_visible_normal(&self.wo, ctx.random.rand_2d());
+        let wi = 2.0 * self.wo.dot(&h) * h - self.wo;
+        self.basis.transform_to_local(wi)
+    }
+}
+
+/// Mixes the coat and the base PDFs. Without a base PDF, when the base is specular
+/// and was not picked, the coat is only sampled with `coat_probability`, which the
+/// values account for.
+struct ClearcoatPdf {
+    coat_probability: f64,
+    coat: CoatPdf,
+    base: Option<Arc<dyn ProbabilityDensityFunction>>,
+}
+
+impl ProbabilityDensityFunction for ClearcoatPdf {
+    fn value(&self, ctx: &RenderContext, direction: &Vector3) -> f64 {
+        let base = match &self.base {
+            Some(base) => (1.0 - self.coat_probability) * base.value(ctx, direction),
+            None => 0.0,
+        };
+        self.coat_probability * self.coat.value(direction) + base
+    }
+
+    fn generate(&self, ctx: &RenderContext) -> Vector3 {
+        match &self.base {
+            Some(base) if ctx.random.rand() >= self.coat_probability => base.generate(ctx),
+            _ => self.coat.generate(ctx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        SeededRandom,
+        material::{Lambertian, Metal},
+    };
+
+    use super::*;
+
+    /// Albedo of `material` lit from straight above, seen from `direction`.
+    fn albedo(material: Arc<dyn Material>, direction: Vector3) -> Color {
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let hit = HitRecord {
+            pt: Vector3::ZERO,
+            normal: Vector3::new(0.0, 0.0, 1.0),
+            t: 1.0,
+            u: 0.0,
+            v: 0.0,
+            front_face: true,
+            material: material.clone(),
+        };
+        let r_in = Ray::new(-direction, direction);
+        let n = 50_000;
+        let mut sum = Color::BLACK;
+        for _ in 0..n {
+            let Some(result) = material.scatter(&ctx, &r_in, &hit) else {
+                continue;
+            };
+            match result.pdf_or_ray {
+                PdfOrRay::Ray(_) => sum += result.attenuation,
+                PdfOrRay::Pdf(pdf) => {
+                    let scattered = Ray::new(hit.pt, pdf.generate(&ctx));
+                    let pdf_value = pdf.value(&ctx, &scattered.direction);
+                    if pdf_value > 0.0 {
+                        sum +=
+                            material.scattering(&ctx, &r_in, &hit, &scattered, result.attenuation)
+                                / pdf_value;
+                    }
+                }
+            }
+        }
+        sum / n as f64
+    }
+
+    #[test]
+    fn coat_reflects_more_at_grazing_angles() {
+        // over a black base only the coat reflects
+        let black = Arc::new(Lambertian::new_from_color(Color::BLACK));
+        let coat: Arc<dyn Material> = Arc::new(Clearcoat::new(black, 1.5).with_roughness(0.2));
+        let facing = albedo(coat.clone(), Vector3::new(0.0, 0.0, -1.0)).g;
+        let grazing = albedo(coat, Vector3::new(0.0, 1.0, -0.1)).g;
+        assert!((facing - 0.04).abs() < 0.01, "{facing}");
+        assert!(grazing > 0.3, "{grazing}");
+    }
+
+    #[test]
+    fn coat_takes_its_share_from_the_base() {
+        for base in [
+            Arc::new(Lambertian::new_from_color(Color::WHITE)) as Arc<dyn Material>,
+            Arc::new(Metal::new(Color::WHITE, 0.0)),
+        ] {
+            let coat: Arc<dyn Material> = Arc::new(Clearcoat::new(base, 1.5));
+            let albedo = albedo(coat, Vector3::new(0.0, 0.6, -0.8)).g;
+            // 4% reflected by the coat, of the rest about 5% reflected on the way out
+            assert!(albedo > 0.85 && albedo <= 1.01, "{albedo}");
+        }
+    }
+}
@@ -1,21 +1,182 @@
 use core::f64;
+use std::{any::Any, str::FromStr};
 
 use crate::{
     Color, Ray, RenderContext,
-    material::{Material, PdfOrRay, ScatterResult},
+    material::{
+        Material, PdfOrRay, ScatterResult,
+        microfacet::{TrowbridgeReitz, fresnel_dielectric},
+    },
     object::HitRecord,
+    spectrum::REFERENCE_WAVELENGTH,
+    utils::OrthonormalBasis,
 };
 
+/// Refractive index of a dielectric, either constant or varying with the wavelength
+/// of light, which disperses white light into its colors.
+///
+/// Wavelengths are in nanometers, the coefficients of the formulas use micrometers
+/// like published glass data.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::material::RefractiveIndex;
+///
+/// let bk7 = RefractiveIndex::BK7;
+/// assert!((bk7.at(587.56) - 1.5168).abs() < 1e-4);
+/// // blue light bends more than red light
+/// assert!(bk7.at(450.0) > bk7.at(650.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RefractiveIndex {
+    /// Same index for all wavelengths
+    Constant(f64),
+    /// Cauchy's equation, n = a + b / λ²
+    Cauchy { a: f64, b: f64 },
+    /// Sellmeier equation, n² = 1 + Σ bᵢ λ² / (λ² - cᵢ)
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl RefractiveIndex {
+    /// Schott N-BK7, a common optical crown glass
+    pub const BK7: RefractiveIndex = RefractiveIndex::Sellmeier {
+        b: [1.03961212, 0.231792344, 1.01046945],
+        c: [0.00600069867, 0.0200179144, 103.560653],
+    };
+
+    /// Schott N-SF11, a dense flint glass with strong dispersion
+    pub const SF11: RefractiveIndex = RefractiveIndex::Sellmeier {
+        b: [1.73759695, 0.313747346, 1.89878101],
+        c: [0.013188707, 0.0623068142, 155.23629],
+    };
+
+    /// Diamond
+    pub const DIAMOND: RefractiveIndex = RefractiveIndex::Sellmeier {
+        b: [0.3306, 4.3356, 0.0],
+        c: [0.030625, 0.011236, 0.0],
+    };
+
+    /// Returns the refractive index for light of `wavelength` nanometers.
+    pub fn at(&self, wavelength: f64) -> f64 {
+        let micrometers = wavelength / 1000.0;
+        let lambda2 = micrometers * micrometers;
+        match self {
+            RefractiveIndex::Constant(n) => *n,
+            RefractiveIndex::Cauchy { a, b } => a + b / lambda2,
+            RefractiveIndex::Sellmeier { b, c } => (1.0
+                + (0..3)
+                    .map(|i| b[i] * lambda2 / (lambda2 - c[i]))
+                    .sum::<f64>())
+            .sqrt(),
+        }
+    }
+
+    /// Returns true if the index varies with the wavelength.
+    pub fn is_dispersive(&self) -> bool {
+        !matches!(self, RefractiveIndex::Constant(_))
+    }
+}
+
+impl FromStr for RefractiveIndex {
+    type Err = String;
+
+    /// Parses the name of one of the built-in glasses.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bk7" => Ok(RefractiveIndex::BK7),
+            "sf11" => Ok(RefractiveIndex::SF11),
+            "diamond" => Ok(RefractiveIndex::DIAMOND),
+            _ => Err(format!("unknown glass \"{s}\"")),
+        }
+    }
+}
+
+/// Smallest transmittance of an absorbing dielectric's tint, black would absorb
+/// everything at any distance.
+const MIN_TINT: f64 = 1e-6;
+
+/// Transparent material such as glass or water.
+///
+/// Light going through the material can be absorbed following the Beer-Lambert law,
+/// which tints thick parts more than thin ones, and the surface can be rough like
+/// frosted glass, in which case reflections and refractions follow a GGX microfacet
+/// distribution.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{
+///     Color,
+///     material::{Dielectric, RefractiveIndex},
+/// };
+///
+/// let glass = Dielectric::new(1.5);
+/// // green bottle glass, letting 60% of the green light through 2 units of glass
+/// let bottle = Dielectric::new(1.5).with_absorption(Color::new(0.3, 0.6, 0.4), 2.0);
+/// let frosted = Dielectric::new(1.5).with_roughness(0.3);
+/// // splits white light into colors in spectral renders
+/// let prism = Dielectric::new_from_index(RefractiveIndex::SF11);
+/// ```
 #[derive(Debug)]
 pub struct Dielectric {
     /// Refractive index in vacuum or air, or the ratio of the material's refractive index over
     /// the refractive index of the enclosing media
-    refraction_index: f64,
+    refraction_index: RefractiveIndex,
+    /// Absorption coefficient per unit of distance inside the material
+    absorption: Color,
+    roughness: f64,
+    /// Microfacet distribution of rough surfaces, None for smooth ones
+    distribution: Option<TrowbridgeReitz>,
 }
 
 impl Dielectric {
     pub fn new(refraction_index: f64) -> Self {
-        Self { refraction_index }
+        Self::new_from_index(RefractiveIndex::Constant(refraction_index))
+    }
+
+    /// Creates a dielectric whose refractive index may depend on the wavelength.
+    /// Spectral paths see the dispersion, RGB paths use the index at
+    /// [`REFERENCE_WAVELENGTH`].
+    pub fn new_from_index(refraction_index: RefractiveIndex) -> Self {
+        Self {
+            refraction_index,
+            absorption: Color::BLACK,
+            roughness: 0.0,
+            distribution: None,
+        }
+    }
+
+    /// Makes the material absorb light, `tint` being the fraction of each color
+    /// channel that goes through `distance` inside the material.
+    pub fn with_absorption(mut self, tint: Color, distance: f64) -> Self {
+        let coefficient = |transmittance: f64| -transmittance.clamp(MIN_TINT, 1.0).ln() / distance;
+        self.absorption = Color::new(
+            coefficient(tint.r),
+            coefficient(tint.g),
+            coefficient(tint.b),
+        );
+        self
+    }
+
+    /// Makes the surface rough, from smooth at 0 to very rough at 1.
+    pub fn with_roughness(mut self, roughness: f64) -> Self {
+        self.roughness = roughness;
+        self.distribution = (roughness > 0.0).then(|| TrowbridgeReitz::from_roughness(roughness));
+        self
+    }
+
+    pub fn refraction_index(&self) -> RefractiveIndex {
+        self.refraction_index
+    }
+
+    /// Returns the absorption coefficient per unit of distance inside the material.
+    pub fn absorption(&self) -> Color {
+        self.absorption
+    }
+
+    pub fn roughness(&self) -> f64 {
+        self.roughness
     }
 
     /// Use Schlick's approximation for reflectance.
@@ -24,16 +185,24 @@ impl Dielectric {
         let r0 = r0 * r0;
         r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
     }
-}
 
-impl Material for Dielectric {
-    fn scatter(&self, ctx: &RenderContext, r_in: &Ray, hit: &HitRecord) -> Option<ScatterResult> {
-        let ri = if hit.front_face {
-            1.0 / self.refraction_index
-        } else {
-            self.refraction_index
-        };
+    /// Returns the fraction of light not absorbed along `r_in` before it reached
+    /// `hit`. Only rays leaving the material, hitting the back of the surface, went
+    /// through it.
+    fn transmittance(&self, r_in: &Ray, hit: &HitRecord) -> Color {
+        if hit.front_face {
+            return Color::WHITE;
+        }
+        let distance = hit.t * r_in.direction.length();
+        Color::new(
+            (-self.absorption.r * distance).exp(),
+            (-self.absorption.g * distance).exp(),
+            (-self.absorption.b * distance).exp(),
+        )
+    }
 
+    /// Samples a smooth surface, which reflects or refracts in a single direction.
+    fn scatter_smooth(&self, ctx: &RenderContext, r_in: &Ray, hit: &HitRecord, ri: f64) -> Ray {
         let unit_direction = r_in.direction.unit();
         let cos_theta = (-unit_direction).dot(&hit.normal).min(1.0);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
@@ -44,10 +213,168 @@ impl Material for Dielectric {
         } else {
             unit_direction.refract(hit.normal, ri)
         };
+        Ray::new_with_time(hit.pt, direction, r_in.time)
+    }
+
+    /// Samples a rough surface: picks a microfacet visible from the incoming ray, then
+    /// reflects or refracts on it according to its Fresnel reflectance. Returns the
+    /// ray and the fraction of light that isn't shadowed by other microfacets, or
+    /// None if the ray ends up on the wrong side of the surface.
+    fn scatter_rough(
+        &self,
+        ctx: &RenderContext,
+        r_in: &Ray,
+        hit: &HitRecord,
+        ri: f64,
+        distribution: &TrowbridgeReitz,
+    ) -> Option<(Ray, f64)> {
+        let basis = OrthonormalBasis::new(hit.normal);
+        let wo = basis.transform_to_basis(-r_in.direction.unit());
+        let h = distribution.sample_visible_normal(&wo, ctx.random.rand_2d());
+        let cos_theta = wo.dot(&h);
+
+        let wi = if fresnel_dielectric(cos_theta, ri) > ctx.random.rand() {
+            let wi = 2.0 * cos_theta * h - wo;
+            if wi.z <= 0.0 {
+                return None;
+            }
+            wi
+        } else {
+            let wi = (-wo).refract(h, ri);
+            if wi.z >= 0.0 {
+                return None;
+            }
+            wi
+        };
+
+        // sampling visible normals and choosing by Fresnel leaves the masking of the
+        // outgoing direction, G2 / G1
+        let weight = distribution.g(&wo, &wi) / distribution.g1(&wo);
+        let direction = basis.transform_to_local(wi);
+        Some((Ray::new_with_time(hit.pt, direction, r_in.time), weight))
+    }
+}
+
+impl Material for Dielectric {
+    fn scatter(&self, ctx: &RenderContext, r_in: &Ray, hit: &HitRecord) -> Option<ScatterResult> {
+        let refraction_index = self
+            .refraction_index
+            .at(r_in.wavelength.unwrap_or(REFERENCE_WAVELENGTH));
+        let ri = if hit.front_face {
+            1.0 / refraction_index
+        } else {
+            refraction_index
+        };
+        let transmittance = self.transmittance(r_in, hit);
+
+        let (scattered, attenuation) = match &self.distribution {
+            None => (self.scatter_smooth(ctx, r_in, hit, ri), transmittance),
+            Some(distribution) => {
+                let (scattered, weight) = self.scatter_rough(ctx, r_in, hit, ri, distribution)?;
+                (scattered, transmittance * weight)
+            }
+        };
 
         Some(ScatterResult {
-            attenuation: Color::WHITE,
-            pdf_or_ray: PdfOrRay::Ray(Ray::new_with_time(hit.pt, direction, r_in.time)),
+            attenuation,
+            pdf_or_ray: PdfOrRay::Ray(scattered),
+            walk_step: false,
         })
     }
+
+    fn is_dispersive(&self) -> bool {
+        self.refraction_index.is_dispersive()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{SeededRandom, Vector3};
+
+    use super::*;
+
+    fn hit(dielectric: Dielectric, t: f64, front_face: bool) -> HitRecord {
+        HitRecord {
+            pt: Vector3::ZERO,
+            normal: Vector3::new(0.0, 0.0, 1.0),
+            t,
+            u: 0.0,
+            v: 0.0,
+            front_face,
+            material: Arc::new(dielectric),
+        }
+    }
+
+    #[test]
+    fn absorption_follows_distance_inside() {
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let tint = Color::new(0.2, 0.5, 1.0);
+        let r_in = Ray::new(Vector3::new(0.0, 0.0, 4.0), Vector3::new(0.0, 0.0, -1.0));
+
+        // entering the material absorbs nothing
+        let entering = hit(Dielectric::new(1.5).with_absorption(tint, 2.0), 4.0, true);
+        let attenuation = entering
+            .material
+            .scatter(&ctx, &r_in, &entering)
+            .unwrap()
+            .attenuation;
+        assert_eq!(attenuation, Color::WHITE);
+
+        // leaving it after 4 units, twice the tint distance, applies the tint twice
+        let leaving = hit(Dielectric::new(1.5).with_absorption(tint, 2.0), 4.0, false);
+        let attenuation = leaving
+            .material
+            .scatter(&ctx, &r_in, &leaving)
+            .unwrap()
+            .attenuation;
+        assert!((attenuation.r - 0.04).abs() < 1e-9);
+        assert!((attenuation.g - 0.25).abs() < 1e-9);
+        assert!((attenuation.b - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rough_surfaces_keep_most_energy() {
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        // entering glass at 27° mostly refracts, leaving it at 51°, past the critical
+        // angle of 42°, mostly reflects internally
+        for (direction, front_face) in [
+            (Vector3::new(0.0, -0.5, -1.0), true),
+            (Vector3::new(0.0, -1.0, -0.8), false),
+        ] {
+            let r_in = Ray::new(-direction, direction);
+            let hit = hit(Dielectric::new(1.5).with_roughness(0.4), 1.0, front_face);
+            let n = 20_000;
+            let mut energy = 0.0;
+            let mut transmitted = 0;
+            for _ in 0..n {
+                if let Some(result) = hit.material.scatter(&ctx, &r_in, &hit) {
+                    energy += result.attenuation.r;
+                    let PdfOrRay::Ray(scattered) = result.pdf_or_ray else {
+                        panic!("rough dielectrics sample rays");
+                    };
+                    if scattered.direction.z < 0.0 {
+                        transmitted += 1;
+                    }
+                }
+            }
+            let energy = energy / n as f64;
+            assert!(energy <= 1.0 && energy > 0.9, "{front_face} {energy}");
+            let transmitted = transmitted as f64 / n as f64;
+            if front_face {
+                assert!(transmitted > 0.85, "{transmitted}");
+            } else {
+                assert!(transmitted < 0.3, "{transmitted}");
+            }
+        }
+    }
 }
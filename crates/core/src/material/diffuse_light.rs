@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{any::Any, sync::Arc};
 
 use crate::{
     Color, Ray, RenderContext, Vector3,
@@ -41,4 +41,8 @@ impl Material for DiffuseLight {
             Color::BLACK
         }
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
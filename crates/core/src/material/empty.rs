@@ -1,3 +1,5 @@
+use std::any::Any;
+
 use crate::material::Material;
 
 #[derive(Debug)]
@@ -24,4 +26,8 @@ impl Material for EmptyMaterial {
     ) -> Option<super::ScatterResult> {
         None
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
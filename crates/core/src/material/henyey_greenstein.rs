@@ -0,0 +1,95 @@
+use std::f64::consts::{PI, TAU};
+
+use crate::{Vector3, utils::OrthonormalBasis};
+
+/// Smallest asymmetry treated as anisotropic, the sampling formula divides by it.
+const MIN_ANISOTROPY: f64 = 1e-3;
+
+/// The Henyey-Greenstein phase function, the distribution of directions light takes
+/// when it scatters in a participating medium.
+///
+/// `g` is the average cosine between the directions before and after scattering:
+/// positive values scatter forward, negative values back, and 0 is isotropic.
+#[derive(Debug, Clone, Copy)]
+pub struct HenyeyGreenstein {
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    /// Creates the phase function, `g` being clamped inside (-1, 1).
+    pub fn new(g: f64) -> Self {
+        Self {
+            g: g.clamp(-0.99, 0.99),
+        }
+    }
+
+    pub fn g(&self) -> f64 {
+        self.g
+    }
+
+    /// Returns the density of scattering with `cos_theta` between the direction of
+    /// propagation before and after scattering, per solid angle.
+    pub fn evaluate(&self, cos_theta: f64) -> f64 {
+        let g2 = self.g * self.g;
+        let denominator = 1.0 + g2 - 2.0 * self.g * cos_theta;
+        (1.0 - g2) / (4.0 * PI * denominator * denominator.sqrt())
+    }
+
+    /// Samples the direction of propagation after scattering from the one before,
+    /// `direction`, with density [`HenyeyGreenstein::evaluate`].
+    pub fn sample(&self, direction: Vector3, (u1, u2): (f64, f64)) -> Vector3 {
+        let cos_theta = if self.g.abs() < MIN_ANISOTROPY {
+            1.0 - 2.0 * u1
+        } else {
+            let g2 = self.g * self.g;
+            let s = (1.0 - g2) / (1.0 - self.g + 2.0 * self.g * u1);
+            ((1.0 + g2 - s * s) / (2.0 * self.g)).clamp(-1.0, 1.0)
+        };
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = TAU * u2;
+        OrthonormalBasis::new(direction.unit()).transform_to_local(Vector3::new(
+            sin_theta * phi.cos(),
+            sin_theta * phi.sin(),
+            cos_theta,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Random, SeededRandom};
+
+    use super::*;
+
+    #[test]
+    fn samples_follow_the_asymmetry() {
+        let random = SeededRandom::new(1);
+        let direction = Vector3::new(0.0, 0.6, 0.8);
+        for g in [-0.7, 0.0, 0.3, 0.9] {
+            let phase = HenyeyGreenstein::new(g);
+            let n = 100_000;
+            let mut mean_cos = 0.0;
+            for _ in 0..n {
+                let scattered = phase.sample(direction, random.rand_2d());
+                assert!((scattered.length() - 1.0).abs() < 1e-9);
+                mean_cos += scattered.dot(&direction);
+            }
+            mean_cos /= n as f64;
+            assert!((mean_cos - g).abs() < 0.01, "{g} {mean_cos}");
+        }
+    }
+
+    #[test]
+    fn density_integrates_to_one() {
+        for g in [-0.5, 0.0, 0.8] {
+            let phase = HenyeyGreenstein::new(g);
+            // the density only depends on cos θ, integrate over it with the midpoint rule
+            let n = 100_000;
+            let step = 2.0 / n as f64;
+            let integral: f64 = (0..n)
+                .map(|i| phase.evaluate(-1.0 + (i as f64 + 0.5) * step) * TAU * step)
+                .sum();
+            assert!((integral - 1.0).abs() < 1e-3, "{g} {integral}");
+        }
+    }
+}
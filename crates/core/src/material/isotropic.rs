@@ -1,5 +1,5 @@
 use core::f64;
-use std::sync::Arc;
+use std::{any::Any, sync::Arc};
 
 use crate::{
     Color, Ray, RenderContext, SpherePdf,
@@ -23,6 +23,10 @@ impl Isotropic {
             texture: Arc::new(SolidColor::new(albedo)),
         }
     }
+
+    pub fn texture(&self) -> &Arc<dyn Texture> {
+        &self.texture
+    }
 }
 
 impl Material for Isotropic {
@@ -30,6 +34,7 @@ impl Material for Isotropic {
         Some(ScatterResult {
             attenuation: self.texture.value(hit.u, hit.v, hit.pt),
             pdf_or_ray: PdfOrRay::Pdf(Arc::new(SpherePdf::new())),
+            walk_step: false,
         })
     }
 
@@ -40,6 +45,10 @@ impl Material for Isotropic {
         _hit: &HitRecord,
         _scattered: &Ray,
     ) -> f64 {
-        1.0 / (4.0 / f64::consts::PI)
+        1.0 / (4.0 * f64::consts::PI)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
     }
 }
@@ -1,5 +1,5 @@
 use core::f64;
-use std::sync::Arc;
+use std::{any::Any, sync::Arc};
 
 use crate::{
     Color, CosinePdf, Ray, RenderContext,
@@ -30,6 +30,7 @@ impl Material for Lambertian {
         Some(ScatterResult {
             attenuation: self.texture.value(hit.u, hit.v, hit.pt),
             pdf_or_ray: PdfOrRay::Pdf(Arc::new(CosinePdf::new(hit.normal))),
+            walk_step: false,
         })
     }
 
@@ -47,4 +48,8 @@ impl Material for Lambertian {
             cos_theta / f64::consts::PI
         }
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
@@ -1,3 +1,5 @@
+use std::any::Any;
+
 use crate::{
     Color, Ray, RenderContext, Vector3,
     material::{Material, PdfOrRay, ScatterResult},
@@ -24,6 +26,11 @@ impl Material for Metal {
         Some(ScatterResult {
             attenuation: self.albedo,
             pdf_or_ray: PdfOrRay::Ray(Ray::new_with_time(hit.pt, reflected, r_in.time)),
+            walk_step: false,
         })
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
@@ -0,0 +1,260 @@
+use std::{any::Any, f64::consts::PI, sync::Arc};
+
+use crate::{
+    Color, ProbabilityDensityFunction, Ray, RenderContext, Vector3,
+    material::{
+        Material, PdfOrRay, ScatterResult,
+        microfacet::{TrowbridgeReitz, schlick_fresnel},
+    },
+    object::HitRecord,
+    texture::{SolidColor, Texture},
+    utils::OrthonormalBasis,
+};
+
+/// Reflectance at normal incidence of dielectrics, the non-metallic surfaces.
+const DIELECTRIC_F0: f64 = 0.04;
+
+/// Physically based material following the glTF metallic-roughness model.
+///
+/// The surface is a mix of a diffuse base and a GGX microfacet specular layer with
+/// Smith masking and shadowing and Schlick Fresnel. Metals (`metallic` 1) have no
+/// diffuse base and reflections tinted by the base color, dielectrics (`metallic` 0)
+/// reflect 4% at normal incidence, more at grazing angles. `roughness` goes from a
+/// mirror at 0 to a very rough surface at 1.
+///
+/// Like glTF metallic-roughness textures, the roughness is read from the green
+/// channel of its texture and the metallic factor from the blue channel, so a single
+/// texture can hold both, and gray textures work for either.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{Color, material::MetallicRoughness};
+///
+/// let gold = MetallicRoughness::new_from_values(Color::new(1.0, 0.78, 0.34), 1.0, 0.3);
+/// let plastic = MetallicRoughness::new_from_values(Color::new(0.1, 0.2, 0.8), 0.0, 0.5);
+/// ```
+#[derive(Debug)]
+pub struct MetallicRoughness {
+    base_color: Arc<dyn Texture>,
+    metallic: Arc<dyn Texture>,
+    roughness: Arc<dyn Texture>,
+}
+
+impl MetallicRoughness {
+    pub fn new(
+        base_color: Arc<dyn Texture>,
+        metallic: Arc<dyn Texture>,
+        roughness: Arc<dyn Texture>,
+    ) -> Self {
+        Self {
+            base_color,
+            metallic,
+            roughness,
+        }
+    }
+
+    pub fn new_from_values(base_color: Color, metallic: f64, roughness: f64) -> Self {
+        Self {
+            base_color: Arc::new(SolidColor::new(base_color)),
+            metallic: Arc::new(SolidColor::new(Color::WHITE * metallic)),
+            roughness: Arc::new(SolidColor::new(Color::WHITE * roughness)),
+        }
+    }
+
+    pub fn base_color(&self) -> &Arc<dyn Texture> {
+        &self.base_color
+    }
+
+    /// Returns the texture whose blue channel is the metallic factor.
+    pub fn metallic(&self) -> &Arc<dyn Texture> {
+        &self.metallic
+    }
+
+    /// Returns the texture whose green channel is the roughness.
+    pub fn roughness(&self) -> &Arc<dyn Texture> {
+        &self.roughness
+    }
+
+    /// Returns the lobes of the material at the hit point.
+    fn lobes(&self, hit: &HitRecord) -> Lobes {
+        let base_color = self.base_color.value(hit.u, hit.v, hit.pt);
+        let metallic = self.metallic.value(hit.u, hit.v, hit.pt).b.clamp(0.0, 1.0);
+        let roughness = self.roughness.value(hit.u, hit.v, hit.pt).g;
+        Lobes {
+            diffuse: base_color * (1.0 - metallic),
+            f0: Color::WHITE * (DIELECTRIC_F0 * (1.0 - metallic)) + base_color * metallic,
+            distribution: TrowbridgeReitz::from_roughness(roughness),
+        }
+    }
+}
+
+struct Lobes {
+    diffuse: Color,
+    /// Specular reflectance at normal incidence
+    f0: Color,
+    distribution: TrowbridgeReitz,
+}
+
+impl Lobes {
+    /// Returns the BSDF times the cosine of `wi`, both directions in the local frame
+    /// of the surface.
+    fn evaluate(&self, wo: &Vector3, wi: &Vector3) -> Color {
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return Color::BLACK;
+        }
+        let h = (*wo + *wi).unit();
+        let fresnel = schlick_fresnel(self.f0, wo.dot(&h));
+        // D G F / (4 cos θo cos θi), times cos θi
+        let specular =
+            fresnel * (self.distribution.d(&h) * self.distribution.g(wo, wi) / (4.0 * wo.z));
+        let transmitted = Color::new(1.0 - fresnel.r, 1.0 - fresnel.g, 1.0 - fresnel.b);
+        let diffuse = self.diffuse * transmitted * (wi.z / PI);
+        specular + diffuse
+    }
+
+    /// Returns the probability of sampling the specular lobe, following the share of
+    /// the light it reflects towards `wo`.
+    fn specular_probability(&self, wo: &Vector3) -> f64 {
+        let specular = schlick_fresnel(self.f0, wo.z).luminance();
+        let diffuse = self.diffuse.luminance() * (1.0 - specular);
+        if specular + diffuse <= 0.0 {
+            return 1.0;
+        }
+        (specular / (specular + diffuse)).clamp(0.1, 1.0)
+    }
+}
+
+impl Material for MetallicRoughness {
+    fn scatter(&self, _ctx: &RenderContext, r_in: &Ray, hit: &HitRecord) -> Option<ScatterResult> {
+        let lobes = self.lobes(hit);
+        let basis = OrthonormalBasis::new(hit.normal);
+        let wo = basis.transform_to_basis(-r_in.direction.unit());
+        Some(ScatterResult {
+            // the color depends on the direction, see scattering
+            attenuation: Color::WHITE,
+            pdf_or_ray: PdfOrRay::Pdf(Arc::new(MetallicRoughnessPdf {
+                specular_probability: lobes.specular_probability(&wo),
+                distribution: lobes.distribution,
+                basis,
+                wo,
+            })),
+            walk_step: false,
+        })
+    }
+
+    fn scattering(
+        &self,
+        _ctx: &RenderContext,
+        r_in: &Ray,
+        hit: &HitRecord,
+        scattered: &Ray,
+        _attenuation: Color,
+    ) -> Color {
+        let basis = OrthonormalBasis::new(hit.normal);
+        let wo = basis.transform_to_basis(-r_in.direction.unit());
+        let wi = basis.transform_to_basis(scattered.direction.unit());
+        self.lobes(hit).evaluate(&wo, &wi)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Samples the specular lobe through the visible normals of the microfacet
+/// distribution and the diffuse lobe with a cosine distribution.
+struct MetallicRoughnessPdf {
+    specular_probability: f64,
+    distribution: TrowbridgeReitz,
+    basis: OrthonormalBasis,
+    /// Direction towards the viewer in the local frame
+    wo: Vector3,
+}
+
+impl ProbabilityDensityFunction for MetallicRoughnessPdf {
+    fn value(&self, _ctx: &RenderContext, direction: &Vector3) -> f64 {
+        let wi = self.basis.transform_to_basis(direction.unit());
+        if wi.z <= 0.0 {
+            return 0.0;
+        }
+        let h = (self.wo + wi).unit();
+        let specular = self.distribution.visible_normal_pdf(&self.wo, &h) / (4.0 * self.wo.dot(&h));
+        let diffuse = wi.z / PI;
+        self.specular_probability * specular + (1.0 - self.specular_probability) * diffuse
+    }
+
+    fn generate(&self, ctx: &RenderContext) -> Vector3 {
+        if ctx.random.rand() < self.specular_probability {
+            let h = self
+                .distribution
+                .sample_visible_normal(&self.wo, ctx.random.rand_2d());
+            let wi = 2.0 * self.wo.dot(&h) * h - self.wo;
+            self.basis.transform_to_local(wi)
+        } else {
+            self.basis
+                .transform_to_local(Vector3::random_cosine_direction(&*ctx.random))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::TAU;
+
+    use crate::{Color, Vector3};
+
+    use super::*;
+
+    /// Albedo of the lobes for light arriving from `wo`, estimated by sampling the
+    /// material's own PDF and by sampling the hemisphere uniformly.
+    fn albedo(lobes: Lobes, wo: Vector3) -> (f64, f64) {
+        let ctx = RenderContext {
+            random: Arc::new(crate::SeededRandom::new(1)),
+        };
+        let pdf = MetallicRoughnessPdf {
+            specular_probability: lobes.specular_probability(&wo),
+            distribution: lobes.distribution,
+            basis: OrthonormalBasis::new(Vector3::new(0.0, 0.0, 1.0)),
+            wo,
+        };
+        let n = 200_000;
+        let mut sampled = 0.0;
+        let mut uniform = 0.0;
+        for _ in 0..n {
+            let direction = pdf.generate(&ctx);
+            let pdf_value = pdf.value(&ctx, &direction);
+            if pdf_value > 0.0 {
+                // the basis doesn't have to line up with x and y, only z
+                let wi = pdf.basis.transform_to_basis(direction.unit());
+                sampled += lobes.evaluate(&wo, &wi).r / pdf_value;
+            }
+
+            let (u1, u2) = ctx.random.rand_2d();
+            let r = (1.0 - u1 * u1).sqrt();
+            let wi = Vector3::new(r * (TAU * u2).cos(), r * (TAU * u2).sin(), u1);
+            uniform += lobes.evaluate(&wo, &wi).r * TAU;
+        }
+        (sampled / n as f64, uniform / n as f64)
+    }
+
+    #[test]
+    fn sampling_matches_the_bsdf() {
+        let wo = Vector3::new(0.5, 0.0, 0.75f64.sqrt());
+        for roughness in [0.5, 0.7, 1.0] {
+            for metallic in [0.0, 1.0] {
+                let lobes = Lobes {
+                    diffuse: Color::WHITE * (1.0 - metallic),
+                    f0: Color::WHITE * (DIELECTRIC_F0 * (1.0 - metallic) + metallic),
+                    distribution: TrowbridgeReitz::from_roughness(roughness),
+                };
+                let (sampled, uniform) = albedo(lobes, wo);
+                assert!(sampled <= 1.0, "{roughness} {metallic} {sampled}");
+                assert!(
+                    (sampled - uniform).abs() < 0.02,
+                    "{roughness} {metallic} {sampled} {uniform}"
+                );
+            }
+        }
+    }
+}
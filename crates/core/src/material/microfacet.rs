@@ -0,0 +1,183 @@
+use std::f64::consts::{PI, TAU};
+
+use crate::{Color, Vector3};
+
+/// Smallest alpha used, perfectly smooth surfaces would make the distribution a
+/// delta function.
+const MIN_ALPHA: f64 = 1e-3;
+
+/// The GGX, or Trowbridge-Reitz, distribution of microfacet normals.
+///
+/// All directions are in the local frame of the surface, where the macro surface
+/// normal is +z, and point away from the surface.
+#[derive(Debug, Clone, Copy)]
+pub struct TrowbridgeReitz {
+    alpha: f64,
+}
+
+impl TrowbridgeReitz {
+    /// Creates the distribution for a perceptual roughness in [0, 1], alpha being the
+    /// square of the roughness like in glTF.
+    pub fn from_roughness(roughness: f64) -> Self {
+        let roughness = roughness.clamp(0.0, 1.0);
+        Self {
+            alpha: (roughness * roughness).max(MIN_ALPHA),
+        }
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Returns the density of microfacets with normal `h`, with respect to the area
+    /// of the macro surface.
+    pub fn d(&self, h: &Vector3) -> f64 {
+        if h.z <= 0.0 {
+            return 0.0;
+        }
+        let alpha2 = self.alpha * self.alpha;
+        let denominator = h.z * h.z * (alpha2 - 1.0) + 1.0;
+        alpha2 / (PI * denominator * denominator)
+    }
+
+    /// Smith's auxiliary function, the area of microfacets hidden from `w` per area
+    /// of visible microfacets.
+    fn lambda(&self, w: &Vector3) -> f64 {
+        let cos2 = w.z * w.z;
+        if cos2 <= 0.0 {
+            return f64::INFINITY;
+        }
+        let tan2 = (1.0 - cos2).max(0.0) / cos2;
+        ((1.0 + self.alpha * self.alpha * tan2).sqrt() - 1.0) / 2.0
+    }
+
+    /// Returns the fraction of microfacets visible from `w`.
+    pub fn g1(&self, w: &Vector3) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// Returns the fraction of microfacets visible from both `wo` and `wi`, with
+    /// height-correlated Smith masking and shadowing.
+    pub fn g(&self, wo: &Vector3, wi: &Vector3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Samples a microfacet normal visible from `wo`, which must be above the
+    /// surface, from the distribution of visible normals (Heitz 2018).
+    pub fn sample_visible_normal(&self, wo: &Vector3, (u1, u2): (f64, f64)) -> Vector3 {
+        // stretch the view direction to the configuration of a hemisphere
+        let vh = Vector3::new(self.alpha * wo.x, self.alpha * wo.y, wo.z).unit();
+        let length2 = vh.x * vh.x + vh.y * vh.y;
+        let t1 = if length2 > 0.0 {
+            Vector3::new(-vh.y, vh.x, 0.0) / length2.sqrt()
+        } else {
+            Vector3::new(1.0, 0.0, 0.0)
+        };
+        let t2 = vh.cross(&t1);
+
+        // a point on the projected disc, warped to the visible half
+        let r = u1.sqrt();
+        let phi = TAU * u2;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * r * phi.sin();
+        let nh = p1 * t1 + p2 * t2 + (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt() * vh;
+
+        // unstretch
+        Vector3::new(self.alpha * nh.x, self.alpha * nh.y, nh.z.max(1e-6)).unit()
+    }
+
+    /// Returns the density of [`TrowbridgeReitz::sample_visible_normal`] sampling
+    /// `h` for `wo`, with respect to solid angle.
+    pub fn visible_normal_pdf(&self, wo: &Vector3, h: &Vector3) -> f64 {
+        if wo.z <= 0.0 {
+            return 0.0;
+        }
+        self.g1(wo) * wo.dot(h).max(0.0) * self.d(h) / wo.z
+    }
+}
+
+/// Schlick's approximation of the Fresnel reflectance for reflectance `f0` at
+/// normal incidence and the cosine of the angle of incidence.
+pub fn schlick_fresnel(f0: Color, cos_theta: f64) -> Color {
+    let weight = (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5);
+    f0 * (1.0 - weight) + Color::WHITE * weight
+}
+
+/// Fresnel reflectance of an interface between dielectrics for the cosine of the
+/// angle of incidence and `eta`, the refractive index on the incident side over the
+/// one on the other side. Returns 1 for total internal reflection.
+pub fn fresnel_dielectric(cos_theta_i: f64, eta: f64) -> f64 {
+    let cos_theta_i = cos_theta_i.clamp(0.0, 1.0);
+    let sin2_theta_t = eta * eta * (1.0 - cos_theta_i * cos_theta_i);
+    if sin2_theta_t >= 1.0 {
+        return 1.0;
+    }
+    let cos_theta_t = (1.0 - sin2_theta_t).sqrt();
+    let r_s = (eta * cos_theta_i - cos_theta_t) / (eta * cos_theta_i + cos_theta_t);
+    let r_p = (cos_theta_i - eta * cos_theta_t) / (cos_theta_i + eta * cos_theta_t);
+    (r_s * r_s + r_p * r_p) / 2.0
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use crate::{SeededRandom, Vector3, random::Random};
+
+    use super::{TrowbridgeReitz, fresnel_dielectric};
+
+    #[test]
+    fn fresnel_dielectric_limits() {
+        // 4% at normal incidence for glass, everything at grazing angles
+        assert!((fresnel_dielectric(1.0, 1.0 / 1.5) - 0.04).abs() < 1e-12);
+        assert!((fresnel_dielectric(1.0, 1.5) - 0.04).abs() < 1e-12);
+        assert_eq!(fresnel_dielectric(0.0, 1.0 / 1.5), 1.0);
+        // total internal reflection past the critical angle
+        assert_eq!(fresnel_dielectric(0.5, 1.5), 1.0);
+        assert!(fresnel_dielectric(0.9, 1.5) < 1.0);
+    }
+
+    #[test]
+    fn projected_normals_cover_the_surface() {
+        // ∫ D(h) cos θh dω = 1, estimated by uniform hemisphere sampling
+        let random = SeededRandom::new(1);
+        for roughness in [0.5, 0.75, 1.0] {
+            let distribution = TrowbridgeReitz::from_roughness(roughness);
+            let n = 200_000;
+            let mut sum = 0.0;
+            for _ in 0..n {
+                let (u1, u2) = random.rand_2d();
+                let z = u1;
+                let r = (1.0 - z * z).sqrt();
+                let h = Vector3::new(r * (2.0 * PI * u2).cos(), r * (2.0 * PI * u2).sin(), z);
+                sum += distribution.d(&h) * h.z * 2.0 * PI;
+            }
+            let integral = sum / n as f64;
+            assert!((integral - 1.0).abs() < 0.03, "{roughness} {integral}");
+        }
+    }
+
+    #[test]
+    fn visible_normals_match_their_pdf() {
+        let random = SeededRandom::new(2);
+        let distribution = TrowbridgeReitz::from_roughness(0.5);
+        // looking straight down every normal is visible, at an angle a few are hidden
+        for (wo, min) in [
+            (Vector3::new(0.0, 0.0, 1.0), 0.99),
+            (Vector3::new(0.6, 0.0, 0.8), 0.97),
+        ] {
+            let n = 100_000;
+            let mut sum = 0.0;
+            for _ in 0..n {
+                let h = distribution.sample_visible_normal(&wo, random.rand_2d());
+                assert!(h.z > 0.0 && wo.dot(&h) > 0.0);
+                // D(h) cos θh over the pdf, which integrates to the projected area of
+                // the visible normals if the samples follow the pdf
+                sum += distribution.d(&h) * h.z / distribution.visible_normal_pdf(&wo, &h);
+            }
+            let mean = sum / n as f64;
+            assert!(mean > min && mean < 1.01, "{mean}");
+        }
+    }
+}
@@ -1,20 +1,29 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{any::Any, fmt::Debug, sync::Arc};
 
 use crate::{Color, ProbabilityDensityFunction, Ray, RenderContext, Vector3, object::HitRecord};
 
+pub mod clearcoat;
 pub mod dielectric;
 pub mod diffuse_light;
 pub mod empty;
+pub mod henyey_greenstein;
 pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
+pub mod metallic_roughness;
+pub mod microfacet;
+pub mod subsurface;
 
-pub use dielectric::Dielectric;
+pub use clearcoat::Clearcoat;
+pub use dielectric::{Dielectric, RefractiveIndex};
 pub use diffuse_light::DiffuseLight;
 pub use empty::EmptyMaterial;
+pub use henyey_greenstein::HenyeyGreenstein;
 pub use isotropic::Isotropic;
 pub use lambertian::Lambertian;
 pub use metal::Metal;
+pub use metallic_roughness::MetallicRoughness;
+pub use subsurface::Subsurface;
 
 pub trait Material: Debug + Send + Sync {
     fn scatter(&self, ctx: &RenderContext, r_in: &Ray, hit: &HitRecord) -> Option<ScatterResult>;
@@ -32,6 +41,33 @@ pub trait Material: Debug + Send + Sync {
     ) -> f64 {
         0.0
     }
+
+    /// Returns the fraction of the light arriving along `scattered` that leaves
+    /// towards the origin of `r_in`, per color channel, including the cosine of the
+    /// angle of incidence.
+    ///
+    /// `attenuation` is the one returned by [`Material::scatter`]. The default is
+    /// `attenuation` times [`Material::scattering_pdf`], which suits materials whose
+    /// color doesn't depend on the directions.
+    fn scattering(
+        &self,
+        ctx: &RenderContext,
+        r_in: &Ray,
+        hit: &HitRecord,
+        scattered: &Ray,
+        attenuation: Color,
+    ) -> Color {
+        attenuation * self.scattering_pdf(ctx, r_in, hit, scattered)
+    }
+
+    /// Returns true if the material scatters light differently depending on its
+    /// wavelength, like a dispersive dielectric. Spectral paths only weight their
+    /// light by their wavelength after reaching such a material.
+    fn is_dispersive(&self) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> &dyn Any;
 }
 
 pub enum PdfOrRay {
@@ -42,4 +78,8 @@ pub enum PdfOrRay {
 pub struct ScatterResult {
     pub attenuation: Color,
     pub pdf_or_ray: PdfOrRay,
+    /// Whether the scattered ray is a step of a random walk inside the material,
+    /// like in [`Subsurface`], rather than a bounce. Walk steps don't count towards
+    /// the maximum depth of a path and skip Russian roulette.
+    pub walk_step: bool,
 }
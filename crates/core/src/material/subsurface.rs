@@ -0,0 +1,249 @@
+use std::{any::Any, sync::Arc};
+
+use crate::{
+    Color, CosinePdf, Ray, RenderContext, Vector3,
+    material::{HenyeyGreenstein, Material, PdfOrRay, ScatterResult},
+    object::HitRecord,
+    utils::OrthonormalBasis,
+};
+
+/// Translucent material where light enters the object and scatters inside before
+/// leaving, like wax, skin or marble.
+///
+/// The inside of the object is a medium with per-channel scattering and absorption
+/// coefficients, the fraction of light scattered and absorbed per unit of distance,
+/// and a Henyey-Greenstein phase function. Light goes through the surface diffusely
+/// in both directions and follows a random walk in the medium: each time the path
+/// reaches the surface from inside, the walk samples whether it scattered before,
+/// the same way [`ConstantMedium`](crate::object::ConstantMedium) samples its volume.
+///
+/// The object must be closed. The steps of the walk carry its channel PDFs and the
+/// camera doesn't count them as bounces, so dense media don't need a high maximum
+/// depth. For a glossy surface, wrap the material in a
+/// [`Clearcoat`](crate::material::Clearcoat).
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{Color, material::Subsurface};
+///
+/// let marble = Subsurface::new(Color::new(8.0, 8.0, 7.0), Color::new(0.01, 0.02, 0.05));
+/// let skin = Subsurface::new(Color::new(5.0, 2.0, 1.0), Color::new(0.1, 0.5, 0.8))
+///     .with_anisotropy(0.8);
+/// ```
+#[derive(Debug)]
+pub struct Subsurface {
+    scattering: Color,
+    absorption: Color,
+    /// Sum of the scattering and absorption coefficients
+    extinction: Color,
+    phase_function: HenyeyGreenstein,
+}
+
+impl Subsurface {
+    pub fn new(scattering: Color, absorption: Color) -> Self {
+        let scattering = Color::new(
+            scattering.r.max(0.0),
+            scattering.g.max(0.0),
+            scattering.b.max(0.0),
+        );
+        let absorption = Color::new(
+            absorption.r.max(0.0),
+            absorption.g.max(0.0),
+            absorption.b.max(0.0),
+        );
+        Self {
+            scattering,
+            absorption,
+            extinction: scattering + absorption,
+            phase_function: HenyeyGreenstein::new(0.0),
+        }
+    }
+
+    /// Sets the asymmetry of the phase function, from back scattering at -1 to
+    /// forward scattering at 1. Skin and most organic materials scatter forward.
+    pub fn with_anisotropy(mut self, g: f64) -> Self {
+        self.phase_function = HenyeyGreenstein::new(g);
+        self
+    }
+
+    pub fn scattering(&self) -> Color {
+        self.scattering
+    }
+
+    pub fn absorption(&self) -> Color {
+        self.absorption
+    }
+
+    /// Returns the asymmetry of the phase function, see [`Subsurface::with_anisotropy`].
+    pub fn anisotropy(&self) -> f64 {
+        self.phase_function.g()
+    }
+
+    /// Continues the walk inside the medium along `r_in`, which reaches the surface
+    /// after `distance`. Returns the distance to the next scattering, if before the
+    /// surface, the weight of the step and the channel PDFs of the walk after it.
+    ///
+    /// The distances follow the extinction of a channel picked in proportion to the
+    /// probability of the walk so far under each channel, so the whole walk is
+    /// weighted against sampling it with any channel and the weights stay bounded
+    /// when the channels differ a lot.
+    fn walk(&self, ctx: &RenderContext, r_in: &Ray, distance: f64) -> (Option<f64>, Color, Color) {
+        let channel_pdfs = r_in.channel_pdfs.unwrap_or(Color::WHITE);
+        let extinction = [self.extinction.r, self.extinction.g, self.extinction.b];
+        let u = ctx.random.rand() * (channel_pdfs.r + channel_pdfs.g + channel_pdfs.b);
+        let channel = if u < channel_pdfs.r {
+            0
+        } else if u < channel_pdfs.r + channel_pdfs.g {
+            1
+        } else {
+            2
+        };
+        let sampled = if extinction[channel] > 0.0 {
+            -(1.0 - ctx.random.rand()).ln() / extinction[channel]
+        } else {
+            f64::INFINITY
+        };
+
+        let transmittance = |distance: f64| {
+            Color::new(
+                (-self.extinction.r * distance).exp(),
+                (-self.extinction.g * distance).exp(),
+                (-self.extinction.b * distance).exp(),
+            )
+        };
+        // the step PDF under each channel, and the contribution of the step
+        let (scattered_at, step_pdfs, contribution) = if sampled < distance {
+            let transmittance = transmittance(sampled);
+            (
+                Some(sampled),
+                self.extinction * transmittance,
+                self.scattering * transmittance,
+            )
+        } else {
+            let transmittance = transmittance(distance);
+            (None, transmittance, transmittance)
+        };
+        let walk_pdfs = channel_pdfs * step_pdfs;
+        let pdf = walk_pdfs.average();
+        (scattered_at, contribution / pdf, walk_pdfs / pdf)
+    }
+}
+
+impl Material for Subsurface {
+    fn scatter(&self, ctx: &RenderContext, r_in: &Ray, hit: &HitRecord) -> Option<ScatterResult> {
+        if hit.front_face {
+            // enter the object
+            let direction = OrthonormalBasis::new(-hit.normal)
+                .transform_to_local(Vector3::random_cosine_direction(&*ctx.random));
+            return Some(ScatterResult {
+                attenuation: Color::WHITE,
+                pdf_or_ray: PdfOrRay::Ray(Ray::new_with_time(hit.pt, direction, r_in.time)),
+                walk_step: false,
+            });
+        }
+
+        let unit_direction = r_in.direction.unit();
+        let (scattered_at, attenuation, channel_pdfs) =
+            self.walk(ctx, r_in, hit.t * r_in.direction.length());
+        match scattered_at {
+            Some(distance) => {
+                let origin = r_in.origin + distance * unit_direction;
+                let direction = self
+                    .phase_function
+                    .sample(unit_direction, ctx.random.rand_2d());
+                let mut scattered = Ray::new_with_time(origin, direction, r_in.time);
+                scattered.channel_pdfs = Some(channel_pdfs);
+                Some(ScatterResult {
+                    attenuation,
+                    pdf_or_ray: PdfOrRay::Ray(scattered),
+                    walk_step: true,
+                })
+            }
+            // leave the object, the normal of a back face points inside
+            None => Some(ScatterResult {
+                attenuation,
+                pdf_or_ray: PdfOrRay::Pdf(Arc::new(CosinePdf::new(-hit.normal))),
+                walk_step: false,
+            }),
+        }
+    }
+
+    fn scattering_pdf(
+        &self,
+        _ctx: &RenderContext,
+        _r_in: &Ray,
+        hit: &HitRecord,
+        scattered: &Ray,
+    ) -> f64 {
+        if hit.front_face {
+            return 0.0;
+        }
+        let cos_theta = -hit.normal.dot(&scattered.direction.unit());
+        cos_theta.max(0.0) / std::f64::consts::PI
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Node, SeededRandom, interval::Interval, object::Sphere};
+
+    use super::*;
+
+    /// Light leaving a unit sphere of `material`, lit by a ray through its center,
+    /// and the average number of steps of the walks.
+    fn walk_sphere(material: Subsurface) -> (Color, f64) {
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let material: Arc<dyn Material> = Arc::new(material);
+        let sphere = Sphere::new(Vector3::ZERO, 1.0, material.clone());
+        let n = 20_000;
+        let mut sum = Color::BLACK;
+        let mut steps = 0;
+        for _ in 0..n {
+            let mut ray = Ray::new(Vector3::new(0.0, 0.0, -2.0), Vector3::new(0.0, 0.0, 1.0));
+            let mut throughput = Color::WHITE;
+            loop {
+                // like in the camera, steps starting right by the surface can miss it
+                let Some(hit) = sphere.hit(&ctx, &ray, Interval::new(0.001, f64::INFINITY)) else {
+                    break;
+                };
+                let result = material.scatter(&ctx, &ray, &hit).unwrap();
+                throughput = throughput * result.attenuation;
+                steps += 1;
+                match result.pdf_or_ray {
+                    PdfOrRay::Ray(scattered) => ray = scattered,
+                    PdfOrRay::Pdf(_) => break,
+                }
+            }
+            sum += throughput;
+        }
+        (sum / n as f64, steps as f64 / n as f64)
+    }
+
+    #[test]
+    fn walks_without_absorption_keep_all_light() {
+        let (light, _) = walk_sphere(
+            Subsurface::new(Color::new(1.0, 5.0, 20.0), Color::BLACK).with_anisotropy(0.5),
+        );
+        for channel in [light.r, light.g, light.b] {
+            assert!((channel - 1.0).abs() < 0.03, "{light:?}");
+        }
+    }
+
+    #[test]
+    fn absorption_tints_the_light() {
+        let (light, steps) = walk_sphere(Subsurface::new(
+            Color::new(5.0, 5.0, 5.0),
+            Color::new(0.5, 0.1, 0.0),
+        ));
+        assert!(light.r < light.g && light.g < light.b, "{light:?}");
+        assert!((light.b - 1.0).abs() < 0.03, "{light:?}");
+        assert!(steps > 5.0, "{steps}");
+    }
+}
@@ -18,7 +18,7 @@ use std::ops::{Index, Mul};
 ///     [0.0, 0.0, 1.0],
 /// ]);
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Matrix3x3 {
     /// Internal storage for the 3x3 matrix in row-major order.
     /// `matrix[row][col]` accesses the element at the given row and column.
@@ -26,6 +26,11 @@ pub struct Matrix3x3 {
 }
 
 impl Matrix3x3 {
+    /// The identity matrix, which leaves vectors unchanged.
+    pub const IDENTITY: Matrix3x3 = Matrix3x3 {
+        matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    };
+
     /// Creates a new 3x3 matrix from a 2D array.
     ///
     /// # Arguments
@@ -46,6 +51,113 @@ impl Matrix3x3 {
     pub fn new(matrix: [[f64; 3]; 3]) -> Self {
         Self { matrix }
     }
+
+    /// Creates the rotation by `angle` degrees around `axis`, counterclockwise when
+    /// looking down the axis towards the origin, with Rodrigues' rotation formula.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::{Matrix3x3, Vector3};
+    ///
+    /// let rotation = Matrix3x3::rotation(Vector3::new(0.0, 0.0, 1.0), 90.0);
+    /// let v = &rotation * Vector3::new(1.0, 0.0, 0.0);
+    /// assert!((v - Vector3::new(0.0, 1.0, 0.0)).length() < 1e-12);
+    /// ```
+    pub fn rotation(axis: Vector3, angle: f64) -> Self {
+        let radians = angle.to_radians();
+        let sin_theta = radians.sin();
+        let cos_theta = radians.cos();
+        let one_minus_cos = 1.0 - cos_theta;
+        let Vector3 { x, y, z } = axis.unit();
+
+        Self::new([
+            [
+                cos_theta + x * x * one_minus_cos,
+                x * y * one_minus_cos - z * sin_theta,
+                x * z * one_minus_cos + y * sin_theta,
+            ],
+            [
+                y * x * one_minus_cos + z * sin_theta,
+                cos_theta + y * y * one_minus_cos,
+                y * z * one_minus_cos - x * sin_theta,
+            ],
+            [
+                z * x * one_minus_cos - y * sin_theta,
+                z * y * one_minus_cos + x * sin_theta,
+                cos_theta + z * z * one_minus_cos,
+            ],
+        ])
+    }
+
+    /// Returns the matrix with its rows and columns swapped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::Matrix3x3;
+    ///
+    /// let m = Matrix3x3::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+    /// assert_eq!(m.transpose()[0], [1.0, 4.0, 7.0]);
+    /// ```
+    pub fn transpose(&self) -> Self {
+        let m = &self.matrix;
+        Self::new([
+            [m[0][0], m[1][0], m[2][0]],
+            [m[0][1], m[1][1], m[2][1]],
+            [m[0][2], m[1][2], m[2][2]],
+        ])
+    }
+
+    /// Returns the determinant, negative when the matrix mirrors space.
+    pub fn determinant(&self) -> f64 {
+        let m = &self.matrix;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// Returns the inverse of the matrix, or `None` if it flattens space and has no
+    /// inverse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::Matrix3x3;
+    ///
+    /// let m = Matrix3x3::new([[2.0, 0.0, 0.0], [0.0, 4.0, 0.0], [0.0, 0.0, 1.0]]);
+    /// let inverse = m.inverse().unwrap();
+    /// assert_eq!(inverse[1][1], 0.25);
+    /// assert!(Matrix3x3::new([[1.0, 0.0, 0.0]; 3]).inverse().is_none());
+    /// ```
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant.abs() < 1e-12 {
+            return None;
+        }
+        let m = &self.matrix;
+        // transpose of the cofactor matrix over the determinant
+        let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+            (m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]) / determinant
+        };
+        Some(Self::new([
+            [
+                cofactor(1, 2, 1, 2),
+                -cofactor(0, 2, 1, 2),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                -cofactor(1, 2, 0, 2),
+                cofactor(0, 2, 0, 2),
+                -cofactor(0, 1, 0, 2),
+            ],
+            [
+                cofactor(1, 2, 0, 1),
+                -cofactor(0, 2, 0, 1),
+                cofactor(0, 1, 0, 1),
+            ],
+        ]))
+    }
 }
 
 /// Allows indexing into the matrix to access rows.
@@ -96,3 +208,231 @@ impl Mul<Vector3> for &Matrix3x3 {
         )
     }
 }
+
+/// Implements the product of two 3x3 matrices, the transformation applying `other`
+/// then `self`.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{Matrix3x3, Vector3};
+///
+/// let scale = Matrix3x3::new([[2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.0]]);
+/// let rotation = Matrix3x3::rotation(Vector3::new(0.0, 0.0, 1.0), 90.0);
+/// let v = &(&rotation * &scale) * Vector3::new(1.0, 0.0, 0.0);
+/// assert!((v - Vector3::new(0.0, 2.0, 0.0)).length() < 1e-12);
+/// ```
+impl Mul<&Matrix3x3> for &Matrix3x3 {
+    type Output = Matrix3x3;
+
+    fn mul(self, other: &Matrix3x3) -> Self::Output {
+        let mut matrix = [[0.0; 3]; 3];
+        for (row, values) in matrix.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = (0..3)
+                    .map(|k| self.matrix[row][k] * other.matrix[k][col])
+                    .sum();
+            }
+        }
+        Matrix3x3::new(matrix)
+    }
+}
+
+/// A 4x4 matrix for affine transformations in 3D space, in row-major order.
+///
+/// The upper left 3x3 block is the linear part of the transformation and the last
+/// column the translation, so points transform with the translation and directions
+/// without it. The last row is expected to be `[0, 0, 0, 1]`.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{Matrix4x4, Vector3};
+///
+/// let transform = &Matrix4x4::translation(Vector3::new(1.0, 0.0, 0.0))
+///     * &Matrix4x4::scaling(Vector3::new(2.0, 2.0, 2.0));
+/// let pt = transform.transform_point(Vector3::new(1.0, 1.0, 1.0));
+/// assert_eq!((pt.x, pt.y, pt.z), (3.0, 2.0, 2.0));
+/// let v = transform.transform_vector(Vector3::new(1.0, 1.0, 1.0));
+/// assert_eq!((v.x, v.y, v.z), (2.0, 2.0, 2.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix4x4 {
+    /// `matrix[row][col]` accesses the element at the given row and column.
+    matrix: [[f64; 4]; 4],
+}
+
+impl Matrix4x4 {
+    /// The identity matrix, which leaves points and vectors unchanged.
+    pub const IDENTITY: Matrix4x4 = Matrix4x4 {
+        matrix: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    };
+
+    /// Creates a new 4x4 matrix from a 2D array in row-major order.
+    pub const fn new(matrix: [[f64; 4]; 4]) -> Self {
+        Self { matrix }
+    }
+
+    /// Creates the transformation applying `linear` then moving by `translation`.
+    pub fn from_linear(linear: &Matrix3x3, translation: Vector3) -> Self {
+        let [r0, r1, r2] = [linear[0], linear[1], linear[2]];
+        Self::new([
+            [r0[0], r0[1], r0[2], translation.x],
+            [r1[0], r1[1], r1[2], translation.y],
+            [r2[0], r2[1], r2[2], translation.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Creates the transformation moving points by `offset`.
+    pub fn translation(offset: Vector3) -> Self {
+        Self::from_linear(&Matrix3x3::IDENTITY, offset)
+    }
+
+    /// Creates the transformation scaling each axis by a component of `factors`.
+    pub fn scaling(factors: Vector3) -> Self {
+        Self::from_linear(
+            &Matrix3x3::new([
+                [factors.x, 0.0, 0.0],
+                [0.0, factors.y, 0.0],
+                [0.0, 0.0, factors.z],
+            ]),
+            Vector3::ZERO,
+        )
+    }
+
+    /// Creates the rotation by `angle` degrees around `axis`, see
+    /// [`Matrix3x3::rotation`].
+    pub fn rotation(axis: Vector3, angle: f64) -> Self {
+        Self::from_linear(&Matrix3x3::rotation(axis, angle), Vector3::ZERO)
+    }
+
+    /// Creates the reflection across the plane through the origin with the given
+    /// `normal`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::{Matrix4x4, Vector3};
+    ///
+    /// let mirror = Matrix4x4::reflection(Vector3::new(1.0, 0.0, 0.0));
+    /// let pt = mirror.transform_point(Vector3::new(2.0, 3.0, 4.0));
+    /// assert_eq!((pt.x, pt.y, pt.z), (-2.0, 3.0, 4.0));
+    /// ```
+    pub fn reflection(normal: Vector3) -> Self {
+        let n = normal.unit();
+        let n = [n.x, n.y, n.z];
+        let mut linear = [[0.0; 3]; 3];
+        for (row, values) in linear.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                let identity = if row == col { 1.0 } else { 0.0 };
+                *value = identity - 2.0 * n[row] * n[col];
+            }
+        }
+        Self::from_linear(&Matrix3x3::new(linear), Vector3::ZERO)
+    }
+
+    /// Returns the upper left 3x3 block, the transformation of directions.
+    pub fn linear_part(&self) -> Matrix3x3 {
+        let m = &self.matrix;
+        Matrix3x3::new([
+            [m[0][0], m[0][1], m[0][2]],
+            [m[1][0], m[1][1], m[1][2]],
+            [m[2][0], m[2][1], m[2][2]],
+        ])
+    }
+
+    /// Returns the last column, where the transformation moves the origin.
+    pub fn translation_part(&self) -> Vector3 {
+        Vector3::new(self.matrix[0][3], self.matrix[1][3], self.matrix[2][3])
+    }
+
+    /// Transforms a point, applying the translation.
+    pub fn transform_point(&self, pt: Vector3) -> Vector3 {
+        &self.linear_part() * pt + self.translation_part()
+    }
+
+    /// Transforms a direction, ignoring the translation.
+    pub fn transform_vector(&self, v: Vector3) -> Vector3 {
+        &self.linear_part() * v
+    }
+
+    /// Returns the inverse of the matrix, or `None` if it flattens space and has no
+    /// inverse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::{Matrix4x4, Vector3};
+    ///
+    /// let transform = &Matrix4x4::translation(Vector3::new(0.0, 5.0, 0.0))
+    ///     * &Matrix4x4::rotation(Vector3::new(1.0, 0.0, 0.0), 30.0);
+    /// let inverse = transform.inverse().unwrap();
+    /// let pt = Vector3::new(1.0, 2.0, 3.0);
+    /// let back = inverse.transform_point(transform.transform_point(pt));
+    /// assert!((back - pt).length() < 1e-12);
+    /// assert!(Matrix4x4::scaling(Vector3::new(1.0, 0.0, 1.0)).inverse().is_none());
+    /// ```
+    pub fn inverse(&self) -> Option<Self> {
+        // Gauss-Jordan elimination with partial pivoting
+        let mut m = self.matrix;
+        let mut inverse = Self::IDENTITY.matrix;
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|&a, &b| m[a][col].abs().total_cmp(&m[b][col].abs()))?;
+            if m[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            m.swap(col, pivot);
+            inverse.swap(col, pivot);
+
+            let scale = 1.0 / m[col][col];
+            for k in 0..4 {
+                m[col][k] *= scale;
+                inverse[col][k] *= scale;
+            }
+            for row in 0..4 {
+                if row == col {
+                    continue;
+                }
+                let factor = m[row][col];
+                for k in 0..4 {
+                    m[row][k] -= factor * m[col][k];
+                    inverse[row][k] -= factor * inverse[col][k];
+                }
+            }
+        }
+        Some(Self::new(inverse))
+    }
+}
+
+/// Allows indexing into the matrix to access rows.
+impl Index<usize> for Matrix4x4 {
+    type Output = [f64; 4];
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.matrix[index]
+    }
+}
+
+/// Implements the product of two 4x4 matrices, the transformation applying `other`
+/// then `self`.
+impl Mul<&Matrix4x4> for &Matrix4x4 {
+    type Output = Matrix4x4;
+
+    fn mul(self, other: &Matrix4x4) -> Self::Output {
+        let mut matrix = [[0.0; 4]; 4];
+        for (row, values) in matrix.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = (0..4)
+                    .map(|k| self.matrix[row][k] * other.matrix[k][col])
+                    .sum();
+            }
+        }
+        Matrix4x4::new(matrix)
+    }
+}
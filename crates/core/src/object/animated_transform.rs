@@ -0,0 +1,452 @@
+use std::{any::Any, sync::Arc};
+
+use crate::{
+    Axis, AxisAlignedBoundingBox, Interval, Matrix3x3, Matrix4x4, Node, Ray, RenderContext,
+    Vector3, object::HitRecord,
+};
+
+/// Number of steps the motion is split into to bound it.
+const BOUNDING_BOX_STEPS: usize = 32;
+
+/// Placement of an object at a point in time, the affine transformation taking a
+/// point `p` of the object to `linear * p + translation`.
+#[derive(Debug, Clone, Copy)]
+pub struct Keyframe {
+    pub time: f64,
+    pub linear: Matrix3x3,
+    pub translation: Vector3,
+}
+
+impl Keyframe {
+    pub fn new(time: f64, linear: Matrix3x3, translation: Vector3) -> Self {
+        Self {
+            time,
+            linear,
+            translation,
+        }
+    }
+
+    /// Creates a keyframe from an affine transformation matrix.
+    pub fn from_matrix(time: f64, matrix: &Matrix4x4) -> Self {
+        Self::new(time, matrix.linear_part(), matrix.translation_part())
+    }
+
+    /// Creates a keyframe leaving the object where it is.
+    pub fn identity(time: f64) -> Self {
+        Self::new(time, Matrix3x3::IDENTITY, Vector3::ZERO)
+    }
+}
+
+/// Transformation of an object moving between two keyframes, for motion blur.
+///
+/// Rays see the object placed as at their time, before the start keyframe it stays
+/// at the start and after the end keyframe at the end. In between, the keyframes
+/// are split into a translation, a rotation and a stretch (Shoemake and Duff 1992)
+/// interpolated separately, so rotating objects keep their shape.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use caustic_core::{
+///     Color, Matrix3x3, Vector3,
+///     material::Lambertian,
+///     object::{AnimatedTransform, Keyframe, Sphere},
+/// };
+///
+/// let material = Arc::new(Lambertian::new_from_color(Color::new(0.8, 0.2, 0.2)));
+/// let sphere = Arc::new(Sphere::new(Vector3::ZERO, 1.0, material));
+/// // spins a quarter turn and moves up while the shutter is open
+/// let spinning = AnimatedTransform::new(
+///     sphere,
+///     Keyframe::identity(0.0),
+///     Keyframe::new(
+///         1.0,
+///         Matrix3x3::rotation(Vector3::new(0.0, 1.0, 0.0), 90.0),
+///         Vector3::new(0.0, 0.5, 0.0),
+///     ),
+/// );
+/// ```
+#[derive(Debug)]
+pub struct AnimatedTransform {
+    object: Arc<dyn Node>,
+    start_time: f64,
+    end_time: f64,
+    start: DecomposedTransform,
+    end: DecomposedTransform,
+    bbox: AxisAlignedBoundingBox,
+}
+
+impl AnimatedTransform {
+    pub fn new(object: Arc<dyn Node>, start: Keyframe, end: Keyframe) -> Self {
+        let mut transform = Self {
+            object,
+            start_time: start.time,
+            end_time: end.time,
+            start: DecomposedTransform::new(&start),
+            end: DecomposedTransform::new(&end),
+            bbox: AxisAlignedBoundingBox::new(),
+        };
+        transform.bbox = transform.compute_bounding_box();
+        transform
+    }
+
+    /// Returns the linear part and the translation of the transformation at `time`.
+    fn transform_at(&self, time: f64) -> (Matrix3x3, Vector3) {
+        let s = if self.end_time > self.start_time {
+            ((time - self.start_time) / (self.end_time - self.start_time)).clamp(0.0, 1.0)
+        } else if time < self.start_time {
+            0.0
+        } else {
+            1.0
+        };
+        let translation = (1.0 - s) * self.start.translation + s * self.end.translation;
+        let rotation = self.start.rotation.slerp(&self.end.rotation, s);
+        let stretch = lerp_matrix(&self.start.stretch, &self.end.stretch, s);
+        (&rotation.to_matrix() * &stretch, translation)
+    }
+
+    /// Bounds the corners of the bounding box of the object at regular steps of the
+    /// motion. Between two steps a corner moves along a curve that strays from the
+    /// straight line between them by less than half its length, so the bounds are
+    /// padded by half the longest step of any corner.
+    fn compute_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let object_bbox = self.object.bounding_box();
+        let corners: Vec<Vector3> = (0..8)
+            .map(|i| {
+                let corner = |axis, bit| {
+                    let interval = object_bbox.axis_interval(axis);
+                    if i & bit == 0 {
+                        interval.min
+                    } else {
+                        interval.max
+                    }
+                };
+                Vector3::new(corner(Axis::X, 1), corner(Axis::Y, 2), corner(Axis::Z, 4))
+            })
+            .collect();
+
+        let mut min = Vector3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut max = -min;
+        let mut previous: Option<Vec<Vector3>> = None;
+        let mut longest_step: f64 = 0.0;
+        for step in 0..=BOUNDING_BOX_STEPS {
+            let s = step as f64 / BOUNDING_BOX_STEPS as f64;
+            let (linear, translation) =
+                self.transform_at(self.start_time + s * (self.end_time - self.start_time));
+            let moved: Vec<Vector3> = corners
+                .iter()
+                .map(|corner| &linear * *corner + translation)
+                .collect();
+            for pt in &moved {
+                min = Vector3::new(min.x.min(pt.x), min.y.min(pt.y), min.z.min(pt.z));
+                max = Vector3::new(max.x.max(pt.x), max.y.max(pt.y), max.z.max(pt.z));
+            }
+            if let Some(previous) = &previous {
+                for (a, b) in previous.iter().zip(&moved) {
+                    longest_step = longest_step.max((*b - *a).length());
+                }
+            }
+            previous = Some(moved);
+        }
+
+        let padding = Vector3::new(1.0, 1.0, 1.0) * (longest_step / 2.0);
+        AxisAlignedBoundingBox::new_from_points(min - padding, max + padding)
+    }
+
+    /// Moves the ray into the space of the object, as placed at the time of the ray.
+    /// Returns the ray with the transformation, or `None` if the object is flattened
+    /// at that time.
+    fn object_ray(&self, ray: &Ray) -> Option<(Ray, Matrix3x3, Matrix3x3, Vector3)> {
+        let (linear, translation) = self.transform_at(ray.time);
+        let inverse = linear.inverse()?;
+        let origin = &inverse * (ray.origin - translation);
+        let direction = &inverse * ray.direction;
+        Some((
+            Ray::new_with_time(origin, direction, ray.time),
+            linear,
+            inverse,
+            translation,
+        ))
+    }
+}
+
+impl Node for AnimatedTransform {
+    fn hit(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let (object_r, linear, inverse, translation) = self.object_ray(ray)?;
+
+        let mut hit = self.object.hit(ctx, &object_r, ray_t)?;
+
+        // normals transform by the transpose of the inverse
+        hit.pt = &linear * hit.pt + translation;
+        hit.normal = (&inverse.transpose() * hit.normal).unit();
+
+        Some(hit)
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        &self.bbox
+    }
+
+    fn transmittance(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> f64 {
+        match self.object_ray(ray) {
+            Some((object_r, ..)) => self.object.transmittance(ctx, &object_r, ray_t),
+            None => 1.0,
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Keyframe split into parts that can be interpolated, the linear part being the
+/// rotation times the stretch.
+#[derive(Debug)]
+struct DecomposedTransform {
+    translation: Vector3,
+    rotation: Quaternion,
+    stretch: Matrix3x3,
+}
+
+impl DecomposedTransform {
+    /// Splits the linear part of `keyframe` with a polar decomposition, averaging the
+    /// matrix with its inverse transpose until it converges to the closest rotation.
+    fn new(keyframe: &Keyframe) -> Self {
+        let linear = keyframe.linear;
+        let mut rotation = linear;
+        for _ in 0..100 {
+            let Some(inverse) = rotation.inverse() else {
+                // a flattened object has no rotation to speak of
+                rotation = Matrix3x3::IDENTITY;
+                break;
+            };
+            let next = lerp_matrix(&rotation, &inverse.transpose(), 0.5);
+            let change: f64 = (0..3)
+                .flat_map(|row| (0..3).map(move |col| (row, col)))
+                .map(|(row, col)| (next[row][col] - rotation[row][col]).abs())
+                .sum();
+            rotation = next;
+            if change < 1e-12 {
+                break;
+            }
+        }
+        // a mirroring transformation is a rotation of the mirrored stretch
+        if rotation.determinant() < 0.0 {
+            rotation = Matrix3x3::new([0, 1, 2].map(|row| rotation[row].map(|value| -value)));
+        }
+        let stretch = &rotation.transpose() * &linear;
+
+        Self {
+            translation: keyframe.translation,
+            rotation: Quaternion::from_matrix(&rotation),
+            stretch,
+        }
+    }
+}
+
+/// Rotation stored as a unit quaternion, to interpolate rotations.
+#[derive(Debug, Clone, Copy)]
+struct Quaternion {
+    w: f64,
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl Quaternion {
+    fn from_matrix(m: &Matrix3x3) -> Self {
+        let trace = m[0][0] + m[1][1] + m[2][2];
+        // divide by the largest of the four components to keep precision
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Self {
+                w: s / 4.0,
+                x: (m[2][1] - m[1][2]) / s,
+                y: (m[0][2] - m[2][0]) / s,
+                z: (m[1][0] - m[0][1]) / s,
+            }
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Self {
+                w: (m[2][1] - m[1][2]) / s,
+                x: s / 4.0,
+                y: (m[0][1] + m[1][0]) / s,
+                z: (m[0][2] + m[2][0]) / s,
+            }
+        } else if m[1][1] > m[2][2] {
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Self {
+                w: (m[0][2] - m[2][0]) / s,
+                x: (m[0][1] + m[1][0]) / s,
+                y: s / 4.0,
+                z: (m[1][2] + m[2][1]) / s,
+            }
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Self {
+                w: (m[1][0] - m[0][1]) / s,
+                x: (m[0][2] + m[2][0]) / s,
+                y: (m[1][2] + m[2][1]) / s,
+                z: s / 4.0,
+            }
+        };
+        q.normalized()
+    }
+
+    fn to_matrix(self) -> Matrix3x3 {
+        let Self { w, x, y, z } = self;
+        Matrix3x3::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ])
+    }
+
+    fn dot(&self, other: &Self) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    fn scaled(self, s: f64) -> Self {
+        Self {
+            w: self.w * s,
+            x: self.x * s,
+            y: self.y * s,
+            z: self.z * s,
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            w: self.w + other.w,
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+
+    fn normalized(self) -> Self {
+        self.scaled(1.0 / self.dot(&self).sqrt())
+    }
+
+    /// Interpolates between the rotations at a constant angular speed, the short way
+    /// around.
+    fn slerp(&self, other: &Self, s: f64) -> Self {
+        let (other, cos_theta) = if self.dot(other) < 0.0 {
+            (other.scaled(-1.0), -self.dot(other))
+        } else {
+            (*other, self.dot(other))
+        };
+        if cos_theta > 0.9995 {
+            // nearly the same rotation, where the angle is imprecise
+            return self.scaled(1.0 - s).add(other.scaled(s)).normalized();
+        }
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        self.scaled(((1.0 - s) * theta).sin() / sin_theta)
+            .add(other.scaled((s * theta).sin() / sin_theta))
+    }
+}
+
+/// Interpolates linearly between the entries of `a` and `b`.
+fn lerp_matrix(a: &Matrix3x3, b: &Matrix3x3, s: f64) -> Matrix3x3 {
+    let mut matrix = [[0.0; 3]; 3];
+    for (row, values) in matrix.iter_mut().enumerate() {
+        for (col, value) in values.iter_mut().enumerate() {
+            *value = (1.0 - s) * a[row][col] + s * b[row][col];
+        }
+    }
+    Matrix3x3::new(matrix)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Color, SeededRandom, material::Lambertian, object::Sphere};
+
+    use super::*;
+
+    fn ctx() -> RenderContext {
+        RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        }
+    }
+
+    fn unit_sphere(center: Vector3) -> Arc<dyn Node> {
+        let material = Arc::new(Lambertian::new_from_color(Color::WHITE));
+        Arc::new(Sphere::new(center, 1.0, material))
+    }
+
+    #[test]
+    fn rays_see_the_object_at_their_time() {
+        let moving = AnimatedTransform::new(
+            unit_sphere(Vector3::ZERO),
+            Keyframe::identity(0.0),
+            Keyframe::new(1.0, Matrix3x3::IDENTITY, Vector3::new(4.0, 0.0, 0.0)),
+        );
+        let hit_x = |time: f64| {
+            let ray = Ray::new_with_time(
+                Vector3::new(-10.0, 0.0, 0.0),
+                Vector3::new(1.0, 0.0, 0.0),
+                time,
+            );
+            moving
+                .hit(&ctx(), &ray, Interval::new(0.001, f64::INFINITY))
+                .map(|hit| hit.pt.x)
+        };
+        assert!((hit_x(0.0).unwrap() + 1.0).abs() < 1e-9);
+        assert!((hit_x(0.5).unwrap() - 1.0).abs() < 1e-9);
+        assert!((hit_x(1.0).unwrap() - 3.0).abs() < 1e-9);
+        // the object stays at the keyframes outside of them
+        assert!((hit_x(2.0).unwrap() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rotations_keep_the_shape() {
+        let spinning = AnimatedTransform::new(
+            unit_sphere(Vector3::new(2.0, 0.0, 0.0)),
+            Keyframe::identity(0.0),
+            Keyframe::new(
+                1.0,
+                &Matrix3x3::rotation(Vector3::new(0.0, 0.0, 1.0), 180.0)
+                    * &Matrix3x3::new([[2.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.0]]),
+                Vector3::ZERO,
+            ),
+        );
+        for time in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            let (linear, _) = spinning.transform_at(time);
+            let center = &linear * Vector3::new(2.0, 0.0, 0.0);
+            // the center goes around a circle while the scale grows from 1 to 2
+            let scale = 1.0 + time;
+            assert!((center.length() - 2.0 * scale).abs() < 1e-9, "{time}");
+            assert!(
+                (linear.determinant() - scale.powi(3)).abs() < 1e-9,
+                "{time}"
+            );
+            // and the bounding box contains the whole sphere
+            let bbox = spinning.bounding_box();
+            for offset in [-scale, scale] {
+                for pt in [
+                    center + Vector3::new(offset, 0.0, 0.0),
+                    center + Vector3::new(0.0, offset, 0.0),
+                    center + Vector3::new(0.0, 0.0, offset),
+                ] {
+                    for axis in Axis::iter() {
+                        assert!(bbox.axis_interval(axis).contains(pt.axis_value(axis)));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,91 +1,493 @@
-use std::{any::Any, cmp::Ordering, sync::Arc};
+use std::{any::Any, sync::Arc};
 
 use crate::{
-    Axis, AxisAlignedBoundingBox, Interval, Ray, RenderContext,
-    object::{Group, HitRecord, Node},
+    Axis, AxisAlignedBoundingBox, Interval, Ray, RenderContext, Vector3,
+    object::{HitRecord, Node},
 };
 
+/// Maximum number of nodes stored in a single leaf of a [`BoundingVolumeHierarchy`].
+const MAX_LEAF_NODES: usize = 2;
+
+/// Number of buckets primitive centroids are binned into when evaluating SAH splits.
+const SAH_BIN_COUNT: usize = 12;
+
+/// Estimated cost of visiting an interior node relative to intersecting a primitive.
+const SAH_TRAVERSAL_COST: f64 = 0.125;
+
+/// Depth after which the builder falls back to median splits, which keeps the tree
+/// shallow enough for the fixed size traversal stack.
+const MAX_SAH_DEPTH: usize = 32;
+
+/// Maximum depth of the traversal stack.
+const MAX_STACK_DEPTH: usize = 64;
+
+/// How primitives are partitioned when building a bounding volume hierarchy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BvhSplitMethod {
+    /// Binned surface area heuristic. Slower to build but produces trees that are
+    /// faster to traverse.
+    SurfaceAreaHeuristic,
+    /// Splits at the median centroid along the longest axis.
+    Median,
+}
+
+/// A node of a [`FlatBvh`].
+///
+/// Interior nodes store their left child directly after themselves and the index of
+/// the right child in `offset`. Leaf nodes store the first primitive in `offset` and
+/// the number of primitives in `count`.
 #[derive(Debug)]
-pub struct BoundingVolumeHierarchy {
-    left: Arc<dyn Node>,
-    right: Arc<dyn Node>,
+struct FlatBvhNode {
     bbox: AxisAlignedBoundingBox,
+    offset: usize,
+    count: usize,
+    axis: Axis,
 }
 
-impl BoundingVolumeHierarchy {
-    pub fn new(nodes: &[Arc<dyn Node>]) -> Self {
-        // Build the bounding box of the span of source objects.
-        let mut bbox = AxisAlignedBoundingBox::new();
-        for obj in nodes {
-            bbox = AxisAlignedBoundingBox::new_from_bbox(bbox, *obj.bounding_box());
+/// A bounding volume hierarchy stored as a flat array of nodes in depth first order.
+///
+/// The hierarchy only knows about the bounding boxes of its primitives; the owner
+/// stores the primitives themselves in the order returned by [`FlatBvh::build`] and
+/// intersects them in the callback passed to [`FlatBvh::hit`].
+#[derive(Debug, Default)]
+pub(crate) struct FlatBvh {
+    nodes: Vec<FlatBvhNode>,
+}
+
+impl FlatBvh {
+    /// Builds a hierarchy over the given primitive bounding boxes.
+    ///
+    /// Nodes are split until they hold at most `max_leaf_size` primitives. With
+    /// [`BvhSplitMethod::SurfaceAreaHeuristic`] a leaf may hold up to twice as many
+    /// when no split is estimated to be cheaper.
+    ///
+    /// # Returns
+    ///
+    /// The hierarchy and the order primitives must be stored in, as indices into
+    /// `bboxes`. Leaf ranges refer to positions in this order.
+    pub(crate) fn build(
+        bboxes: &[AxisAlignedBoundingBox],
+        split_method: BvhSplitMethod,
+        max_leaf_size: usize,
+    ) -> (Self, Vec<usize>) {
+        let centroids: Vec<Vector3> = bboxes.iter().map(|bbox| bbox.centroid()).collect();
+        let mut order: Vec<usize> = (0..bboxes.len()).collect();
+        let mut builder = FlatBvhBuilder {
+            bboxes,
+            centroids: &centroids,
+            split_method,
+            max_leaf_size: max_leaf_size.max(1),
+            nodes: Vec::with_capacity(bboxes.len() * 2),
+        };
+        if !order.is_empty() {
+            builder.build_node(&mut order, 0, 0);
         }
+        (
+            Self {
+                nodes: builder.nodes,
+            },
+            order,
+        )
+    }
 
-        let (left, right) = if nodes.is_empty() {
-            let left: Arc<dyn Node> = Arc::new(Group::new());
-            let right: Arc<dyn Node> = Arc::new(Group::new());
-            (left, right)
-        } else if nodes.len() == 1 {
-            (nodes[0].clone(), nodes[0].clone())
-        } else if nodes.len() == 2 {
-            (nodes[0].clone(), nodes[1].clone())
-        } else {
-            let axis = bbox.longest_axis();
+    /// Returns the bounding box of all primitives.
+    pub(crate) fn bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.nodes.first().map(|node| node.bbox).unwrap_or_default()
+    }
 
-            let mut nodes = nodes.to_vec();
-            nodes.sort_by(|a, b| bbox_compare(a, b, axis));
+    /// Finds the closest primitive hit along the ray.
+    ///
+    /// `hit_primitive` is called with the position of a primitive in the build order
+    /// and the current ray interval, and returns the distance and record of a hit.
+    /// The far end of the interval shrinks to each hit found so farther nodes are
+    /// culled. Children are visited nearest first based on the sign of the ray
+    /// direction along the axis they were split on.
+    pub(crate) fn hit<T>(
+        &self,
+        ray: &Ray,
+        ray_t: Interval,
+        mut hit_primitive: impl FnMut(usize, Interval) -> Option<(f64, T)>,
+    ) -> Option<T> {
+        if self.nodes.is_empty() {
+            return None;
+        }
 
-            let mid = nodes.len() / 2;
-            let left: Arc<dyn Node> = Arc::new(BoundingVolumeHierarchy::new(&nodes[..mid]));
-            let right: Arc<dyn Node> = Arc::new(BoundingVolumeHierarchy::new(&nodes[mid..]));
-            (left, right)
-        };
+        let mut closest = None;
+        let mut ray_t = ray_t;
 
-        let bbox =
-            AxisAlignedBoundingBox::new_from_bbox(*left.bounding_box(), *right.bounding_box());
-        Self { left, right, bbox }
-    }
+        let mut stack = [0usize; MAX_STACK_DEPTH];
+        let mut stack_len = 1;
+        while stack_len > 0 {
+            stack_len -= 1;
+            let node_index = stack[stack_len];
+            let node = &self.nodes[node_index];
+            if !node.bbox.hit(ray, ray_t) {
+                continue;
+            }
 
-    pub fn get_left(&self) -> Arc<dyn Node> {
-        self.left.clone()
+            if node.count > 0 {
+                for i in node.offset..node.offset + node.count {
+                    if let Some((t, hit)) = hit_primitive(i, ray_t) {
+                        ray_t.max = t;
+                        closest = Some(hit);
+                    }
+                }
+            } else {
+                let left = node_index + 1;
+                let right = node.offset;
+                let (near, far) = if ray.direction.axis_value(node.axis) < 0.0 {
+                    (right, left)
+                } else {
+                    (left, right)
+                };
+                stack[stack_len] = far;
+                stack[stack_len + 1] = near;
+                stack_len += 2;
+            }
+        }
+
+        closest
     }
 
-    pub fn get_right(&self) -> Arc<dyn Node> {
-        self.right.clone()
+    /// Calls `visit_primitive` with the position in the build order of every
+    /// primitive in the leaves the ray goes through within `ray_t`, in no particular
+    /// order, until it returns false.
+    pub(crate) fn visit(
+        &self,
+        ray: &Ray,
+        ray_t: Interval,
+        mut visit_primitive: impl FnMut(usize) -> bool,
+    ) {
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        let mut stack = [0usize; MAX_STACK_DEPTH];
+        let mut stack_len = 1;
+        while stack_len > 0 {
+            stack_len -= 1;
+            let node_index = stack[stack_len];
+            let node = &self.nodes[node_index];
+            if !node.bbox.hit(ray, ray_t) {
+                continue;
+            }
+
+            if node.count > 0 {
+                for i in node.offset..node.offset + node.count {
+                    if !visit_primitive(i) {
+                        return;
+                    }
+                }
+            } else {
+                stack[stack_len] = node.offset;
+                stack[stack_len + 1] = node_index + 1;
+                stack_len += 2;
+            }
+        }
     }
 }
 
-impl Node for BoundingVolumeHierarchy {
-    fn hit(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
-        if !self.bbox.hit(ray, ray_t) {
+struct FlatBvhBuilder<'a> {
+    bboxes: &'a [AxisAlignedBoundingBox],
+    centroids: &'a [Vector3],
+    split_method: BvhSplitMethod,
+    max_leaf_size: usize,
+    nodes: Vec<FlatBvhNode>,
+}
+
+impl FlatBvhBuilder<'_> {
+    /// Recursively builds the hierarchy over `order`, whose primitives start at
+    /// position `first` of the final primitive order.
+    fn build_node(&mut self, order: &mut [usize], first: usize, depth: usize) {
+        let bbox = order.iter().fold(AxisAlignedBoundingBox::new(), |bbox, i| {
+            AxisAlignedBoundingBox::new_from_bbox(bbox, self.bboxes[*i])
+        });
+        let node_index = self.nodes.len();
+        self.nodes.push(FlatBvhNode {
+            bbox,
+            offset: first,
+            count: order.len(),
+            axis: Axis::X,
+        });
+
+        if order.len() <= self.max_leaf_size {
+            return;
+        }
+
+        let centroid_bounds = order.iter().fold(AxisAlignedBoundingBox::new(), |bbox, i| {
+            let c = self.centroids[*i];
+            AxisAlignedBoundingBox::new_from_bbox(
+                bbox,
+                AxisAlignedBoundingBox::new_from_points(c, c),
+            )
+        });
+
+        let split =
+            if self.split_method == BvhSplitMethod::SurfaceAreaHeuristic && depth < MAX_SAH_DEPTH {
+                self.sah_split(order, &bbox, &centroid_bounds)
+            } else {
+                Some(self.median_split(order, &centroid_bounds))
+            };
+
+        // SAH decided splitting is more expensive than testing every primitive
+        let Some((axis, mid)) = split else {
+            return;
+        };
+
+        let (left, right) = order.split_at_mut(mid);
+        self.build_node(left, first, depth + 1);
+        let right_index = self.nodes.len();
+        self.build_node(right, first + mid, depth + 1);
+
+        let node = &mut self.nodes[node_index];
+        node.offset = right_index;
+        node.count = 0;
+        node.axis = axis;
+    }
+
+    fn median_split(
+        &self,
+        order: &mut [usize],
+        centroid_bounds: &AxisAlignedBoundingBox,
+    ) -> (Axis, usize) {
+        let axis = centroid_bounds.longest_axis();
+        let mid = order.len() / 2;
+        order.select_nth_unstable_by(mid, |a, b| {
+            self.centroids[*a]
+                .axis_value(axis)
+                .total_cmp(&self.centroids[*b].axis_value(axis))
+        });
+        (axis, mid)
+    }
+
+    /// Finds the cheapest split over all axes by binning centroids into buckets and
+    /// evaluating the surface area heuristic at each bucket boundary, then partitions
+    /// `order` at it.
+    ///
+    /// Returns `None` when no split is cheaper than a leaf of all primitives and there
+    /// are at most twice the maximum leaf size of them.
+    fn sah_split(
+        &self,
+        order: &mut [usize],
+        bbox: &AxisAlignedBoundingBox,
+        centroid_bounds: &AxisAlignedBoundingBox,
+    ) -> Option<(Axis, usize)> {
+        let mut best: Option<(f64, Axis, usize)> = None;
+
+        for axis in Axis::iter() {
+            let extent = centroid_bounds.axis_interval(axis);
+            if extent.size() <= 0.0 {
+                continue;
+            }
+
+            let mut counts = [0usize; SAH_BIN_COUNT];
+            let mut bounds = [AxisAlignedBoundingBox::new(); SAH_BIN_COUNT];
+            for i in order.iter() {
+                let bin = Self::bin_index(
+                    self.centroids[*i].axis_value(axis),
+                    extent.min,
+                    extent.size(),
+                );
+                counts[bin] += 1;
+                bounds[bin] = AxisAlignedBoundingBox::new_from_bbox(bounds[bin], self.bboxes[*i]);
+            }
+
+            // sweep from the right to get the cost of everything right of each boundary
+            let mut right_costs = [0.0; SAH_BIN_COUNT];
+            let mut right_bbox = AxisAlignedBoundingBox::new();
+            let mut right_count = 0;
+            for bin in (1..SAH_BIN_COUNT).rev() {
+                right_bbox = AxisAlignedBoundingBox::new_from_bbox(right_bbox, bounds[bin]);
+                right_count += counts[bin];
+                right_costs[bin] = Self::side_cost(&right_bbox, right_count);
+            }
+
+            let mut left_bbox = AxisAlignedBoundingBox::new();
+            let mut left_count = 0;
+            for bin in 0..SAH_BIN_COUNT - 1 {
+                left_bbox = AxisAlignedBoundingBox::new_from_bbox(left_bbox, bounds[bin]);
+                left_count += counts[bin];
+                if left_count == 0 || left_count == order.len() {
+                    continue;
+                }
+                let cost = Self::side_cost(&left_bbox, left_count) + right_costs[bin + 1];
+                if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                    best = Some((cost, axis, bin));
+                }
+            }
+        }
+
+        let Some((cost, axis, split_bin)) = best else {
+            // every centroid is in the same place, SAH cannot separate them
+            return Some(self.median_split(order, centroid_bounds));
+        };
+
+        let parent_area = bbox.surface_area();
+        let split_cost = SAH_TRAVERSAL_COST + cost / parent_area;
+        let leaf_cost = order.len() as f64;
+        if split_cost >= leaf_cost && order.len() <= self.max_leaf_size * 2 {
             return None;
         }
 
-        let hit_left = self.left.hit(ctx, ray, ray_t);
+        let extent = centroid_bounds.axis_interval(axis);
+        let mut mid = 0;
+        for j in 0..order.len() {
+            let bin = Self::bin_index(
+                self.centroids[order[j]].axis_value(axis),
+                extent.min,
+                extent.size(),
+            );
+            if bin <= split_bin {
+                order.swap(j, mid);
+                mid += 1;
+            }
+        }
+        Some((axis, mid))
+    }
 
-        // check to see if right is closer
-        let mut t = ray_t.max;
-        if let Some(hit_left) = &hit_left {
-            t = hit_left.t;
+    fn bin_index(value: f64, min: f64, size: f64) -> usize {
+        let bin = ((value - min) / size * SAH_BIN_COUNT as f64) as usize;
+        bin.min(SAH_BIN_COUNT - 1)
+    }
+
+    fn side_cost(bbox: &AxisAlignedBoundingBox, count: usize) -> f64 {
+        if count == 0 {
+            0.0
+        } else {
+            bbox.surface_area() * count as f64
         }
-        let hit_right = self.right.hit(ctx, ray, Interval::new(ray_t.min, t));
-        if hit_right.is_some() {
-            return hit_right;
+    }
+}
+
+/// A bounding volume hierarchy over a list of nodes.
+///
+/// The hierarchy is built with a binned surface area heuristic (SAH) by default and
+/// stored as a flat array, so traversal walks an index based stack instead of
+/// recursing through nested nodes.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use caustic_core::{
+///     Color, Interval, Ray, RenderContext, Vector3, random_new,
+///     material::Lambertian,
+///     object::{BoundingVolumeHierarchy, Node, Sphere},
+/// };
+///
+/// let material = Arc::new(Lambertian::new_from_color(Color::WHITE));
+/// let spheres: Vec<Arc<dyn Node>> = (0..10)
+///     .map(|i| {
+///         Arc::new(Sphere::new(Vector3::new(i as f64 * 3.0, 0.0, 0.0), 1.0, material.clone()))
+///             as Arc<dyn Node>
+///     })
+///     .collect();
+/// let bvh = BoundingVolumeHierarchy::new(&spheres);
+///
+/// let ctx = RenderContext { random: random_new() };
+/// let ray = Ray::new(Vector3::new(9.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+/// let hit = bvh.hit(&ctx, &ray, Interval::new(0.001, f64::INFINITY)).unwrap();
+/// assert_eq!(hit.t, 4.0);
+/// ```
+#[derive(Debug)]
+pub struct BoundingVolumeHierarchy {
+    primitives: Vec<Arc<dyn Node>>,
+    bvh: FlatBvh,
+    bbox: AxisAlignedBoundingBox,
+}
+
+impl BoundingVolumeHierarchy {
+    pub fn new(nodes: &[Arc<dyn Node>]) -> Self {
+        Self::new_with_split_method(nodes, BvhSplitMethod::SurfaceAreaHeuristic)
+    }
+
+    pub fn new_with_split_method(nodes: &[Arc<dyn Node>], split_method: BvhSplitMethod) -> Self {
+        let bboxes: Vec<AxisAlignedBoundingBox> =
+            nodes.iter().map(|node| *node.bounding_box()).collect();
+        let (bvh, order) = FlatBvh::build(&bboxes, split_method, MAX_LEAF_NODES);
+        let primitives = order.iter().map(|i| nodes[*i].clone()).collect();
+        let bbox = bvh.bounding_box();
+        Self {
+            primitives,
+            bvh,
+            bbox,
         }
+    }
 
-        hit_left
+    /// Returns the nodes contained in the hierarchy, in traversal order.
+    pub fn primitives(&self) -> &[Arc<dyn Node>] {
+        &self.primitives
+    }
+}
+
+impl Node for BoundingVolumeHierarchy {
+    fn hit(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        self.bvh.hit(ray, ray_t, |i, ray_t| {
+            self.primitives[i]
+                .hit(ctx, ray, ray_t)
+                .map(|hit| (hit.t, hit))
+        })
     }
 
     fn bounding_box(&self) -> &AxisAlignedBoundingBox {
         &self.bbox
     }
 
+    fn transmittance(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> f64 {
+        let mut transmittance = 1.0;
+        self.bvh.visit(ray, ray_t, |i| {
+            transmittance *= self.primitives[i].transmittance(ctx, ray, ray_t);
+            transmittance > 0.0
+        });
+        transmittance
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
 }
 
-fn bbox_compare(a: &Arc<dyn Node>, b: &Arc<dyn Node>, axis: Axis) -> Ordering {
-    let a_axis_interval = a.bounding_box().axis_interval(axis);
-    let b_axis_interval = b.bounding_box().axis_interval(axis);
-    a_axis_interval.min.total_cmp(&b_axis_interval.min)
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        Color, Interval, Ray, RenderContext, Vector3,
+        material::Lambertian,
+        object::{BoundingVolumeHierarchy, BvhSplitMethod, Group, Node, Sphere},
+        random_new,
+    };
+
+    #[test]
+    fn matches_linear_search() {
+        let ctx = RenderContext {
+            random: random_new(),
+        };
+        let random = &*ctx.random;
+        let material = Arc::new(Lambertian::new_from_color(Color::WHITE));
+        let spheres: Vec<Arc<dyn Node>> = (0..500)
+            .map(|_| {
+                let center = Vector3::random_interval(random, -10.0, 10.0);
+                let radius = random.rand_interval(0.05, 1.0);
+                Arc::new(Sphere::new(center, radius, material.clone())) as Arc<dyn Node>
+            })
+            .collect();
+
+        let group = Group::from_list(&spheres);
+        for split_method in [BvhSplitMethod::SurfaceAreaHeuristic, BvhSplitMethod::Median] {
+            let bvh = BoundingVolumeHierarchy::new_with_split_method(&spheres, split_method);
+            assert_eq!(bvh.primitives().len(), spheres.len());
+
+            for _ in 0..1000 {
+                let ray = Ray::new(
+                    Vector3::random_interval(random, -15.0, 15.0),
+                    Vector3::random_unit(random),
+                );
+                let ray_t = Interval::new(0.001, f64::INFINITY);
+                let expected = group.hit(&ctx, &ray, ray_t).map(|hit| hit.t);
+                let actual = bvh.hit(&ctx, &ray, ray_t).map(|hit| hit.t);
+                assert_eq!(actual, expected);
+            }
+        }
+    }
 }
@@ -318,8 +318,7 @@ impl Node for ConeFrustumWall {
         let r1 = self.r1;
         let h = self.height;
 
-        let u1 = ctx.random.rand_interval(0.0, 1.0);
-        let u2 = ctx.random.rand_interval(0.0, 1.0);
+        let (u1, u2) = ctx.random.rand_2d();
 
         // 1. Azimuthal Angle (u1)
         let phi = 2.0 * f64::consts::PI * u1;
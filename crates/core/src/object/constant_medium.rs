@@ -35,39 +35,30 @@ impl ConstantMedium {
             phase_function: Arc::new(Isotropic::new_from_color(albedo)),
         }
     }
+
+    pub fn density(&self) -> f64 {
+        -1.0 / self.neg_inv_density
+    }
+
+    /// Returns the material scattering the light inside the medium.
+    pub fn phase_function(&self) -> &Arc<dyn Material> {
+        &self.phase_function
+    }
 }
 
 impl Node for ConstantMedium {
     fn hit(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
-        let mut hit1 = self.boundary.hit(ctx, ray, Interval::UNIVERSE)?;
-        let mut hit2 =
-            self.boundary
-                .hit(ctx, ray, Interval::new(hit1.t + 0.0001, f64::INFINITY))?;
-
-        if hit1.t < ray_t.min {
-            hit1.t = ray_t.min;
-        }
-        if hit2.t > ray_t.max {
-            hit2.t = ray_t.max;
-        }
-
-        if hit1.t >= hit2.t {
-            return None;
-        }
-
-        if hit1.t < 0.0 {
-            hit1.t = 0.0;
-        }
+        let inside = boundary_interval(&*self.boundary, ctx, ray, ray_t)?;
 
         let ray_length = ray.direction.length();
-        let distance_inside_boundary = (hit2.t - hit1.t) * ray_length;
+        let distance_inside_boundary = inside.size() * ray_length;
         let hit_distance = self.neg_inv_density * ctx.random.rand().ln();
 
         if hit_distance > distance_inside_boundary {
             return None;
         }
 
-        let t = hit1.t + hit_distance / ray_length;
+        let t = inside.min + hit_distance / ray_length;
         Some(HitRecord {
             pt: ray.at(t),
             normal: Vector3::new(1.0, 0.0, 0.0), // arbitrary
@@ -79,6 +70,13 @@ impl Node for ConstantMedium {
         })
     }
 
+    fn transmittance(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> f64 {
+        match boundary_interval(&*self.boundary, ctx, ray, ray_t) {
+            Some(inside) => (inside.size() * ray.direction.length() / self.neg_inv_density).exp(),
+            None => 1.0,
+        }
+    }
+
     fn bounding_box(&self) -> &AxisAlignedBoundingBox {
         self.boundary.bounding_box()
     }
@@ -87,3 +85,32 @@ impl Node for ConstantMedium {
         self
     }
 }
+
+/// Returns the part of `ray_t` where `ray` is inside `boundary`, which must be convex,
+/// starting at 0 at the earliest.
+pub(crate) fn boundary_interval(
+    boundary: &dyn Node,
+    ctx: &RenderContext,
+    ray: &Ray,
+    ray_t: Interval,
+) -> Option<Interval> {
+    let mut hit1 = boundary.hit(ctx, ray, Interval::UNIVERSE)?;
+    let mut hit2 = boundary.hit(ctx, ray, Interval::new(hit1.t + 0.0001, f64::INFINITY))?;
+
+    if hit1.t < ray_t.min {
+        hit1.t = ray_t.min;
+    }
+    if hit2.t > ray_t.max {
+        hit2.t = ray_t.max;
+    }
+
+    if hit1.t >= hit2.t {
+        return None;
+    }
+
+    if hit1.t < 0.0 {
+        hit1.t = 0.0;
+    }
+
+    Some(Interval::new(hit1.t, hit2.t))
+}
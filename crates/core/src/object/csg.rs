@@ -0,0 +1,327 @@
+use std::{any::Any, sync::Arc};
+
+use crate::{Axis, AxisAlignedBoundingBox, Interval, Node, Ray, RenderContext, object::HitRecord};
+
+/// Part of a ray inside a solid, between the surface where it enters and the one
+/// where it leaves.
+pub struct Span {
+    pub enter: HitRecord,
+    pub exit: HitRecord,
+}
+
+/// Returns the spans of `ray` inside `node` from its successive hits along the whole
+/// ray, see [`Node::solid_spans`].
+///
+/// Nested surfaces, like overlapping objects of a group, are merged into a single
+/// span. Unmatched hits, like the two sides of a sphere closer than the step between
+/// hits when the ray grazes it, are left out.
+pub(crate) fn hit_spans<N: Node + ?Sized>(node: &N, ctx: &RenderContext, ray: &Ray) -> Vec<Span> {
+    let mut spans = vec![];
+    let mut enter = None;
+    let mut depth = 0;
+    let mut t_min = f64::NEG_INFINITY;
+    while let Some(hit) = node.hit(ctx, ray, Interval::new(t_min, f64::INFINITY)) {
+        t_min = hit.t + 0.0001;
+        if hit.front_face {
+            if depth == 0 {
+                enter = Some(hit);
+            }
+            depth += 1;
+        } else if depth > 0 {
+            depth -= 1;
+            if depth == 0
+                && let Some(enter) = enter.take()
+            {
+                spans.push(Span { enter, exit: hit });
+            }
+        }
+    }
+    spans
+}
+
+/// Boolean operation combining the solids of a [`Csg`] node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsgOperation {
+    /// Inside any of the solids
+    Union,
+    /// Inside all of the solids
+    Intersection,
+    /// Inside the first solid and none of the others
+    Difference,
+}
+
+impl CsgOperation {
+    /// Returns whether a point inside the first operand or not, `a`, and the second
+    /// operand or not, `b`, is inside the result.
+    fn inside(self, a: bool, b: bool) -> bool {
+        match self {
+            CsgOperation::Union => a || b,
+            CsgOperation::Intersection => a && b,
+            CsgOperation::Difference => a && !b,
+        }
+    }
+
+    /// Combines the spans of a ray inside two solids into its spans inside the result.
+    ///
+    /// Going along the ray, each surface toggles whether the ray is inside its solid,
+    /// and the result has a surface where that toggles whether the ray is inside the
+    /// result. The hits keep their normal facing the ray, only which side is the
+    /// inside changes, for example where a difference cuts into the first solid.
+    fn combine(self, a: Vec<Span>, b: Vec<Span>) -> Vec<Span> {
+        let mut crossings: Vec<(HitRecord, bool)> = a
+            .into_iter()
+            .flat_map(|span| [(span.enter, true), (span.exit, true)])
+            .chain(
+                b.into_iter()
+                    .flat_map(|span| [(span.enter, false), (span.exit, false)]),
+            )
+            .collect();
+        crossings.sort_by(|(hit_a, _), (hit_b, _)| hit_a.t.total_cmp(&hit_b.t));
+
+        let mut spans = vec![];
+        let (mut inside_a, mut inside_b, mut inside) = (false, false, false);
+        let mut enter = None;
+        for (mut hit, from_a) in crossings {
+            if from_a {
+                inside_a = !inside_a;
+            } else {
+                inside_b = !inside_b;
+            }
+            if self.inside(inside_a, inside_b) == inside {
+                continue;
+            }
+            inside = !inside;
+            hit.front_face = inside;
+            if inside {
+                enter = Some(hit);
+            } else if let Some(enter) = enter.take() {
+                spans.push(Span { enter, exit: hit });
+            }
+        }
+        spans
+    }
+}
+
+/// Constructive solid geometry: the union, intersection or difference of closed
+/// solids, rendered exactly from where rays enter and leave each of them.
+///
+/// Each ray is followed through the whole of every child to find the spans inside
+/// it, so the children must be closed with their normals facing out, and a CSG node
+/// costs more to hit than its children. The surfaces of a difference cut by the
+/// other solids keep their material.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use caustic_core::{
+///     Color, Node, Vector3,
+///     material::Lambertian,
+///     object::{BoxPrimitive, Csg, Sphere},
+/// };
+///
+/// let material = Arc::new(Lambertian::new_from_color(Color::new(0.8, 0.8, 0.8)));
+/// let cube: Arc<dyn Node> = Arc::new(BoxPrimitive::new(
+///     Vector3::new(-1.0, -1.0, -1.0),
+///     Vector3::new(1.0, 1.0, 1.0),
+///     material.clone(),
+/// ));
+/// let sphere: Arc<dyn Node> = Arc::new(Sphere::new(Vector3::ZERO, 1.3, material));
+/// // a cube with rounded corners, and the cube with the rounded corners left over
+/// let rounded = Csg::intersection(vec![cube.clone(), sphere.clone()]);
+/// let corners = Csg::difference(vec![cube, sphere]);
+/// ```
+#[derive(Debug)]
+pub struct Csg {
+    operation: CsgOperation,
+    children: Vec<Arc<dyn Node>>,
+    bbox: AxisAlignedBoundingBox,
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, children: Vec<Arc<dyn Node>>) -> Self {
+        let bbox = Self::compute_bounding_box(operation, &children);
+        Self {
+            operation,
+            children,
+            bbox,
+        }
+    }
+
+    /// Creates the solid inside any of `children`.
+    pub fn union(children: Vec<Arc<dyn Node>>) -> Self {
+        Self::new(CsgOperation::Union, children)
+    }
+
+    /// Creates the solid inside all of `children`.
+    pub fn intersection(children: Vec<Arc<dyn Node>>) -> Self {
+        Self::new(CsgOperation::Intersection, children)
+    }
+
+    /// Creates the solid inside the first of `children` and none of the others.
+    pub fn difference(children: Vec<Arc<dyn Node>>) -> Self {
+        Self::new(CsgOperation::Difference, children)
+    }
+
+    pub fn operation(&self) -> CsgOperation {
+        self.operation
+    }
+
+    pub fn children(&self) -> &[Arc<dyn Node>] {
+        &self.children
+    }
+
+    fn compute_bounding_box(
+        operation: CsgOperation,
+        children: &[Arc<dyn Node>],
+    ) -> AxisAlignedBoundingBox {
+        let mut boxes = children.iter().map(|child| *child.bounding_box());
+        let Some(first) = boxes.next() else {
+            return AxisAlignedBoundingBox::new();
+        };
+        match operation {
+            CsgOperation::Union => boxes.fold(first, AxisAlignedBoundingBox::new_from_bbox),
+            CsgOperation::Intersection => boxes.fold(first, |a, b| {
+                let overlap = |axis| {
+                    let (a, b) = (a.axis_interval(axis), b.axis_interval(axis));
+                    Interval::new(a.min.max(b.min), a.max.min(b.max))
+                };
+                AxisAlignedBoundingBox::new_from_intervals(
+                    overlap(Axis::X),
+                    overlap(Axis::Y),
+                    overlap(Axis::Z),
+                )
+            }),
+            CsgOperation::Difference => first,
+        }
+    }
+}
+
+impl Node for Csg {
+    fn hit(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        if !self.bbox.hit(ray, ray_t) {
+            return None;
+        }
+        self.solid_spans(ctx, ray)
+            .into_iter()
+            .flat_map(|span| [span.enter, span.exit])
+            .find(|hit| ray_t.surrounds(hit.t))
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        &self.bbox
+    }
+
+    fn solid_spans(&self, ctx: &RenderContext, ray: &Ray) -> Vec<Span> {
+        let mut children = self.children.iter();
+        let Some(first) = children.next() else {
+            return vec![];
+        };
+        let mut spans = first.solid_spans(ctx, ray);
+        for child in children {
+            // nothing is left to intersect with or cut from
+            if spans.is_empty() && self.operation != CsgOperation::Union {
+                break;
+            }
+            spans = self.operation.combine(spans, child.solid_spans(ctx, ray));
+        }
+        spans
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        Color, SeededRandom, Vector3,
+        material::{Lambertian, Material},
+        object::{BoxPrimitive, Sphere},
+    };
+
+    use super::*;
+
+    /// Returns the distances along the x axis where a ray from x = -10 enters and
+    /// leaves `node`.
+    fn crossings(node: &dyn Node) -> Vec<(f64, bool)> {
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let ray = Ray::new(Vector3::new(-10.0, 0.1, 0.2), Vector3::new(1.0, 0.0, 0.0));
+        let mut crossings = vec![];
+        let mut t_min = 0.001;
+        while let Some(hit) = node.hit(&ctx, &ray, Interval::new(t_min, f64::INFINITY)) {
+            // the normal always faces the ray
+            assert!(hit.normal.dot(&ray.direction) < 0.0);
+            crossings.push(((hit.t - 10.0 + 0.005).floor() + 0.0, hit.front_face));
+            t_min = hit.t + 0.001;
+        }
+        crossings
+    }
+
+    fn solids() -> (Arc<dyn Node>, Arc<dyn Node>, Arc<dyn Node>) {
+        let material: Arc<dyn Material> = Arc::new(Lambertian::new_from_color(Color::WHITE));
+        let cube = Arc::new(BoxPrimitive::new(
+            Vector3::new(-2.0, -2.0, -2.0),
+            Vector3::new(2.0, 2.0, 2.0),
+            material.clone(),
+        ));
+        let left = Arc::new(Sphere::new(
+            Vector3::new(-3.0, 0.0, 0.0),
+            2.0,
+            material.clone(),
+        ));
+        let right = Arc::new(Sphere::new(Vector3::new(3.0, 0.0, 0.0), 2.0, material));
+        (cube, left, right)
+    }
+
+    #[test]
+    fn operations_combine_the_spans() {
+        // along the ray, the cube spans [-2, 2] and the spheres about [-5, -1] and [1, 5]
+        let (cube, left, right) = solids();
+        let children = vec![cube, left, right];
+
+        let union = Csg::union(children.clone());
+        assert_eq!(crossings(&union), vec![(-5.0, true), (4.0, false)]);
+
+        let intersection = Csg::intersection(children[..2].to_vec());
+        assert_eq!(crossings(&intersection), vec![(-2.0, true), (-2.0, false)]);
+
+        let difference = Csg::difference(children.clone());
+        assert_eq!(crossings(&difference), vec![(-2.0, true), (1.0, false)]);
+
+        // a difference nested in a union
+        let nested = Csg::union(vec![
+            Arc::new(difference),
+            Arc::new(Sphere::new(
+                Vector3::new(-6.0, 0.0, 0.0),
+                1.0,
+                Arc::new(Lambertian::new_from_color(Color::WHITE)),
+            )),
+        ]);
+        assert_eq!(
+            crossings(&nested),
+            vec![(-7.0, true), (-6.0, false), (-2.0, true), (1.0, false)]
+        );
+    }
+
+    #[test]
+    fn bounding_boxes_follow_the_operation() {
+        let (cube, left, _) = solids();
+        let intersection = Csg::intersection(vec![cube.clone(), left.clone()]);
+        let x = intersection.bounding_box().axis_interval(Axis::X);
+        assert!(
+            (x.min + 2.0).abs() < 1e-3 && (x.max + 1.0).abs() < 1e-3,
+            "{x:?}"
+        );
+
+        let difference = Csg::difference(vec![cube, left]);
+        let x = difference.bounding_box().axis_interval(Axis::X);
+        assert!(
+            (x.min + 2.0).abs() < 1e-3 && (x.max - 2.0).abs() < 1e-3,
+            "{x:?}"
+        );
+    }
+}
@@ -71,8 +71,9 @@ impl Disc {
         // 1. Generate a random point in a unit square and map it to a unit disc.
         // We use random_in_unit_disc() from a common library or implement it:
         // A simple way is to use a polar coordinate approach for uniform sampling
-        let r_sq = random.rand().sqrt(); // Radius from center: r in [0, 1]
-        let phi = 2.0 * f64::consts::PI * random.rand(); // Angle: phi in [0, 2pi)
+        let (r1, r2) = random.rand_2d();
+        let r_sq = r1.sqrt(); // Radius from center: r in [0, 1]
+        let phi = 2.0 * f64::consts::PI * r2; // Angle: phi in [0, 2pi)
 
         let x = r_sq * phi.cos() * radius;
         let y = r_sq * phi.sin() * radius;
@@ -57,6 +57,17 @@ impl Node for Group {
         &self.bbox
     }
 
+    fn transmittance(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> f64 {
+        let mut transmittance = 1.0;
+        for node in &self.nodes {
+            transmittance *= node.transmittance(ctx, ray, ray_t);
+            if transmittance <= 0.0 {
+                break;
+            }
+        }
+        transmittance
+    }
+
     fn pdf_value(&self, ctx: &RenderContext, origin: &Vector3, direction: &Vector3) -> f64 {
         let weight = 1.0 / (self.nodes.len() as f64);
         let mut sum = 0.0;
@@ -0,0 +1,269 @@
+use std::{any::Any, sync::Arc};
+
+use crate::{
+    AxisAlignedBoundingBox, Color, Interval, Node, Ray, RenderContext, Vector3,
+    material::{Isotropic, Material},
+    object::{HitRecord, constant_medium::boundary_interval},
+    texture::Texture,
+    volume::DensityField,
+};
+
+/// Participating medium whose density varies inside its boundary, like smoke or
+/// clouds, where [`ConstantMedium`](crate::object::ConstantMedium) is a uniform fog.
+///
+/// The density at a point is `density` times the value of the density field there.
+/// Rays are scattered with delta tracking: free flights are sampled with the largest
+/// density of the field, and each collision is kept with the probability that it is
+/// real rather than virtual. Shadow rays go through with ratio tracking, which
+/// multiplies the probabilities that collisions are virtual instead of picking one.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use caustic_core::{
+///     Color, Vector3, material::Lambertian, object::{HeterogeneousMedium, Sphere},
+///     random_new, texture::PerlinTurbulenceTexture, volume::TextureDensity,
+/// };
+///
+/// let boundary = Arc::new(Sphere::new(
+///     Vector3::ZERO,
+///     2.0,
+///     Arc::new(Lambertian::new_from_color(Color::WHITE)),
+/// ));
+/// let turbulence = Arc::new(PerlinTurbulenceTexture::new(&*random_new(), 0.0, 7));
+/// let smoke = HeterogeneousMedium::new_from_color(
+///     boundary,
+///     5.0,
+///     Arc::new(TextureDensity::new(turbulence)),
+///     Color::new(0.8, 0.8, 0.8),
+/// );
+/// ```
+#[derive(Debug)]
+pub struct HeterogeneousMedium {
+    boundary: Arc<dyn Node>,
+    density: f64,
+    density_field: Arc<dyn DensityField>,
+    /// Largest density inside the boundary
+    max_density: f64,
+    phase_function: Arc<dyn Material>,
+}
+
+impl HeterogeneousMedium {
+    pub fn new_from_texture(
+        boundary: Arc<dyn Node>,
+        density: f64,
+        density_field: Arc<dyn DensityField>,
+        texture: Arc<dyn Texture>,
+    ) -> Self {
+        Self::new_with_phase_function(
+            boundary,
+            density,
+            density_field,
+            Arc::new(Isotropic::new_from_texture(texture)),
+        )
+    }
+
+    pub fn new_from_color(
+        boundary: Arc<dyn Node>,
+        density: f64,
+        density_field: Arc<dyn DensityField>,
+        albedo: Color,
+    ) -> Self {
+        Self::new_with_phase_function(
+            boundary,
+            density,
+            density_field,
+            Arc::new(Isotropic::new_from_color(albedo)),
+        )
+    }
+
+    fn new_with_phase_function(
+        boundary: Arc<dyn Node>,
+        density: f64,
+        density_field: Arc<dyn DensityField>,
+        phase_function: Arc<dyn Material>,
+    ) -> Self {
+        let density = density.max(0.0);
+        Self {
+            boundary,
+            density,
+            max_density: density * density_field.max_density(),
+            density_field,
+            phase_function,
+        }
+    }
+
+    /// Returns the density the values of the density field are scaled by.
+    pub fn density(&self) -> f64 {
+        self.density
+    }
+
+    pub fn density_field(&self) -> &Arc<dyn DensityField> {
+        &self.density_field
+    }
+
+    /// Returns the material scattering the light inside the medium.
+    pub fn phase_function(&self) -> &Arc<dyn Material> {
+        &self.phase_function
+    }
+
+    /// Returns the distance along the ray to the next tentative collision, with the
+    /// largest density.
+    fn free_flight(&self, ctx: &RenderContext, ray_length: f64) -> f64 {
+        -(1.0 - ctx.random.rand()).ln() / (self.max_density * ray_length)
+    }
+}
+
+impl Node for HeterogeneousMedium {
+    fn hit(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        if self.max_density <= 0.0 {
+            return None;
+        }
+        let inside = boundary_interval(&*self.boundary, ctx, ray, ray_t)?;
+
+        // delta tracking
+        let ray_length = ray.direction.length();
+        let mut t = inside.min;
+        loop {
+            t += self.free_flight(ctx, ray_length);
+            if t >= inside.max {
+                return None;
+            }
+            let pt = ray.at(t);
+            let density = self.density * self.density_field.density(pt);
+            if ctx.random.rand() * self.max_density < density {
+                return Some(HitRecord {
+                    pt,
+                    normal: Vector3::new(1.0, 0.0, 0.0), // arbitrary
+                    t,
+                    u: 0.0,
+                    v: 0.0,
+                    front_face: true, // also arbitrary
+                    material: self.phase_function.clone(),
+                });
+            }
+        }
+    }
+
+    fn transmittance(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> f64 {
+        if self.max_density <= 0.0 {
+            return 1.0;
+        }
+        let Some(inside) = boundary_interval(&*self.boundary, ctx, ray, ray_t) else {
+            return 1.0;
+        };
+
+        // ratio tracking
+        let ray_length = ray.direction.length();
+        let mut transmittance = 1.0;
+        let mut t = inside.min;
+        loop {
+            t += self.free_flight(ctx, ray_length);
+            if t >= inside.max {
+                return transmittance;
+            }
+            let density = self.density * self.density_field.density(ray.at(t));
+            transmittance *= 1.0 - (density / self.max_density).min(1.0);
+            if transmittance <= 0.0 {
+                return 0.0;
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        self.boundary.bounding_box()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        SeededRandom,
+        object::{ConstantMedium, Sphere},
+        volume::VoxelGrid,
+    };
+
+    use super::*;
+
+    fn unit_sphere() -> Arc<dyn Node> {
+        Arc::new(Sphere::new(
+            Vector3::ZERO,
+            1.0,
+            Arc::new(Isotropic::new_from_color(Color::WHITE)),
+        ))
+    }
+
+    /// Fraction of rays through the center of the unit sphere that go through `node`,
+    /// estimated by tracing them and with its transmittance.
+    fn transmittances(node: &dyn Node) -> (f64, f64) {
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let n = 100_000;
+        let mut traced = 0.0;
+        let mut tracked = 0.0;
+        for _ in 0..n {
+            if node.hit(&ctx, &ray(), RAY_T).is_none() {
+                traced += 1.0;
+            }
+            tracked += node.transmittance(&ctx, &ray(), RAY_T);
+        }
+        (traced / n as f64, tracked / n as f64)
+    }
+
+    const RAY_T: Interval = Interval::new(0.001, f64::INFINITY);
+
+    fn ray() -> Ray {
+        Ray::new(Vector3::new(0.0, 0.0, -2.0), Vector3::new(0.0, 0.0, 1.0))
+    }
+
+    fn cube() -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::new_from_points(
+            Vector3::new(-1.0, -1.0, -1.0),
+            Vector3::new(1.0, 1.0, 1.0),
+        )
+    }
+
+    #[test]
+    fn uniform_field_matches_constant_medium() {
+        let uniform = Arc::new(VoxelGrid::new([2, 2, 2], cube(), vec![0.25; 8]));
+        let medium = HeterogeneousMedium::new_from_color(unit_sphere(), 2.0, uniform, Color::WHITE);
+        let constant = ConstantMedium::new_from_color(unit_sphere(), 0.5, Color::WHITE);
+
+        // e^(-0.5 * 2)
+        let expected = (-1.0f64).exp();
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let exact = constant.transmittance(&ctx, &ray(), RAY_T);
+        assert!((exact - expected).abs() < 1e-9, "{exact}");
+        let (traced, tracked) = transmittances(&medium);
+        assert!((traced - expected).abs() < 0.01, "{traced}");
+        assert!((tracked - expected).abs() < 0.01, "{tracked}");
+    }
+
+    #[test]
+    fn half_filled_grid_halves_the_optical_depth() {
+        // dense in the half of the sphere with z > 0 only
+        let mut values = vec![0.0; 4 * 4 * 4];
+        for (i, value) in values.iter_mut().enumerate() {
+            if i / 16 >= 2 {
+                *value = 1.0;
+            }
+        }
+        let grid = Arc::new(VoxelGrid::new([4, 4, 4], cube(), values));
+        let medium = HeterogeneousMedium::new_from_color(unit_sphere(), 2.0, grid, Color::WHITE);
+
+        // the density ramps up linearly between the centers of the voxels on each side
+        // of z = 0, which keeps the optical depth at 2 * 1 over the ray
+        let expected = (-2.0f64).exp();
+        let (traced, tracked) = transmittances(&medium);
+        assert!((traced - expected).abs() < 0.01, "{traced}");
+        assert!((tracked - expected).abs() < 0.01, "{tracked}");
+    }
+}
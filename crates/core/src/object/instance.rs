@@ -0,0 +1,178 @@
+use std::{any::Any, sync::Arc};
+
+use crate::{
+    AxisAlignedBoundingBox, Interval, Matrix4x4, Node, Ray, RenderContext,
+    material::Material,
+    object::{HitRecord, Span, Transform},
+};
+
+/// Copy of a shared object, the prototype, placed with its own transformation and
+/// optionally another material.
+///
+/// Instances only reference the prototype, so thousands of copies of a detailed
+/// mesh cost its memory once. A prototype made of many nodes should be a
+/// [`BoundingVolumeHierarchy`](crate::object::BoundingVolumeHierarchy), shared by
+/// the instances too, while the hierarchy of the scene is built over the bounding
+/// boxes of the instances.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use caustic_core::{
+///     Color, Matrix4x4, Node, Vector3,
+///     material::Lambertian,
+///     object::{BoundingVolumeHierarchy, Instance, Sphere},
+/// };
+///
+/// let white = Arc::new(Lambertian::new_from_color(Color::WHITE));
+/// let red = Arc::new(Lambertian::new_from_color(Color::new(0.8, 0.1, 0.1)));
+/// let prototype: Arc<dyn Node> = Arc::new(Sphere::new(Vector3::ZERO, 0.4, white));
+/// let instances: Vec<Arc<dyn Node>> = (0..100)
+///     .map(|i| {
+///         let offset = Vector3::new((i % 10) as f64, 0.0, (i / 10) as f64);
+///         let instance = Instance::new(prototype.clone(), Matrix4x4::translation(offset));
+///         let instance = if i % 2 == 0 {
+///             instance.with_material(red.clone())
+///         } else {
+///             instance
+///         };
+///         Arc::new(instance) as Arc<dyn Node>
+///     })
+///     .collect();
+/// let world = BoundingVolumeHierarchy::new(&instances);
+/// ```
+#[derive(Debug)]
+pub struct Instance {
+    transform: Transform,
+    material: Option<Arc<dyn Material>>,
+}
+
+impl Instance {
+    pub fn new(prototype: Arc<dyn Node>, matrix: Matrix4x4) -> Self {
+        Self {
+            transform: Transform::new(prototype, matrix),
+            material: None,
+        }
+    }
+
+    /// Replaces the materials of the prototype with `material` for this instance.
+    pub fn with_material(mut self, material: Arc<dyn Material>) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    /// Returns the shared object this instance is a copy of.
+    pub fn prototype(&self) -> &Arc<dyn Node> {
+        self.transform.object()
+    }
+
+    /// Returns the matrix taking points of the prototype to world space.
+    pub fn matrix(&self) -> &Matrix4x4 {
+        self.transform.matrix()
+    }
+
+    /// Returns the material replacing the materials of the prototype, if any.
+    pub fn material(&self) -> Option<&Arc<dyn Material>> {
+        self.material.as_ref()
+    }
+
+    fn override_material(&self, mut hit: HitRecord) -> HitRecord {
+        if let Some(material) = &self.material {
+            hit.material = material.clone();
+        }
+        hit
+    }
+}
+
+impl Node for Instance {
+    fn hit(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let hit = self.transform.hit(ctx, ray, ray_t)?;
+        Some(self.override_material(hit))
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        self.transform.bounding_box()
+    }
+
+    fn transmittance(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> f64 {
+        self.transform.transmittance(ctx, ray, ray_t)
+    }
+
+    fn solid_spans(&self, ctx: &RenderContext, ray: &Ray) -> Vec<Span> {
+        self.transform
+            .solid_spans(ctx, ray)
+            .into_iter()
+            .map(|span| Span {
+                enter: self.override_material(span.enter),
+                exit: self.override_material(span.exit),
+            })
+            .collect()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        Color, SeededRandom, Vector3,
+        material::Lambertian,
+        object::{BoundingVolumeHierarchy, MeshData, MeshFace, TriangleMesh},
+    };
+
+    use super::*;
+
+    #[test]
+    fn instances_share_the_prototype() {
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let white: Arc<dyn Material> = Arc::new(Lambertian::new_from_color(Color::WHITE));
+        let red: Arc<dyn Material> =
+            Arc::new(Lambertian::new_from_color(Color::new(1.0, 0.0, 0.0)));
+        // a unit square facing +z, as two triangles
+        let data = Arc::new(MeshData {
+            positions: vec![
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(1.0, 0.0, 0.0),
+                Vector3::new(1.0, 1.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+            ],
+            normals: vec![],
+            uvs: vec![],
+        });
+        let faces = vec![MeshFace::new([0, 1, 2]), MeshFace::new([0, 2, 3])];
+        let prototype: Arc<dyn Node> = Arc::new(TriangleMesh::new(data, faces, white.clone()));
+
+        let n = 10_000;
+        let instances: Vec<Arc<dyn Node>> = (0..n)
+            .map(|i| {
+                let offset = Vector3::new((i % 100) as f64 * 2.0, (i / 100) as f64 * 2.0, 0.0);
+                let instance = Instance::new(prototype.clone(), Matrix4x4::translation(offset));
+                let instance = if i == 4321 {
+                    instance.with_material(red.clone())
+                } else {
+                    instance
+                };
+                Arc::new(instance) as Arc<dyn Node>
+            })
+            .collect();
+        assert_eq!(Arc::strong_count(&prototype), n + 1);
+
+        let world = BoundingVolumeHierarchy::new(&instances);
+        let hit_material = |x: f64, y: f64| {
+            let ray = Ray::new(Vector3::new(x, y, 5.0), Vector3::new(0.0, 0.0, -1.0));
+            world
+                .hit(&ctx, &ray, Interval::new(0.001, f64::INFINITY))
+                .map(|hit| hit.material)
+        };
+        // instance 4321 is at column 21, row 43
+        assert!(Arc::ptr_eq(&hit_material(42.5, 86.5).unwrap(), &red));
+        assert!(Arc::ptr_eq(&hit_material(40.5, 86.5).unwrap(), &white));
+        // between the squares
+        assert!(hit_material(41.5, 86.5).is_none());
+    }
+}
@@ -4,29 +4,41 @@ use crate::{
     AxisAlignedBoundingBox, Interval, RenderContext, material::Material, ray::Ray, vector::Vector3,
 };
 
+pub mod animated_transform;
 pub mod bounding_volume_hierarchy;
 pub mod box_node;
 pub mod cone;
 pub mod constant_medium;
+pub mod csg;
 pub mod disc;
 pub mod group;
+pub mod heterogeneous_medium;
+pub mod instance;
 pub mod quad;
 pub mod rotate;
 pub mod scale;
 pub mod sphere;
+pub mod transform;
 pub mod translate;
+pub mod triangle_mesh;
 
-pub use bounding_volume_hierarchy::BoundingVolumeHierarchy;
+pub use animated_transform::{AnimatedTransform, Keyframe};
+pub use bounding_volume_hierarchy::{BoundingVolumeHierarchy, BvhSplitMethod};
 pub use box_node::BoxPrimitive;
 pub use cone::ConeFrustum;
 pub use constant_medium::ConstantMedium;
+pub use csg::{Csg, CsgOperation, Span};
 pub use disc::Disc;
 pub use group::Group;
+pub use heterogeneous_medium::HeterogeneousMedium;
+pub use instance::Instance;
 pub use quad::Quad;
 pub use rotate::Rotate;
 pub use scale::Scale;
 pub use sphere::Sphere;
+pub use transform::Transform;
 pub use translate::Translate;
+pub use triangle_mesh::{MeshData, MeshFace, TriangleMesh};
 
 pub struct HitRecord {
     pub pt: Vector3,
@@ -56,6 +68,24 @@ pub trait Node: Send + Sync + Debug {
 
     fn bounding_box(&self) -> &AxisAlignedBoundingBox;
 
+    /// Returns the fraction of the light that goes through the node along `ray` within
+    /// `ray_t`, for shadow rays. Surfaces block all of it, participating media let
+    /// some through, and nodes containing others combine their transmittances.
+    fn transmittance(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> f64 {
+        if self.hit(ctx, ray, ray_t).is_some() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns the parts of the whole of `ray` inside the node, in order, for [`Csg`]
+    /// nodes. By default they are found from the successive hits of the node, which
+    /// must be closed with its normals facing out.
+    fn solid_spans(&self, ctx: &RenderContext, ray: &Ray) -> Vec<Span> {
+        csg::hit_spans(self, ctx, ray)
+    }
+
     fn pdf_value(&self, _ctx: &RenderContext, _origin: &Vector3, _direction: &Vector3) -> f64 {
         0.0
     }
@@ -240,7 +240,8 @@ impl Node for Quad {
     ///
     /// A direction vector from the origin to a randomly sampled point on the quad.
     fn random(&self, ctx: &RenderContext, origin: &Vector3) -> Vector3 {
-        let p = self.q + (ctx.random.rand() * self.u) + (ctx.random.rand() * self.v);
+        let (s, t) = ctx.random.rand_2d();
+        let p = self.q + (s * self.u) + (t * self.v);
         p - *origin
     }
 
@@ -17,55 +17,9 @@ pub struct Rotate {
 impl Rotate {
     /// Creates a rotation around an arbitrary axis
     pub fn new(object: Arc<dyn Node>, axis: Vector3, angle: f64) -> Self {
-        let radians = angle.to_radians();
-        let sin_theta = radians.sin();
-        let cos_theta = radians.cos();
-
-        // Normalize the axis
-        let axis = axis.unit();
-        let x = axis.x;
-        let y = axis.y;
-        let z = axis.z;
-
-        // Rodrigues' rotation formula to build rotation matrix
-        let one_minus_cos = 1.0 - cos_theta;
-
-        let rotation_matrix = Matrix3x3::new([
-            [
-                cos_theta + x * x * one_minus_cos,
-                x * y * one_minus_cos - z * sin_theta,
-                x * z * one_minus_cos + y * sin_theta,
-            ],
-            [
-                y * x * one_minus_cos + z * sin_theta,
-                cos_theta + y * y * one_minus_cos,
-                y * z * one_minus_cos - x * sin_theta,
-            ],
-            [
-                z * x * one_minus_cos - y * sin_theta,
-                z * y * one_minus_cos + x * sin_theta,
-                cos_theta + z * z * one_minus_cos,
-            ],
-        ]);
-
+        let rotation_matrix = Matrix3x3::rotation(axis, angle);
         // The inverse rotation is just the transpose for rotation matrices
-        let inverse_rotation_matrix = Matrix3x3::new([
-            [
-                rotation_matrix[0][0],
-                rotation_matrix[1][0],
-                rotation_matrix[2][0],
-            ],
-            [
-                rotation_matrix[0][1],
-                rotation_matrix[1][1],
-                rotation_matrix[2][1],
-            ],
-            [
-                rotation_matrix[0][2],
-                rotation_matrix[1][2],
-                rotation_matrix[2][2],
-            ],
-        ]);
+        let inverse_rotation_matrix = rotation_matrix.transpose();
 
         let obj_bbox = object.bounding_box();
         let bbox = Self::compute_bounding_box(obj_bbox, &rotation_matrix);
@@ -129,14 +83,18 @@ impl Rotate {
 
         AxisAlignedBoundingBox::new_from_points(min, max)
     }
+
+    /// Transforms the ray from world space to object space using inverse rotation.
+    fn object_ray(&self, ray: &Ray) -> Ray {
+        let origin = &self.inverse_rotation_matrix * ray.origin;
+        let direction = &self.inverse_rotation_matrix * ray.direction;
+        Ray::new_with_time(origin, direction, ray.time)
+    }
 }
 
 impl Node for Rotate {
     fn hit(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
-        // Transform the ray from world space to object space using inverse rotation
-        let origin = &self.inverse_rotation_matrix * ray.origin;
-        let direction = &self.inverse_rotation_matrix * ray.direction;
-        let rotated_r = Ray::new_with_time(origin, direction, ray.time);
+        let rotated_r = self.object_ray(ray);
 
         // Determine whether an intersection exists in object space
         let mut hit = self.object.hit(ctx, &rotated_r, ray_t)?;
@@ -152,6 +110,10 @@ impl Node for Rotate {
         &self.bbox
     }
 
+    fn transmittance(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> f64 {
+        self.object.transmittance(ctx, &self.object_ray(ray), ray_t)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -88,14 +88,20 @@ impl Scale {
             Interval::new(new_min_z, new_max_z),
         )
     }
+
+    /// Transforms the ray from world space to object space using the inverse scale
+    /// matrix.
+    fn object_ray(&self, ray: &Ray) -> Ray {
+        let origin = &self.inverse_scale_matrix * ray.origin;
+        let direction = &self.inverse_scale_matrix * ray.direction;
+        Ray::new_with_time(origin, direction, ray.time)
+    }
 }
 
 impl Node for Scale {
     fn hit(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
-        // 1. Transform the ray from world space to object space using the inverse scale matrix
-        let origin = &self.inverse_scale_matrix * ray.origin;
-        let direction = &self.inverse_scale_matrix * ray.direction;
-        let scaled_r = Ray::new_with_time(origin, direction, ray.time);
+        // 1. Transform the ray from world space to object space
+        let scaled_r = self.object_ray(ray);
 
         // 2. Determine whether an intersection exists in object space
         let mut hit = self.object.hit(ctx, &scaled_r, ray_t)?;
@@ -120,6 +126,10 @@ impl Node for Scale {
         &self.bbox
     }
 
+    fn transmittance(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> f64 {
+        self.object.transmittance(ctx, &self.object_ray(ray), ray_t)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
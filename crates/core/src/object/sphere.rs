@@ -83,8 +83,7 @@ impl Sphere {
     }
 
     fn random_to_sphere(random: &dyn Random, radius: f64, distance_squared: f64) -> Vector3 {
-        let r1 = random.rand();
-        let r2 = random.rand();
+        let (r1, r2) = random.rand_2d();
         let z = 1.0 + r2 * ((1.0 - radius * radius / distance_squared).sqrt() - 1.0);
 
         let phi = 2.0 * f64::consts::PI * r1;
@@ -0,0 +1,213 @@
+use std::{any::Any, sync::Arc};
+
+use crate::{
+    Axis, AxisAlignedBoundingBox, Interval, Matrix3x3, Matrix4x4, Node, Ray, RenderContext,
+    Vector3,
+    object::{HitRecord, Span},
+};
+
+/// Affine transformation of an object by a 4x4 matrix, which can translate, rotate,
+/// scale, mirror and shear it in a single step.
+///
+/// A chain of transformations costs a single hop for each ray once their matrices
+/// are multiplied together. Matrices that flatten the object, like a scale of zero
+/// along an axis, leave nothing to hit.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use caustic_core::{
+///     Color, Matrix4x4, Vector3,
+///     material::Lambertian,
+///     object::{BoxPrimitive, Transform},
+/// };
+///
+/// let material = Arc::new(Lambertian::new_from_color(Color::new(0.73, 0.73, 0.73)));
+/// let cube = Arc::new(BoxPrimitive::new(
+///     Vector3::ZERO,
+///     Vector3::new(165.0, 330.0, 165.0),
+///     material,
+/// ));
+/// let matrix = &Matrix4x4::translation(Vector3::new(265.0, 0.0, 295.0))
+///     * &Matrix4x4::rotation(Vector3::new(0.0, 1.0, 0.0), 15.0);
+/// let placed = Transform::new(cube, matrix);
+/// ```
+#[derive(Debug)]
+pub struct Transform {
+    object: Arc<dyn Node>,
+    matrix: Matrix4x4,
+    /// Inverse of the matrix, `None` if the object is flattened
+    inverse: Option<Matrix4x4>,
+    /// Transpose of the inverse of the linear part, which transforms normals
+    normal_matrix: Matrix3x3,
+    bbox: AxisAlignedBoundingBox,
+}
+
+impl Transform {
+    pub fn new(object: Arc<dyn Node>, matrix: Matrix4x4) -> Self {
+        let inverse = matrix.inverse();
+        let normal_matrix = inverse
+            .map(|inverse| inverse.linear_part().transpose())
+            .unwrap_or(Matrix3x3::IDENTITY);
+        let bbox = Self::compute_bounding_box(object.bounding_box(), &matrix);
+        Self {
+            object,
+            matrix,
+            inverse,
+            normal_matrix,
+            bbox,
+        }
+    }
+
+    /// Returns the transformed object.
+    pub fn object(&self) -> &Arc<dyn Node> {
+        &self.object
+    }
+
+    /// Returns the matrix taking points of the object to world space.
+    pub fn matrix(&self) -> &Matrix4x4 {
+        &self.matrix
+    }
+
+    /// Bounds the transformed corners of the bounding box of the object.
+    fn compute_bounding_box(
+        original_bbox: &AxisAlignedBoundingBox,
+        matrix: &Matrix4x4,
+    ) -> AxisAlignedBoundingBox {
+        let mut min = Vector3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+        let mut max = -min;
+        for i in 0..8 {
+            let corner = |axis, bit| {
+                let interval = original_bbox.axis_interval(axis);
+                if i & bit == 0 {
+                    interval.min
+                } else {
+                    interval.max
+                }
+            };
+            let pt = matrix.transform_point(Vector3::new(
+                corner(Axis::X, 1),
+                corner(Axis::Y, 2),
+                corner(Axis::Z, 4),
+            ));
+            min = Vector3::new(min.x.min(pt.x), min.y.min(pt.y), min.z.min(pt.z));
+            max = Vector3::new(max.x.max(pt.x), max.y.max(pt.y), max.z.max(pt.z));
+        }
+        AxisAlignedBoundingBox::new_from_points(min, max)
+    }
+
+    /// Transforms the ray from world space to object space with the inverse matrix.
+    fn object_ray(&self, ray: &Ray) -> Option<Ray> {
+        let inverse = self.inverse.as_ref()?;
+        Some(Ray::new_with_time(
+            inverse.transform_point(ray.origin),
+            inverse.transform_vector(ray.direction),
+            ray.time,
+        ))
+    }
+
+    /// Moves a hit of the object ray back to world space.
+    fn world_hit(&self, mut hit: HitRecord) -> HitRecord {
+        hit.pt = self.matrix.transform_point(hit.pt);
+        hit.normal = (&self.normal_matrix * hit.normal).unit();
+        hit
+    }
+}
+
+impl Node for Transform {
+    fn hit(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let object_r = self.object_ray(ray)?;
+
+        // the ray parameter is the same in both spaces, only the hit needs moving back
+        let hit = self.object.hit(ctx, &object_r, ray_t)?;
+        Some(self.world_hit(hit))
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        &self.bbox
+    }
+
+    fn transmittance(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> f64 {
+        match self.object_ray(ray) {
+            Some(object_r) => self.object.transmittance(ctx, &object_r, ray_t),
+            None => 1.0,
+        }
+    }
+
+    fn solid_spans(&self, ctx: &RenderContext, ray: &Ray) -> Vec<Span> {
+        let Some(object_r) = self.object_ray(ray) else {
+            return vec![];
+        };
+        self.object
+            .solid_spans(ctx, &object_r)
+            .into_iter()
+            .map(|span| Span {
+                enter: self.world_hit(span.enter),
+                exit: self.world_hit(span.exit),
+            })
+            .collect()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Color, SeededRandom, material::Lambertian, object::Sphere};
+
+    use super::*;
+
+    #[test]
+    fn hits_the_transformed_object() {
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let material = Arc::new(Lambertian::new_from_color(Color::WHITE));
+        let sphere = Arc::new(Sphere::new(Vector3::ZERO, 1.0, material));
+        // an ellipsoid 2 wide along x, sheared and moved to x = 5
+        let matrix = &Matrix4x4::translation(Vector3::new(5.0, 0.0, 0.0))
+            * &Matrix4x4::new([
+                [2.0, 0.5, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ]);
+        let transform = Transform::new(sphere, matrix);
+
+        let ray = Ray::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        let hit = transform
+            .hit(&ctx, &ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        assert!((hit.pt.x - 3.0).abs() < 1e-9, "{:?}", hit.pt);
+        // the normal is perpendicular to the surface, which is sheared along x
+        let tangent = matrix.transform_vector(Vector3::new(0.0, 1.0, 0.0));
+        assert!(hit.normal.dot(&tangent).abs() < 1e-9);
+        assert!(hit.normal.x < 0.0 && hit.normal.y > 0.0, "{:?}", hit.normal);
+
+        let bbox = transform.bounding_box();
+        let x = bbox.axis_interval(Axis::X);
+        assert!(
+            (x.min - 2.5).abs() < 1e-9 && (x.max - 7.5).abs() < 1e-9,
+            "{x:?}"
+        );
+    }
+
+    #[test]
+    fn flattened_objects_are_not_hit() {
+        let ctx = RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        };
+        let material = Arc::new(Lambertian::new_from_color(Color::WHITE));
+        let sphere = Arc::new(Sphere::new(Vector3::ZERO, 1.0, material));
+        let transform = Transform::new(sphere, Matrix4x4::scaling(Vector3::new(1.0, 0.0, 1.0)));
+        let ray = Ray::new(Vector3::new(0.0, -5.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+        assert!(
+            transform
+                .hit(&ctx, &ray, Interval::new(0.001, f64::INFINITY))
+                .is_none()
+        );
+    }
+}
@@ -20,12 +20,16 @@ impl Translate {
             bbox,
         }
     }
+
+    /// Moves the ray backwards by the offset, into the space of the object.
+    fn object_ray(&self, ray: &Ray) -> Ray {
+        Ray::new_with_time(ray.origin - self.offset, ray.direction, ray.time)
+    }
 }
 
 impl Node for Translate {
     fn hit(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
-        // Move the ray backwards by the offset
-        let offset_r = Ray::new_with_time(ray.origin - self.offset, ray.direction, ray.time);
+        let offset_r = self.object_ray(ray);
 
         // Determine whether an intersection exists along the offset ray (and if so, where)
         let mut hit = self.object.hit(ctx, &offset_r, ray_t)?;
@@ -40,6 +44,10 @@ impl Node for Translate {
         &self.bbox
     }
 
+    fn transmittance(&self, ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> f64 {
+        self.object.transmittance(ctx, &self.object_ray(ray), ray_t)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
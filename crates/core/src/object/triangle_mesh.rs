@@ -0,0 +1,536 @@
+use core::f64;
+use std::{any::Any, sync::Arc};
+
+use crate::{
+    AxisAlignedBoundingBox, Interval, Node, Ray, RenderContext, Vector3,
+    material::Material,
+    object::{
+        HitRecord,
+        bounding_volume_hierarchy::{BvhSplitMethod, FlatBvh},
+    },
+};
+
+/// Maximum number of triangles stored in a single leaf of the mesh BVH.
+const MAX_LEAF_TRIANGLES: usize = 4;
+
+/// Vertex buffers shared by one or more [`TriangleMesh`] nodes.
+///
+/// Positions, normals and texture coordinates are stored in separate buffers and
+/// referenced by index from each [`MeshFace`], so several meshes (for example one
+/// per material of an imported model) can share the same buffers.
+#[derive(Debug, Default)]
+pub struct MeshData {
+    /// Vertex positions
+    pub positions: Vec<Vector3>,
+    /// Vertex normals used for smooth shading
+    pub normals: Vec<Vector3>,
+    /// Vertex texture coordinates as `(u, v)`
+    pub uvs: Vec<(f64, f64)>,
+}
+
+/// A single triangle of a [`TriangleMesh`], described by indices into [`MeshData`].
+#[derive(Debug, Clone, Copy)]
+pub struct MeshFace {
+    /// Indices into [`MeshData::positions`], counter-clockwise when viewed from the front
+    pub positions: [usize; 3],
+    /// Optional indices into [`MeshData::normals`]. When absent the face is flat shaded.
+    pub normals: Option<[usize; 3]>,
+    /// Optional indices into [`MeshData::uvs`]. When absent the barycentric
+    /// coordinates of the hit are used as UVs.
+    pub uvs: Option<[usize; 3]>,
+}
+
+impl MeshFace {
+    /// Creates a flat shaded face without texture coordinates.
+    pub fn new(positions: [usize; 3]) -> Self {
+        Self {
+            positions,
+            normals: None,
+            uvs: None,
+        }
+    }
+}
+
+/// A triangle mesh with its own internal bounding volume hierarchy.
+///
+/// The mesh is a single [`Node`] in the scene regardless of how many triangles it
+/// contains. Intersection is accelerated by a flattened BVH built over the triangles
+/// when the mesh is created. Faces that carry vertex normals are smooth shaded by
+/// interpolating the normals across the triangle.
+///
+/// The mesh can also be used as an area light: [`Node::random`] samples a point
+/// uniformly over the surface area of the mesh.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use caustic_core::{
+///     Interval, Node, Ray, RenderContext, Vector3, random_new,
+///     material::EmptyMaterial,
+///     object::{MeshData, MeshFace, TriangleMesh},
+/// };
+///
+/// let data = Arc::new(MeshData {
+///     positions: vec![
+///         Vector3::new(0.0, 0.0, 0.0),
+///         Vector3::new(1.0, 0.0, 0.0),
+///         Vector3::new(0.0, 1.0, 0.0),
+///     ],
+///     ..Default::default()
+/// });
+/// let mesh = TriangleMesh::new(data, vec![MeshFace::new([0, 1, 2])], Arc::new(EmptyMaterial::new()));
+///
+/// let ctx = RenderContext { random: random_new() };
+/// let ray = Ray::new(Vector3::new(0.25, 0.25, 1.0), Vector3::new(0.0, 0.0, -1.0));
+/// let hit = mesh.hit(&ctx, &ray, Interval::new(0.001, f64::INFINITY)).unwrap();
+/// assert!((hit.t - 1.0).abs() < 1e-10);
+/// assert!(hit.front_face);
+/// ```
+#[derive(Debug)]
+pub struct TriangleMesh {
+    /// Shared vertex buffers
+    data: Arc<MeshData>,
+    /// Faces, reordered so that each BVH leaf references a contiguous range
+    faces: Vec<MeshFace>,
+    /// Flattened BVH over the faces
+    bvh: FlatBvh,
+    /// Running sum of face areas, used to sample faces proportionally to their area
+    area_cdf: Vec<f64>,
+    /// Total surface area of the mesh
+    area: f64,
+    /// Surface material for rendering
+    material: Arc<dyn Material>,
+    /// Axis-aligned bounding box containing every face
+    bbox: AxisAlignedBoundingBox,
+}
+
+impl TriangleMesh {
+    /// Creates a new triangle mesh and builds its acceleration structure.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Vertex buffers referenced by the faces
+    /// * `faces` - Triangles making up the mesh
+    /// * `material` - Material for the mesh's surface
+    pub fn new(data: Arc<MeshData>, faces: Vec<MeshFace>, material: Arc<dyn Material>) -> Self {
+        let bboxes: Vec<AxisAlignedBoundingBox> = faces
+            .iter()
+            .map(|face| Self::face_bbox(&data, face))
+            .collect();
+        let (bvh, order) = FlatBvh::build(
+            &bboxes,
+            BvhSplitMethod::SurfaceAreaHeuristic,
+            MAX_LEAF_TRIANGLES,
+        );
+        let faces: Vec<MeshFace> = order.iter().map(|i| faces[*i]).collect();
+
+        let mut area_cdf = Vec::with_capacity(faces.len());
+        let mut area = 0.0;
+        for face in &faces {
+            let (p0, p1, p2) = Self::face_positions(&data, face);
+            area += 0.5 * (p1 - p0).cross(&(p2 - p0)).length();
+            area_cdf.push(area);
+        }
+
+        let bbox = bvh.bounding_box();
+
+        Self {
+            data,
+            faces,
+            bvh,
+            area_cdf,
+            area,
+            material,
+            bbox,
+        }
+    }
+
+    /// Returns the number of triangles in the mesh.
+    pub fn face_count(&self) -> usize {
+        self.faces.len()
+    }
+
+    /// Returns the total surface area of the mesh.
+    pub fn area(&self) -> f64 {
+        self.area
+    }
+
+    /// Returns the material of the surface.
+    pub fn material(&self) -> &Arc<dyn Material> {
+        &self.material
+    }
+
+    fn face_positions(data: &MeshData, face: &MeshFace) -> (Vector3, Vector3, Vector3) {
+        (
+            data.positions[face.positions[0]],
+            data.positions[face.positions[1]],
+            data.positions[face.positions[2]],
+        )
+    }
+
+    fn face_normal(data: &MeshData, face: &MeshFace) -> Vector3 {
+        let (p0, p1, p2) = Self::face_positions(data, face);
+        (p1 - p0).cross(&(p2 - p0)).unit()
+    }
+
+    fn face_bbox(data: &MeshData, face: &MeshFace) -> AxisAlignedBoundingBox {
+        let (p0, p1, p2) = Self::face_positions(data, face);
+        AxisAlignedBoundingBox::new_from_bbox(
+            AxisAlignedBoundingBox::new_from_points(p0, p1),
+            AxisAlignedBoundingBox::new_from_points(p2, p2),
+        )
+    }
+
+    /// Intersects a ray with a single face using the Möller–Trumbore algorithm.
+    ///
+    /// # Returns
+    ///
+    /// `Some((t, b1, b2))` where `b1` and `b2` are the barycentric weights of the
+    /// second and third vertex, or `None` if the ray misses the face.
+    fn hit_face(&self, face: &MeshFace, ray: &Ray, ray_t: Interval) -> Option<(f64, f64, f64)> {
+        let (p0, p1, p2) = Self::face_positions(&self.data, face);
+        let edge1 = p1 - p0;
+        let edge2 = p2 - p0;
+
+        let p_vec = ray.direction.cross(&edge2);
+        let det = edge1.dot(&p_vec);
+
+        // No hit if the ray is parallel to the triangle.
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        let t_vec = ray.origin - p0;
+        let b1 = t_vec.dot(&p_vec) * inv_det;
+        if !(0.0..=1.0).contains(&b1) {
+            return None;
+        }
+
+        let q_vec = t_vec.cross(&edge1);
+        let b2 = ray.direction.dot(&q_vec) * inv_det;
+        if b2 < 0.0 || b1 + b2 > 1.0 {
+            return None;
+        }
+
+        let t = edge2.dot(&q_vec) * inv_det;
+        if !ray_t.contains(t) {
+            return None;
+        }
+
+        Some((t, b1, b2))
+    }
+
+    /// Returns the index of the closest face hit by the ray, the distance to it and
+    /// the barycentric weights of the hit.
+    fn hit_closest_face(&self, ray: &Ray, ray_t: Interval) -> Option<(usize, f64, f64, f64)> {
+        self.bvh.hit(ray, ray_t, |i, ray_t| {
+            self.hit_face(&self.faces[i], ray, ray_t)
+                .map(|(t, b1, b2)| (t, (i, t, b1, b2)))
+        })
+    }
+
+    fn create_hit_record(&self, face: &MeshFace, ray: &Ray, t: f64, b1: f64, b2: f64) -> HitRecord {
+        let b0 = 1.0 - b1 - b2;
+        let geometric_normal = Self::face_normal(&self.data, face);
+
+        let (u, v) = match face.uvs {
+            Some(uvs) => {
+                let uv0 = self.data.uvs[uvs[0]];
+                let uv1 = self.data.uvs[uvs[1]];
+                let uv2 = self.data.uvs[uvs[2]];
+                (
+                    b0 * uv0.0 + b1 * uv1.0 + b2 * uv2.0,
+                    b0 * uv0.1 + b1 * uv1.1 + b2 * uv2.1,
+                )
+            }
+            None => (b1, b2),
+        };
+
+        let mut hit = HitRecord {
+            pt: ray.at(t),
+            normal: Vector3::ZERO,
+            t,
+            u,
+            v,
+            front_face: false,
+            material: self.material.clone(),
+        };
+        hit.set_face_normal(ray, geometric_normal);
+
+        // Smooth shading: interpolate the vertex normals and orient the result to
+        // the same side as the geometric normal.
+        if let Some(normals) = face.normals {
+            let shading_normal = b0 * self.data.normals[normals[0]]
+                + b1 * self.data.normals[normals[1]]
+                + b2 * self.data.normals[normals[2]];
+            if !shading_normal.is_near_zero() {
+                let shading_normal = shading_normal.unit();
+                hit.normal = if shading_normal.dot(&hit.normal) < 0.0 {
+                    -shading_normal
+                } else {
+                    shading_normal
+                };
+            }
+        }
+
+        hit
+    }
+}
+
+impl Node for TriangleMesh {
+    fn hit(&self, _ctx: &RenderContext, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+        let (i, t, b1, b2) = self.hit_closest_face(ray, ray_t)?;
+        Some(self.create_hit_record(&self.faces[i], ray, t, b1, b2))
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        &self.bbox
+    }
+
+    /// Calculates the solid angle PDF of sampling the mesh from `origin`.
+    ///
+    /// Points are sampled uniformly over the total surface area, so the PDF is
+    /// `distance² / (cosine * area)` at the first surface hit along `direction`, the
+    /// cosine being with the normal of the face, not the interpolated one of smooth
+    /// shading.
+    fn pdf_value(&self, _ctx: &RenderContext, origin: &Vector3, direction: &Vector3) -> f64 {
+        if self.area <= 0.0 {
+            return 0.0;
+        }
+
+        let Some((i, t, _, _)) = self.hit_closest_face(
+            &Ray::new(*origin, *direction),
+            Interval::new(0.001, f64::INFINITY),
+        ) else {
+            return 0.0;
+        };
+
+        let distance_squared = t * t * direction.length_squared();
+        let normal = Self::face_normal(&self.data, &self.faces[i]);
+        let cosine = (direction.dot(&normal) / direction.length()).abs();
+        if cosine < 1e-8 {
+            return 0.0;
+        }
+
+        distance_squared / (cosine * self.area)
+    }
+
+    /// Generates a direction from `origin` towards a point sampled uniformly over the
+    /// surface area of the mesh.
+    fn random(&self, ctx: &RenderContext, origin: &Vector3) -> Vector3 {
+        if self.faces.is_empty() {
+            return Vector3::new(1.0, 0.0, 0.0);
+        }
+
+        // Pick a face proportionally to its area.
+        let target = ctx.random.rand() * self.area;
+        let index = self
+            .area_cdf
+            .partition_point(|v| *v < target)
+            .min(self.faces.len() - 1);
+        let (p0, p1, p2) = Self::face_positions(&self.data, &self.faces[index]);
+
+        // Uniformly sample a point on the face.
+        let (r1, r2) = ctx.random.rand_2d();
+        let su = r1.sqrt();
+        let b1 = r2 * su;
+        let b0 = 1.0 - su;
+        let p = b0 * p0 + b1 * p1 + (1.0 - b0 - b1) * p2;
+        p - *origin
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{SeededRandom, material::EmptyMaterial};
+
+    use super::*;
+
+    fn ctx() -> RenderContext {
+        RenderContext {
+            random: Arc::new(SeededRandom::new(1)),
+        }
+    }
+
+    /// A mesh of `faces` over `positions`, with no normals or texture coordinates.
+    fn mesh(positions: Vec<Vector3>, faces: Vec<MeshFace>) -> TriangleMesh {
+        let data = Arc::new(MeshData {
+            positions,
+            ..Default::default()
+        });
+        TriangleMesh::new(data, faces, Arc::new(EmptyMaterial::new()))
+    }
+
+    /// The triangle (0, 0, 0), (1, 0, 0), (0, 1, 0), facing +z.
+    fn triangle() -> TriangleMesh {
+        mesh(
+            vec![
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(1.0, 0.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+            ],
+            vec![MeshFace::new([0, 1, 2])],
+        )
+    }
+
+    #[test]
+    fn hits_inside_the_face() {
+        let mesh = triangle();
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        let ray = Ray::new(Vector3::new(0.2, 0.3, 2.0), Vector3::new(0.0, 0.0, -1.0));
+        let hit = mesh.hit(&ctx(), &ray, ray_t).unwrap();
+        assert!((hit.t - 2.0).abs() < 1e-12);
+        assert!(hit.front_face);
+        assert_eq!(hit.normal, Vector3::new(0.0, 0.0, 1.0));
+        // without texture coordinates the UVs are the barycentric coordinates
+        assert!((hit.u - 0.2).abs() < 1e-12 && (hit.v - 0.3).abs() < 1e-12);
+
+        // from behind, the normal faces the ray
+        let ray = Ray::new(Vector3::new(0.2, 0.3, -2.0), Vector3::new(0.0, 0.0, 1.0));
+        let hit = mesh.hit(&ctx(), &ray, ray_t).unwrap();
+        assert!(!hit.front_face);
+        assert_eq!(hit.normal, Vector3::new(0.0, 0.0, -1.0));
+
+        // outside of the interval
+        assert!(mesh.hit(&ctx(), &ray, Interval::new(0.001, 1.0)).is_none());
+    }
+
+    #[test]
+    fn misses_outside_the_face() {
+        let mesh = triangle();
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+        let down = Vector3::new(0.0, 0.0, -1.0);
+
+        // beyond each edge
+        for (x, y) in [(-0.1, 0.5), (0.5, -0.1), (0.6, 0.6)] {
+            let ray = Ray::new(Vector3::new(x, y, 1.0), down);
+            assert!(mesh.hit(&ctx(), &ray, ray_t).is_none(), "{x} {y}");
+        }
+        // edge-on, in the plane of the face and across it
+        let ray = Ray::new(Vector3::new(-1.0, 0.25, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert!(mesh.hit(&ctx(), &ray, ray_t).is_none());
+        // pointing away
+        let ray = Ray::new(Vector3::new(0.2, 0.2, 1.0), -down);
+        assert!(mesh.hit(&ctx(), &ray, ray_t).is_none());
+    }
+
+    #[test]
+    fn interpolates_vertex_normals() {
+        let normals = vec![
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(1.0, 0.0, 1.0).unit(),
+            Vector3::new(0.0, 1.0, 1.0).unit(),
+        ];
+        let data = Arc::new(MeshData {
+            positions: vec![
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(1.0, 0.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+            ],
+            normals: normals.clone(),
+            uvs: vec![],
+        });
+        let face = MeshFace {
+            positions: [0, 1, 2],
+            normals: Some([0, 1, 2]),
+            uvs: None,
+        };
+        let mesh = TriangleMesh::new(data, vec![face], Arc::new(EmptyMaterial::new()));
+        let ray_t = Interval::new(0.001, f64::INFINITY);
+
+        let (b1, b2) = (0.5, 0.25);
+        let expected = ((1.0 - b1 - b2) * normals[0] + b1 * normals[1] + b2 * normals[2]).unit();
+        let ray = Ray::new(Vector3::new(b1, b2, 1.0), Vector3::new(0.0, 0.0, -1.0));
+        let hit = mesh.hit(&ctx(), &ray, ray_t).unwrap();
+        assert!(hit.front_face);
+        assert!((hit.normal - expected).length() < 1e-12, "{:?}", hit.normal);
+
+        // from behind the interpolated normal is flipped to face the ray
+        let ray = Ray::new(Vector3::new(b1, b2, -1.0), Vector3::new(0.0, 0.0, 1.0));
+        let hit = mesh.hit(&ctx(), &ray, ray_t).unwrap();
+        assert!(!hit.front_face);
+        assert!((hit.normal + expected).length() < 1e-12, "{:?}", hit.normal);
+    }
+
+    /// Solid angle of the triangle `a`, `b`, `c` seen from the origin, with the
+    /// formula of Van Oosterom and Strackee.
+    fn solid_angle(a: Vector3, b: Vector3, c: Vector3) -> f64 {
+        let (la, lb, lc) = (a.length(), b.length(), c.length());
+        let numerator = a.dot(&b.cross(&c)).abs();
+        let denominator = la * lb * lc + a.dot(&b) * lc + a.dot(&c) * lb + b.dot(&c) * la;
+        2.0 * numerator.atan2(denominator)
+    }
+
+    #[test]
+    fn pdf_value_matches_random() {
+        // a large and a small face, smooth shaded with tilted normals, which mustn't
+        // change how the area is sampled
+        let positions = vec![
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(0.0, 2.0, 0.0),
+            Vector3::new(-1.0, -1.0, 0.0),
+            Vector3::new(-0.5, -1.0, 0.0),
+            Vector3::new(-1.0, -0.5, 0.0),
+        ];
+        let tilted = Vector3::new(1.0, 1.0, 0.5).unit();
+        let data = Arc::new(MeshData {
+            positions: positions.clone(),
+            normals: vec![tilted],
+            uvs: vec![],
+        });
+        let faces = vec![
+            MeshFace {
+                positions: [0, 1, 2],
+                normals: Some([0, 0, 0]),
+                uvs: None,
+            },
+            MeshFace {
+                positions: [3, 4, 5],
+                normals: Some([0, 0, 0]),
+                uvs: None,
+            },
+        ];
+        let mesh = TriangleMesh::new(data, faces, Arc::new(EmptyMaterial::new()));
+        assert!((mesh.area() - 2.125).abs() < 1e-12);
+
+        // the average of 1 / pdf over sampled directions is the solid angle of the mesh
+        let ctx = ctx();
+        let origin = Vector3::new(0.3, 0.2, 1.5);
+        let n = 100_000;
+        let mut sum = 0.0;
+        for _ in 0..n {
+            let direction = mesh.random(&ctx, &origin);
+            let pdf = mesh.pdf_value(&ctx, &origin, &direction);
+            assert!(pdf > 0.0);
+            sum += 1.0 / pdf;
+        }
+        let expected = solid_angle(
+            positions[0] - origin,
+            positions[1] - origin,
+            positions[2] - origin,
+        ) + solid_angle(
+            positions[3] - origin,
+            positions[4] - origin,
+            positions[5] - origin,
+        );
+        let estimate = sum / n as f64;
+        assert!(
+            (estimate - expected).abs() < expected * 0.01,
+            "{estimate} {expected}"
+        );
+
+        // directions missing the mesh are never sampled
+        assert_eq!(
+            mesh.pdf_value(&ctx, &origin, &Vector3::new(0.0, 0.0, 1.0)),
+            0.0
+        );
+    }
+}
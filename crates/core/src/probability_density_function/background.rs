@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use crate::{ProbabilityDensityFunction, RenderContext, Vector3, background::Background};
+
+/// Samples directions towards the bright parts of an importance sampled
+/// [`Background`].
+pub struct BackgroundPdf {
+    background: Arc<dyn Background>,
+}
+
+impl BackgroundPdf {
+    pub fn new(background: Arc<dyn Background>) -> Self {
+        Self { background }
+    }
+}
+
+impl ProbabilityDensityFunction for BackgroundPdf {
+    fn value(&self, _ctx: &RenderContext, direction: &Vector3) -> f64 {
+        self.background.pdf_value(direction)
+    }
+
+    fn generate(&self, ctx: &RenderContext) -> Vector3 {
+        self.background.random(&*ctx.random)
+    }
+}
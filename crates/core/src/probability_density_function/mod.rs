@@ -1,8 +1,10 @@
+pub mod background;
 pub mod cosine;
 pub mod hittable;
 pub mod mixture;
 pub mod sphere;
 
+pub use background::BackgroundPdf;
 pub use cosine::CosinePdf;
 pub use hittable::HittablePdf;
 pub use mixture::MixturePdf;
@@ -16,3 +18,21 @@ pub trait ProbabilityDensityFunction: Send + Sync {
     fn value(&self, ctx: &RenderContext, direction: &Vector3) -> f64;
     fn generate(&self, ctx: &RenderContext) -> Vector3;
 }
+
+/// Veach's power heuristic with exponent 2: the weight of a sample drawn with
+/// density `pdf` when `other_pdf` could also have drawn it.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::probability_density_function::power_heuristic;
+///
+/// assert_eq!(power_heuristic(1.0, 0.0), 1.0);
+/// assert_eq!(power_heuristic(1.0, 1.0), 0.5);
+/// assert_eq!(power_heuristic(2.0, 1.0) + power_heuristic(1.0, 2.0), 1.0);
+/// ```
+pub fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let pdf2 = pdf * pdf;
+    let sum = pdf2 + other_pdf * other_pdf;
+    if sum > 0.0 { pdf2 / sum } else { 0.0 }
+}
@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use crate::{Camera, Color, Node, RenderContext, light::Lights};
+
+/// Renders a scene in passes over the whole image, accumulating samples.
+///
+/// Each call to [`ProgressiveRenderer::render_pass`] adds the same number of
+/// samples to every pixel, so the current average is a complete, if noisy, image
+/// that refines with every pass. The samples of consecutive passes are stratified
+/// together, see [`Camera::render_sample`]. Adaptive sampling is not applied.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use caustic_core::{
+///     CameraBuilder, ProgressiveRenderer, RenderContext, light::Lights, object::Group, random_new,
+/// };
+///
+/// let ctx = RenderContext { random: random_new() };
+/// let mut camera_builder = CameraBuilder::new();
+/// camera_builder.image_width = 16;
+/// camera_builder.samples_per_pixel = 4;
+/// let camera = Arc::new(camera_builder.build());
+///
+/// let mut renderer = ProgressiveRenderer::new(camera, Arc::new(Group::new()), Lights::default());
+/// while !renderer.is_complete() {
+///     renderer.render_pass(&ctx, 1);
+///     let _preview = renderer.image();
+/// }
+/// assert_eq!(renderer.sample_count(), 4);
+/// ```
+#[derive(Debug)]
+pub struct ProgressiveRenderer {
+    camera: Arc<Camera>,
+    world: Arc<dyn Node>,
+    lights: Lights,
+    /// Sum of the linear samples of each pixel, row-major
+    accumulation: Vec<Color>,
+    /// Number of samples accumulated in every pixel
+    sample_count: u32,
+}
+
+impl ProgressiveRenderer {
+    pub fn new(camera: Arc<Camera>, world: Arc<dyn Node>, lights: Lights) -> Self {
+        let pixel_count = (camera.image_width() * camera.image_height()) as usize;
+        Self {
+            camera,
+            world,
+            lights,
+            accumulation: vec![Color::BLACK; pixel_count],
+            sample_count: 0,
+        }
+    }
+
+    /// Traces `samples` more samples for every pixel of the image.
+    pub fn render_pass(&mut self, ctx: &RenderContext, samples: u32) {
+        let width = self.camera.image_width();
+        for (i, pixel) in self.accumulation.iter_mut().enumerate() {
+            let x = i as u32 % width;
+            let y = i as u32 / width;
+            for sample_index in self.sample_count..self.sample_count + samples {
+                *pixel +=
+                    self.camera
+                        .render_sample(ctx, x, y, sample_index, &*self.world, &self.lights);
+            }
+        }
+        self.sample_count += samples;
+    }
+
+    /// Returns the number of samples accumulated in every pixel.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Returns true once the samples per pixel configured on the camera have been
+    /// accumulated. Further passes keep refining the image.
+    pub fn is_complete(&self) -> bool {
+        self.sample_count >= self.camera.samples_per_pixel()
+    }
+
+    /// Discards all accumulated samples.
+    pub fn reset(&mut self) {
+        self.accumulation.fill(Color::BLACK);
+        self.sample_count = 0;
+    }
+
+    /// Returns the current linear average of a pixel, black before the first pass.
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        let sum = self.accumulation[(y * self.camera.image_width() + x) as usize];
+        self.average(sum)
+    }
+
+    /// Returns the current linear average of every pixel, row-major.
+    pub fn image_linear(&self) -> Vec<Color> {
+        self.accumulation
+            .iter()
+            .map(|sum| self.average(*sum))
+            .collect()
+    }
+
+    /// Returns the current average of every pixel, row-major, passed through
+    /// [`Camera::display_transform`].
+    pub fn image(&self) -> Vec<Color> {
+        self.accumulation
+            .iter()
+            .map(|sum| self.camera.display_transform(self.average(*sum)))
+            .collect()
+    }
+
+    fn average(&self, sum: Color) -> Color {
+        if self.sample_count == 0 {
+            Color::BLACK
+        } else {
+            sum / self.sample_count as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::{
+        CameraBuilder, Color, RenderContext, background::GradientBackground, light::Lights,
+        object::Group, random_new,
+    };
+
+    use super::ProgressiveRenderer;
+
+    #[test]
+    fn accumulates_passes() {
+        let ctx = RenderContext {
+            random: random_new(),
+        };
+        let mut camera_builder = CameraBuilder::new();
+        camera_builder.image_width = 8;
+        camera_builder.samples_per_pixel = 9;
+        camera_builder.background = Arc::new(GradientBackground::new(Color::BLACK, Color::WHITE));
+        let camera = Arc::new(camera_builder.build());
+        let mut renderer =
+            ProgressiveRenderer::new(camera, Arc::new(Group::new()), Lights::default());
+
+        assert_eq!(renderer.pixel(0, 0), Color::BLACK);
+        renderer.render_pass(&ctx, 4);
+        assert!(!renderer.is_complete());
+        renderer.render_pass(&ctx, 5);
+        assert!(renderer.is_complete());
+        assert_eq!(renderer.sample_count(), 9);
+
+        // the camera looks along -z so the top row sees more of the white top
+        let top = renderer.pixel(4, 0);
+        let bottom = renderer.pixel(4, 7);
+        assert!(top.r > 0.5 && top.r > bottom.r);
+        assert_eq!(renderer.image_linear().len(), 64);
+
+        renderer.reset();
+        assert_eq!(renderer.sample_count(), 0);
+        assert_eq!(renderer.pixel(4, 0), Color::BLACK);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::vector::Vector3;
+use crate::{Color, vector::Vector3};
 
 /// Represents a ray in 3D space with an origin point, direction vector, and time.
 ///
@@ -30,12 +30,22 @@ pub struct Ray {
 
     /// The time at which this ray exists (for motion blur)
     pub time: f64,
+
+    /// The wavelength in nanometers carried by the rays of spectral paths, None for
+    /// RGB paths (see [`crate::spectrum`])
+    pub wavelength: Option<f64>,
+
+    /// The probabilities of the random walk through a medium the ray is a step of,
+    /// relative to their average, if each color channel had been sampled on its own.
+    /// None outside of walks (see [`crate::material::Subsurface`])
+    pub channel_pdfs: Option<Color>,
 }
 
 impl Ray {
     /// Creates a new ray with the given origin and direction.
     ///
-    /// The time is set to 0.0 by default.
+    /// The time is set to 0.0 by default, and the ray carries no wavelength nor random
+    /// walk.
     ///
     /// # Arguments
     ///
@@ -58,6 +68,8 @@ impl Ray {
             origin,
             direction,
             time: 0.0,
+            wavelength: None,
+            channel_pdfs: None,
         }
     }
 
@@ -86,6 +98,8 @@ impl Ray {
             origin,
             direction,
             time,
+            wavelength: None,
+            channel_pdfs: None,
         }
     }
 
@@ -0,0 +1,79 @@
+use crate::sampler::{SampleSequence, hash, sobol::sobol_pair_value, to_unit_float};
+
+/// Width and height, as a power of two, of the tiles of pixels sharing a sequence.
+const TILE_SIZE_LOG2: u32 = 6;
+
+/// A point of an Owen-scrambled Sobol sequence shared by a tile of pixels, which
+/// distributes the remaining error as blue noise over the image.
+///
+/// Each pixel takes a block of consecutive samples from the sequence, and the
+/// blocks are assigned along a Z-order curve, following Ahmed and Wonka,
+/// "Screen-Space Blue-Noise Diffusion of Monte Carlo Sampling Error via
+/// Hierarchical Ordering of Pixels" (2020). Every power of two block of samples of
+/// the sequence is well stratified, so the samples of neighboring pixels complement
+/// each other and their errors tend to cancel out, leaving fine grain instead of
+/// blotches. The scrambling of the sequence randomizes the order of the curve.
+#[derive(Debug)]
+pub struct BlueNoiseSequence {
+    index: u32,
+    seed: u32,
+}
+
+impl BlueNoiseSequence {
+    /// Creates sample `index` of pixel (`x`, `y`), where every pixel takes
+    /// `samples_per_pixel` samples. `seed` selects the scrambling of the sequence.
+    pub fn new(index: u32, x: u32, y: u32, samples_per_pixel: u32, seed: u32) -> Self {
+        let tile_mask = (1 << TILE_SIZE_LOG2) - 1;
+        let rank = morton_code(x & tile_mask, y & tile_mask);
+        let tile_seed = hash(&[seed, x >> TILE_SIZE_LOG2, y >> TILE_SIZE_LOG2]);
+
+        // samples past samples_per_pixel continue in a new block for the whole tile
+        let block_size = samples_per_pixel.max(1).next_power_of_two();
+        let round = index / block_size;
+        let tile_samples = block_size << (2 * TILE_SIZE_LOG2);
+        let index = round
+            .wrapping_mul(tile_samples)
+            .wrapping_add(rank * block_size)
+            .wrapping_add(index % block_size);
+
+        Self {
+            index,
+            seed: tile_seed,
+        }
+    }
+}
+
+impl SampleSequence for BlueNoiseSequence {
+    fn value(&self, dimension: u32) -> f64 {
+        to_unit_float(sobol_pair_value(self.index, self.seed, dimension))
+    }
+}
+
+/// Interleaves the bits of `x` and `y`, so every aligned block of 4^k codes is a
+/// 2^k x 2^k square.
+fn morton_code(x: u32, y: u32) -> u32 {
+    let spread = |mut v: u32| {
+        v &= 0x0000_ffff;
+        v = (v | (v << 8)) & 0x00ff_00ff;
+        v = (v | (v << 4)) & 0x0f0f_0f0f;
+        v = (v | (v << 2)) & 0x3333_3333;
+        (v | (v << 1)) & 0x5555_5555
+    };
+    spread(x) | (spread(y) << 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::morton_code;
+
+    #[test]
+    fn morton_code_blocks_are_squares() {
+        assert_eq!(morton_code(0, 0), 0);
+        assert_eq!(morton_code(1, 0), 1);
+        assert_eq!(morton_code(0, 1), 2);
+        assert_eq!(morton_code(1, 1), 3);
+        assert_eq!(morton_code(2, 0), 4);
+        assert_eq!(morton_code(3, 3), 15);
+        assert_eq!(morton_code(63, 63), 4095);
+    }
+}
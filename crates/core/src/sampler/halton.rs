@@ -0,0 +1,80 @@
+use crate::sampler::{SampleSequence, hash, mix_bits, sobol::nested_uniform_scramble};
+
+/// Smallest digit weight that is scrambled.
+const SCRAMBLE_PRECISION: f64 = 1.0 / 4_294_967_296.0;
+
+/// A point of the Owen-scrambled Halton sequence.
+///
+/// Every pair of dimensions is a 2D Halton point, the radical inverses of the
+/// sample index in bases 2 and 3, with every digit permuted depending on the digits
+/// before it. Like the [`crate::sampler::SobolSequence`], the pairs are decorrelated
+/// by shuffling the sample index with a different seed for each pair, which avoids
+/// the poor distribution of the Halton sequence in large bases.
+#[derive(Debug)]
+pub struct HaltonSequence {
+    index: u32,
+    seed: u32,
+}
+
+impl HaltonSequence {
+    /// Creates sample `index` of the sequence scrambled with `seed`.
+    pub fn new(index: u32, seed: u32) -> Self {
+        Self { index, seed }
+    }
+}
+
+impl SampleSequence for HaltonSequence {
+    fn value(&self, dimension: u32) -> f64 {
+        let pair_seed = hash(&[self.seed, dimension / 2]);
+        let index = nested_uniform_scramble(self.index, pair_seed);
+        let base = if dimension.is_multiple_of(2) { 2 } else { 3 };
+        owen_scrambled_radical_inverse(base, index, hash(&[pair_seed, dimension % 2]))
+    }
+}
+
+/// Mirrors the base `base` digits of `index` around the radix point, randomly
+/// shifting each digit with a shift that depends on the digits before it.
+fn owen_scrambled_radical_inverse(base: u32, mut index: u32, seed: u32) -> f64 {
+    let base = base as u64;
+    let inverse_base = 1.0 / base as f64;
+    let mut inverse_base_m = 1.0;
+    let mut reversed_digits: u64 = 0;
+    // keep going past the digits of the index up to the 32-bit precision of the
+    // Sobol sequence, trailing zeros are scrambled too
+    while inverse_base_m > SCRAMBLE_PRECISION {
+        let digit = index as u64 % base;
+        index /= base as u32;
+        let shift = mix_bits(((seed as u64) << 32) ^ reversed_digits);
+        reversed_digits = reversed_digits * base + (digit + shift) % base;
+        inverse_base_m *= inverse_base;
+    }
+    (reversed_digits as f64 * inverse_base_m).min(1.0 - f64::EPSILON / 2.0)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sampler::SampleSequence;
+
+    use super::HaltonSequence;
+
+    #[test]
+    fn pairs_are_stratified() {
+        for seed in [0, 7] {
+            for pair in 0..4 {
+                // the first 16 Halton points, in base 2 one in each column of 16, in
+                // base 3 one in each row of 9 for the first 9 of them
+                let mut columns = [0; 16];
+                let mut rows = [0; 3];
+                for i in 0..16 {
+                    let point = HaltonSequence::new(i, seed);
+                    let (u, v) = (point.value(pair * 2), point.value(pair * 2 + 1));
+                    assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v));
+                    columns[(u * 16.0) as usize] += 1;
+                    rows[(v * 3.0) as usize] += 1;
+                }
+                assert_eq!(columns, [1; 16]);
+                assert!(rows.iter().all(|r| (5..=6).contains(r)), "{rows:?}");
+            }
+        }
+    }
+}
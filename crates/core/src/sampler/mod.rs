@@ -0,0 +1,355 @@
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+};
+
+use crate::Random;
+
+pub mod blue_noise;
+pub mod halton;
+pub mod random;
+pub mod sobol;
+
+pub use blue_noise::BlueNoiseSequence;
+pub use halton::HaltonSequence;
+pub use random::RandomSampler;
+pub use sobol::SobolSequence;
+
+/// Number of dimensions reserved for the camera ray: the position in the pixel, the
+/// point on the lens, the time and the wavelength.
+pub const CAMERA_DIMENSIONS: u32 = 6;
+
+/// Number of dimensions reserved for the decisions made at each path vertex.
+pub const VERTEX_DIMENSIONS: u32 = 6;
+
+/// Source of the sample values used while tracing a single camera sample.
+///
+/// A sampler hands out sample dimensions in order. The camera takes the position in
+/// the pixel from the first two dimensions and calls [`Sampler::start_vertex`]
+/// before each bounce, so the same decision of different samples of a pixel uses
+/// the same dimension, where a low-discrepancy sequence spreads the values evenly.
+/// Materials and lights draw their values through the [`Random`] supertrait, which
+/// returns the next dimension, or the next pair for [`Random::rand_2d`].
+pub trait Sampler: Random {
+    /// Returns the next dimension of the sample, in [0, 1).
+    fn get_1d(&self) -> f64;
+
+    /// Returns the next two dimensions of the sample, stratified together, in
+    /// [0, 1)².
+    fn get_2d(&self) -> (f64, f64);
+
+    /// Skips to the dimensions reserved for path vertex `vertex`, 0 being the first
+    /// surface hit by the camera ray. Dimensions are never handed out twice, a vertex
+    /// that used more than [`VERTEX_DIMENSIONS`] pushes the following ones back.
+    fn start_vertex(&self, vertex: u32);
+}
+
+/// Kind of [`Sampler`] used by a camera.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{Random, SamplerType, random_new};
+///
+/// let sampler_type: SamplerType = "sobol".parse().unwrap();
+/// let sampler = sampler_type.start_sample(random_new(), 3, 4, 0, 16, 0);
+/// let (u, v) = sampler.get_2d();
+/// assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SamplerType {
+    /// Independent random values, with the pixel position stratified by the camera.
+    #[default]
+    Random,
+    /// Owen-scrambled Sobol points, padded from 2D pairs.
+    Sobol,
+    /// Owen-scrambled Halton points.
+    Halton,
+    /// Sobol points shared by neighboring pixels, which spreads the remaining error
+    /// as high-frequency noise over the image.
+    BlueNoise,
+}
+
+impl SamplerType {
+    /// Creates the sampler for sample `sample_index` of pixel (`x`, `y`), where every
+    /// pixel takes `samples_per_pixel` samples.
+    ///
+    /// `seed` selects the scrambling of the low-discrepancy sequences, renders using
+    /// the same seed see the same sample values. `random` provides the values of the
+    /// random sampler.
+    pub fn start_sample(
+        &self,
+        random: Arc<dyn Random>,
+        x: u32,
+        y: u32,
+        sample_index: u32,
+        samples_per_pixel: u32,
+        seed: u32,
+    ) -> Arc<dyn Sampler> {
+        let pixel_seed = hash(&[seed, x, y]);
+        match self {
+            SamplerType::Random => Arc::new(RandomSampler::new(random)),
+            SamplerType::Sobol => Arc::new(SequenceSampler::new(SobolSequence::new(
+                sample_index,
+                pixel_seed,
+            ))),
+            SamplerType::Halton => Arc::new(SequenceSampler::new(HaltonSequence::new(
+                sample_index,
+                pixel_seed,
+            ))),
+            SamplerType::BlueNoise => Arc::new(SequenceSampler::new(BlueNoiseSequence::new(
+                sample_index,
+                x,
+                y,
+                samples_per_pixel,
+                seed,
+            ))),
+        }
+    }
+}
+
+impl FromStr for SamplerType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "random" => Ok(SamplerType::Random),
+            "sobol" => Ok(SamplerType::Sobol),
+            "halton" => Ok(SamplerType::Halton),
+            "blue_noise" | "bluenoise" => Ok(SamplerType::BlueNoise),
+            _ => Err(format!("unknown sampler \"{s}\"")),
+        }
+    }
+}
+
+impl Display for SamplerType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SamplerType::Random => "random",
+            SamplerType::Sobol => "sobol",
+            SamplerType::Halton => "halton",
+            SamplerType::BlueNoise => "blue_noise",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// The dimensions of one sample point of a low-discrepancy sequence.
+pub trait SampleSequence: Debug + Send + Sync {
+    /// Returns `dimension` of the point in [0, 1).
+    fn value(&self, dimension: u32) -> f64;
+}
+
+/// A [`Sampler`] handing out the dimensions of a [`SampleSequence`] point in order.
+#[derive(Debug)]
+pub struct SequenceSampler<S: SampleSequence> {
+    sequence: S,
+    dimension: AtomicU32,
+}
+
+impl<S: SampleSequence> SequenceSampler<S> {
+    pub fn new(sequence: S) -> Self {
+        Self {
+            sequence,
+            dimension: AtomicU32::new(0),
+        }
+    }
+
+    fn value(&self, dimension: u32) -> f64 {
+        self.sequence.value(dimension)
+    }
+}
+
+impl<S: SampleSequence> Sampler for SequenceSampler<S> {
+    fn get_1d(&self) -> f64 {
+        self.value(self.dimension.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn get_2d(&self) -> (f64, f64) {
+        // pairs start on even dimensions, which are stratified together
+        let dimension = self.dimension.load(Ordering::Relaxed).next_multiple_of(2);
+        self.dimension.store(dimension + 2, Ordering::Relaxed);
+        (self.value(dimension), self.value(dimension + 1))
+    }
+
+    fn start_vertex(&self, vertex: u32) {
+        let start = CAMERA_DIMENSIONS + vertex * VERTEX_DIMENSIONS;
+        self.dimension.fetch_max(start, Ordering::Relaxed);
+    }
+}
+
+impl<S: SampleSequence> Random for SequenceSampler<S> {
+    fn rand(&self) -> f64 {
+        self.get_1d()
+    }
+
+    fn rand_interval(&self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.get_1d()
+    }
+
+    fn rand_2d(&self) -> (f64, f64) {
+        self.get_2d()
+    }
+
+    fn rand_int_interval(&self, min: i64, max: i64) -> i64 {
+        let range = (max - min) as f64;
+        (min + (self.get_1d() * range) as i64).min(max - 1)
+    }
+}
+
+/// Hashes a few words into one, used to derive independent scrambling seeds.
+pub(crate) fn hash(values: &[u32]) -> u32 {
+    let mut h: u64 = 0x9E37_79B9_7F4A_7C15;
+    for v in values {
+        h = mix_bits(h ^ *v as u64);
+    }
+    h as u32
+}
+
+pub(crate) fn mix_bits(mut v: u64) -> u64 {
+    v ^= v >> 31;
+    v = v.wrapping_mul(0x7fb5_d329_728e_a185);
+    v ^= v >> 27;
+    v = v.wrapping_mul(0x81da_def4_bc2d_d44d);
+    v ^= v >> 33;
+    v
+}
+
+/// Converts a 32-bit fixed point fraction to a float in [0, 1).
+pub(crate) fn to_unit_float(v: u32) -> f64 {
+    v as f64 * (1.0 / 4_294_967_296.0)
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::FRAC_PI_4;
+
+    use crate::SeededRandom;
+
+    use super::*;
+
+    #[test]
+    fn dimensions_are_not_reused() {
+        let sampler = SequenceSampler::new(SobolSequence::new(5, 1));
+        sampler.get_1d();
+        assert_eq!(sampler.dimension.load(Ordering::Relaxed), 1);
+        sampler.get_2d();
+        assert_eq!(sampler.dimension.load(Ordering::Relaxed), 4);
+        sampler.start_vertex(0);
+        assert_eq!(sampler.dimension.load(Ordering::Relaxed), CAMERA_DIMENSIONS);
+        for _ in 0..VERTEX_DIMENSIONS + 3 {
+            sampler.get_1d();
+        }
+        // the first vertex used more than its share, the second starts after it
+        sampler.start_vertex(1);
+        assert_eq!(
+            sampler.dimension.load(Ordering::Relaxed),
+            CAMERA_DIMENSIONS + VERTEX_DIMENSIONS + 3
+        );
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        for sampler_type in [
+            SamplerType::Random,
+            SamplerType::Sobol,
+            SamplerType::Halton,
+            SamplerType::BlueNoise,
+        ] {
+            assert_eq!(sampler_type.to_string().parse(), Ok(sampler_type));
+        }
+        assert!("stratified".parse::<SamplerType>().is_err());
+    }
+
+    /// Errors of 16 sample estimates of the integral of `f` over [0, 1)^4, which is
+    /// `integral`, for a square of `size` x `size` pixels, row-major.
+    fn pixel_errors(
+        sampler_type: SamplerType,
+        f: impl Fn([f64; 4]) -> f64,
+        integral: f64,
+        size: u32,
+    ) -> Vec<f64> {
+        let random = Arc::new(SeededRandom::new(1));
+        (0..size * size)
+            .map(|p| {
+                let mut sum = 0.0;
+                for i in 0..16 {
+                    let sampler =
+                        sampler_type.start_sample(random.clone(), p % size, p / size, i, 16, 0);
+                    sampler.start_vertex(0);
+                    let (u0, u1) = sampler.get_2d();
+                    let (u2, u3) = sampler.get_2d();
+                    sum += f([u0, u1, u2, u3]);
+                }
+                sum / 16.0 - integral
+            })
+            .collect()
+    }
+
+    /// A smooth function, every factor integrates to 1 over [0, 1).
+    fn smooth(u: [f64; 4]) -> f64 {
+        u.iter()
+            .map(|u| 1.0 + (std::f64::consts::TAU * u).sin() * 0.5 + (u - 0.5))
+            .product::<f64>()
+    }
+
+    /// A quarter disc in the first two dimensions, like the edge of an object
+    /// crossing a pixel.
+    fn quarter_disc(u: [f64; 4]) -> f64 {
+        if u[0] * u[0] + u[1] * u[1] < 1.0 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn root_mean_square(errors: &[f64]) -> f64 {
+        (errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64).sqrt()
+    }
+
+    /// Root mean square of the average error of blocks of `block` x `block` pixels.
+    fn block_error(errors: &[f64], size: usize, block: usize) -> f64 {
+        let mut block_errors = vec![];
+        for block_y in 0..size / block {
+            for block_x in 0..size / block {
+                let mut sum = 0.0;
+                for y in 0..block {
+                    for x in 0..block {
+                        sum += errors[(block_y * block + y) * size + block_x * block + x];
+                    }
+                }
+                block_errors.push(sum / (block * block) as f64);
+            }
+        }
+        root_mean_square(&block_errors)
+    }
+
+    #[test]
+    fn low_discrepancy_samplers_reduce_error() {
+        let random_error = root_mean_square(&pixel_errors(SamplerType::Random, smooth, 1.0, 8));
+        for sampler_type in [
+            SamplerType::Sobol,
+            SamplerType::Halton,
+            SamplerType::BlueNoise,
+        ] {
+            let error = root_mean_square(&pixel_errors(sampler_type, smooth, 1.0, 8));
+            assert!(
+                error < random_error * 0.5,
+                "{sampler_type} {error} random {random_error}"
+            );
+        }
+    }
+
+    #[test]
+    fn blue_noise_errors_cancel_out_locally() {
+        let sobol = pixel_errors(SamplerType::Sobol, quarter_disc, FRAC_PI_4, 64);
+        let blue_noise = pixel_errors(SamplerType::BlueNoise, quarter_disc, FRAC_PI_4, 64);
+        // seen from a distance, averaged over a few pixels, the image is smoother
+        assert!(block_error(&blue_noise, 64, 2) < block_error(&sobol, 64, 2) * 0.8);
+        assert!(block_error(&blue_noise, 64, 4) < block_error(&sobol, 64, 4) * 0.6);
+    }
+}
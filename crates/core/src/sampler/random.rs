@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use crate::{Random, sampler::Sampler};
+
+/// A [`Sampler`] returning independent random values for every dimension.
+pub struct RandomSampler {
+    random: Arc<dyn Random>,
+}
+
+impl RandomSampler {
+    pub fn new(random: Arc<dyn Random>) -> Self {
+        Self { random }
+    }
+}
+
+impl Sampler for RandomSampler {
+    fn get_1d(&self) -> f64 {
+        self.random.rand()
+    }
+
+    fn get_2d(&self) -> (f64, f64) {
+        (self.random.rand(), self.random.rand())
+    }
+
+    fn start_vertex(&self, _vertex: u32) {}
+}
+
+impl Random for RandomSampler {
+    fn rand(&self) -> f64 {
+        self.random.rand()
+    }
+
+    fn rand_interval(&self, min: f64, max: f64) -> f64 {
+        self.random.rand_interval(min, max)
+    }
+
+    fn rand_int_interval(&self, min: i64, max: i64) -> i64 {
+        self.random.rand_int_interval(min, max)
+    }
+
+    fn rand_2d(&self) -> (f64, f64) {
+        self.random.rand_2d()
+    }
+}
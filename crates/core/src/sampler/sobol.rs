@@ -0,0 +1,105 @@
+use crate::sampler::{SampleSequence, hash, to_unit_float};
+
+/// A point of the Owen-scrambled Sobol sequence.
+///
+/// Every pair of dimensions is a scrambled 2D Sobol (0, 2)-sequence, and the pairs
+/// are decorrelated by shuffling the sample index with a different seed for each
+/// pair, following Burley, "Practical Hash-based Owen Scrambling" (2020). Every
+/// power of two prefix of the samples of a pixel is well stratified in each pair.
+#[derive(Debug)]
+pub struct SobolSequence {
+    index: u32,
+    seed: u32,
+}
+
+impl SobolSequence {
+    /// Creates sample `index` of the sequence scrambled with `seed`.
+    pub fn new(index: u32, seed: u32) -> Self {
+        Self { index, seed }
+    }
+}
+
+impl SampleSequence for SobolSequence {
+    fn value(&self, dimension: u32) -> f64 {
+        to_unit_float(sobol_pair_value(self.index, self.seed, dimension))
+    }
+}
+
+/// Returns `dimension` of an Owen-scrambled, index shuffled 2D Sobol point as a
+/// 32-bit fixed point fraction.
+pub(crate) fn sobol_pair_value(index: u32, seed: u32, dimension: u32) -> u32 {
+    let pair_seed = hash(&[seed, dimension / 2]);
+    let index = nested_uniform_scramble(index, pair_seed);
+    let value = if dimension.is_multiple_of(2) {
+        index.reverse_bits()
+    } else {
+        sobol_second_dimension(index)
+    };
+    nested_uniform_scramble(value, hash(&[pair_seed, dimension % 2]))
+}
+
+/// The second dimension of the Sobol sequence, the first being the van der Corput
+/// sequence `index.reverse_bits()`.
+fn sobol_second_dimension(mut index: u32) -> u32 {
+    let mut result = 0;
+    let mut v = 1 << 31;
+    while index != 0 {
+        if index & 1 != 0 {
+            result ^= v;
+        }
+        index >>= 1;
+        v ^= v >> 1;
+    }
+    result
+}
+
+/// Owen scrambles the bits of a fixed point fraction, each bit being flipped
+/// depending on the bits above it.
+pub(crate) fn nested_uniform_scramble(v: u32, seed: u32) -> u32 {
+    laine_karras_permutation(v.reverse_bits(), seed).reverse_bits()
+}
+
+/// A hash where each bit only depends on the bits below it.
+fn laine_karras_permutation(mut v: u32, seed: u32) -> u32 {
+    v = v.wrapping_add(seed);
+    v ^= v.wrapping_mul(0x6c50_b47c);
+    v ^= v.wrapping_mul(0xb82f_1e52);
+    v ^= v.wrapping_mul(0xc7af_e638);
+    v ^= v.wrapping_mul(0x8d22_f6e6);
+    v
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sampler::SampleSequence;
+
+    use super::SobolSequence;
+
+    #[test]
+    fn pairs_are_stratified() {
+        for seed in [0, 1, 12345] {
+            for pair in 0..4 {
+                let points: Vec<(f64, f64)> = (0..16)
+                    .map(|i| {
+                        let point = SobolSequence::new(i, seed);
+                        (point.value(pair * 2), point.value(pair * 2 + 1))
+                    })
+                    .collect();
+
+                // one point in each row, each column and each cell of a 4x4 grid
+                let mut rows = [0; 16];
+                let mut columns = [0; 16];
+                let mut cells = [0; 16];
+                for (u, v) in points {
+                    assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v));
+                    rows[(v * 16.0) as usize] += 1;
+                    columns[(u * 16.0) as usize] += 1;
+                    cells[(v * 4.0) as usize * 4 + (u * 4.0) as usize] += 1;
+                }
+                assert_eq!(rows, [1; 16]);
+                assert_eq!(columns, [1; 16]);
+                assert_eq!(cells, [1; 16]);
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Wavelengths of visible light, for the spectral mode of the camera.
+//!
+//! A spectral path carries a single hero wavelength, sampled in proportion to how
+//! much the eye responds to it. Materials that depend on the wavelength, such as
+//! dispersive dielectrics, read it from the ray, and the camera converts the light
+//! the path brings back to RGB with [`wavelength_weight`].
+
+use crate::Color;
+
+/// Shortest wavelength sampled, in nanometers.
+pub const MIN_WAVELENGTH: f64 = 360.0;
+
+/// Longest wavelength sampled, in nanometers.
+pub const MAX_WAVELENGTH: f64 = 830.0;
+
+/// Wavelength of the sodium d-line, in nanometers, where refractive indices are
+/// usually quoted and which RGB paths use.
+pub const REFERENCE_WAVELENGTH: f64 = 587.56;
+
+/// Integral of each channel of [`wavelength_to_rgb`] over the sampled wavelengths.
+const RGB_INTEGRAL: [f64; 3] = [128.3368, 101.5543, 97.1016];
+
+/// Samples a visible wavelength from `u` in [0, 1), with a density that roughly
+/// follows the response of the eye (Radziszewski et al. 2009).
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::spectrum::{MAX_WAVELENGTH, MIN_WAVELENGTH, sample_visible_wavelength};
+///
+/// assert!((sample_visible_wavelength(0.0) - MIN_WAVELENGTH).abs() < 1e-3);
+/// assert!((sample_visible_wavelength(0.5) - 546.0).abs() < 1.0);
+/// assert!((sample_visible_wavelength(1.0) - MAX_WAVELENGTH).abs() < 1e-3);
+/// ```
+pub fn sample_visible_wavelength(u: f64) -> f64 {
+    538.0 - 138.888889 * (0.85691062 - 1.82750197 * u).atanh()
+}
+
+/// Returns the density of [`sample_visible_wavelength`] sampling `wavelength`.
+pub fn visible_wavelength_pdf(wavelength: f64) -> f64 {
+    if !(MIN_WAVELENGTH..=MAX_WAVELENGTH).contains(&wavelength) {
+        return 0.0;
+    }
+    let cosh = (0.0072 * (wavelength - 538.0)).cosh();
+    0.0039398042 / (cosh * cosh)
+}
+
+/// Returns the linear sRGB color of light of a single wavelength in nanometers, from
+/// the multi-lobe fit of the CIE 1931 color matching functions (Wyman et al. 2013).
+/// Most wavelengths are outside the sRGB gamut and have a negative channel.
+pub fn wavelength_to_rgb(wavelength: f64) -> Color {
+    // piecewise Gaussian with different widths on each side of its mean
+    let g = |mean: f64, below: f64, above: f64| {
+        let sigma = if wavelength < mean { below } else { above };
+        let t = (wavelength - mean) / sigma;
+        (-0.5 * t * t).exp()
+    };
+    let x =
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2);
+    let y = 0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1);
+    let z = 1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8);
+    Color::from_cie_xyz(x, y, z)
+}
+
+/// Returns the weight turning the RGB light carried by a path of `wavelength`,
+/// sampled with [`sample_visible_wavelength`], into its share of the pixel color.
+///
+/// The weight averages to white over the sampled wavelengths, so a path whose light
+/// doesn't depend on the wavelength keeps its color on average.
+pub fn wavelength_weight(wavelength: f64) -> Color {
+    let pdf = visible_wavelength_pdf(wavelength);
+    if pdf <= 0.0 {
+        return Color::BLACK;
+    }
+    let rgb = wavelength_to_rgb(wavelength);
+    Color::new(
+        rgb.r / RGB_INTEGRAL[0],
+        rgb.g / RGB_INTEGRAL[1],
+        rgb.b / RGB_INTEGRAL[2],
+    ) / pdf
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn weights_average_to_white() {
+        // midpoint rule over the wavelengths, weighted by their density
+        let n = 4700;
+        let step = (MAX_WAVELENGTH - MIN_WAVELENGTH) / n as f64;
+        let mut sum = Color::BLACK;
+        for i in 0..n {
+            let wavelength = MIN_WAVELENGTH + (i as f64 + 0.5) * step;
+            sum += wavelength_weight(wavelength) * (visible_wavelength_pdf(wavelength) * step);
+        }
+        for channel in [sum.r, sum.g, sum.b] {
+            assert!((channel - 1.0).abs() < 1e-4, "{sum:?}");
+        }
+    }
+
+    #[test]
+    fn wavelengths_have_their_color() {
+        let red = wavelength_to_rgb(650.0);
+        assert!(red.r > red.g && red.r > red.b);
+        let green = wavelength_to_rgb(530.0);
+        assert!(green.g > green.r && green.g > green.b);
+        let blue = wavelength_to_rgb(450.0);
+        assert!(blue.b > blue.r && blue.b > blue.g);
+    }
+}
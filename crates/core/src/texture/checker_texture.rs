@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{any::Any, sync::Arc};
 
 use crate::texture::Texture;
 
@@ -33,4 +33,8 @@ impl Texture for CheckerTexture {
             self.odd.value(u, v, pt)
         }
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{any::Any, sync::Arc};
 
 use crate::{Color, Image, Vector3, texture::Texture};
 
@@ -11,6 +11,10 @@ impl ImageTexture {
     pub fn new(image: Arc<dyn Image>) -> Self {
         Self { image }
     }
+
+    pub fn image(&self) -> Arc<dyn Image> {
+        self.image.clone()
+    }
 }
 
 impl Texture for ImageTexture {
@@ -27,4 +31,8 @@ impl Texture for ImageTexture {
             Color::new(0.0, 1.0, 1.0)
         }
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{any::Any, fmt::Debug};
 
 use crate::{Color, Vector3};
 
@@ -16,6 +16,7 @@ pub use solid_color::SolidColor;
 
 pub trait Texture: Debug + Send + Sync {
     fn value(&self, u: f64, v: f64, pt: Vector3) -> Color;
+    fn as_any(&self) -> &dyn Any;
 }
 
 impl PartialEq for dyn Texture {
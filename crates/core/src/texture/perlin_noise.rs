@@ -1,3 +1,5 @@
+use std::any::Any;
+
 use crate::{Color, Random, Vector3, texture::Texture, utils::Perlin};
 
 #[derive(Debug)]
@@ -19,4 +21,8 @@ impl Texture for PerlinNoiseTexture {
     fn value(&self, _u: f64, _v: f64, pt: Vector3) -> Color {
         Color::new(1.0, 1.0, 1.0) * 0.5 * (1.0 + self.noise.noise(self.scale * pt))
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
@@ -1,3 +1,5 @@
+use std::any::Any;
+
 use crate::{Color, Random, Vector3, texture::Texture, utils::Perlin};
 
 #[derive(Debug)]
@@ -24,4 +26,8 @@ impl Texture for PerlinTurbulenceTexture {
                 + (self.scale * pt.z + 10.0 * self.noise.turbulence(pt, self.turbulence_depth))
                     .sin())
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
@@ -1,3 +1,5 @@
+use std::any::Any;
+
 use crate::{Color, texture::Texture};
 
 #[derive(Debug)]
@@ -15,4 +17,8 @@ impl Texture for SolidColor {
     fn value(&self, _u: f64, _v: f64, _pt: crate::Vector3) -> crate::Color {
         self.albedo
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
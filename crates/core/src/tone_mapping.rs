@@ -0,0 +1,138 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::Color;
+
+/// Operator used to compress linear scene radiance into the displayable [0, 1] range.
+///
+/// Tone mapping is applied after exposure and before the sRGB transfer function, see
+/// [`crate::Camera::display_transform`].
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{Color, ToneMapping};
+///
+/// let bright = Color::new(4.0, 1.0, 0.25);
+/// let mapped = ToneMapping::Reinhard.apply(bright);
+/// assert!(mapped.r < 1.0);
+///
+/// let tone_mapping: ToneMapping = "aces".parse().unwrap();
+/// assert_eq!(tone_mapping, ToneMapping::AcesFilmic);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMapping {
+    /// No tone mapping, values above 1.0 are clipped.
+    #[default]
+    None,
+    /// Simple Reinhard operator `c / (1 + c)` applied per channel.
+    Reinhard,
+    /// Krzysztof Narkowicz's fit of the ACES filmic reference rendering transform.
+    AcesFilmic,
+    /// John Hable's filmic curve from Uncharted 2.
+    Uncharted2,
+}
+
+/// Linear white point of the Uncharted 2 curve.
+const UNCHARTED2_WHITE: f64 = 11.2;
+
+/// Exposure bias applied before the Uncharted 2 curve, as in Hable's reference.
+const UNCHARTED2_EXPOSURE_BIAS: f64 = 2.0;
+
+impl ToneMapping {
+    /// Maps a linear color to the [0, 1] range. Negative components are treated as 0.
+    pub fn apply(&self, color: Color) -> Color {
+        let map = |v: f64| {
+            let v = v.max(0.0);
+            match self {
+                ToneMapping::None => v,
+                ToneMapping::Reinhard => v / (1.0 + v),
+                ToneMapping::AcesFilmic => (v * (2.51 * v + 0.03)) / (v * (2.43 * v + 0.59) + 0.14),
+                ToneMapping::Uncharted2 => {
+                    uncharted2_curve(v * UNCHARTED2_EXPOSURE_BIAS)
+                        / uncharted2_curve(UNCHARTED2_WHITE)
+                }
+            }
+        };
+        Color::new(map(color.r), map(color.g), map(color.b)).clamp(0.0, 1.0)
+    }
+}
+
+fn uncharted2_curve(v: f64) -> f64 {
+    const A: f64 = 0.15; // shoulder strength
+    const B: f64 = 0.50; // linear strength
+    const C: f64 = 0.10; // linear angle
+    const D: f64 = 0.20; // toe strength
+    const E: f64 = 0.02; // toe numerator
+    const F: f64 = 0.30; // toe denominator
+    ((v * (A * v + C * B) + D * E) / (v * (A * v + B) + D * F)) - E / F
+}
+
+impl FromStr for ToneMapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(ToneMapping::None),
+            "reinhard" => Ok(ToneMapping::Reinhard),
+            "aces" | "aces_filmic" => Ok(ToneMapping::AcesFilmic),
+            "uncharted2" => Ok(ToneMapping::Uncharted2),
+            _ => Err(format!("unknown tone mapping \"{s}\"")),
+        }
+    }
+}
+
+impl Display for ToneMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ToneMapping::None => "none",
+            ToneMapping::Reinhard => "reinhard",
+            ToneMapping::AcesFilmic => "aces",
+            ToneMapping::Uncharted2 => "uncharted2",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assert_eq_float::assert_eq_float;
+
+    use super::*;
+
+    #[test]
+    fn operators_preserve_black_and_stay_in_range() {
+        for tone_mapping in [
+            ToneMapping::None,
+            ToneMapping::Reinhard,
+            ToneMapping::AcesFilmic,
+            ToneMapping::Uncharted2,
+        ] {
+            let black = tone_mapping.apply(Color::BLACK);
+            assert!(black.r.abs() < 1e-6, "{tone_mapping}");
+
+            let bright = tone_mapping.apply(Color::new(1000.0, 1.0, -1.0));
+            assert!(bright.r <= 1.0 && bright.r > 0.9, "{tone_mapping}");
+            assert!(bright.g > 0.0 && bright.g <= 1.0, "{tone_mapping}");
+            assert_eq_float!(bright.b, 0.0);
+        }
+    }
+
+    #[test]
+    fn uncharted2_maps_white_point_to_one() {
+        let white = ToneMapping::Uncharted2.apply(Color::WHITE * (UNCHARTED2_WHITE / 2.0));
+        assert_eq_float!(white.r, 1.0);
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        for tone_mapping in [
+            ToneMapping::None,
+            ToneMapping::Reinhard,
+            ToneMapping::AcesFilmic,
+            ToneMapping::Uncharted2,
+        ] {
+            assert_eq!(tone_mapping.to_string().parse(), Ok(tone_mapping));
+        }
+        assert!("filmic".parse::<ToneMapping>().is_err());
+    }
+}
@@ -95,4 +95,20 @@ impl OrthonormalBasis {
     pub fn transform_to_local(&self, v: Vector3) -> Vector3 {
         (v.x * self.u) + (v.y * self.v) + (v.z * self.w)
     }
+
+    /// Transforms a vector from local space to basis coordinates, the inverse of
+    /// [`OrthonormalBasis::transform_to_local`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use caustic_core::{utils::OrthonormalBasis, Vector3};
+    ///
+    /// let basis = OrthonormalBasis::new(Vector3::new(0.0, 1.0, 0.0));
+    /// let basis_vec = basis.transform_to_basis(Vector3::new(0.0, 2.0, 0.0));
+    /// assert_eq!(basis_vec, Vector3::new(0.0, 0.0, 2.0));
+    /// ```
+    pub fn transform_to_basis(&self, v: Vector3) -> Vector3 {
+        Vector3::new(v.dot(&self.u), v.dot(&self.v), v.dot(&self.w))
+    }
 }
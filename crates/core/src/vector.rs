@@ -114,11 +114,8 @@ impl Vector3 {
     /// * `random` - A random number generator implementing the Random trait.
     pub fn random_in_unit_disk(random: &dyn Random) -> Vector3 {
         loop {
-            let pt = Vector3::new(
-                random.rand_interval(-1.0, 1.0),
-                random.rand_interval(-1.0, 1.0),
-                0.0,
-            );
+            let (u, v) = random.rand_2d();
+            let pt = Vector3::new(2.0 * u - 1.0, 2.0 * v - 1.0, 0.0);
             if pt.length_squared() < 1.0 {
                 return pt;
             }
@@ -134,8 +131,7 @@ impl Vector3 {
     ///
     /// * `random` - A random number generator implementing the Random trait.
     pub fn random_cosine_direction(random: &dyn Random) -> Vector3 {
-        let r1 = random.rand();
-        let r2 = random.rand();
+        let (r1, r2) = random.rand_2d();
 
         let phi = 2.0 * f64::consts::PI * r1;
         let x = phi.cos() * r2.sqrt();
@@ -153,7 +149,8 @@ impl Vector3 {
     ///
     /// * `random` - A random number generator implementing the Random trait.
     pub fn sample_square(random: &dyn Random) -> Vector3 {
-        Vector3::new(random.rand() - 0.5, random.rand() - 0.5, 0.0)
+        let (u, v) = random.rand_2d();
+        Vector3::new(u - 0.5, v - 0.5, 0.0)
     }
 
     /// Returns the length (magnitude) of the vector.
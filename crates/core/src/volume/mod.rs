@@ -0,0 +1,22 @@
+//! Density fields of heterogeneous participating media, see
+//! [`HeterogeneousMedium`](crate::object::HeterogeneousMedium).
+
+use std::fmt::Debug;
+
+use crate::Vector3;
+
+pub mod texture_density;
+pub mod voxel_grid;
+
+pub use texture_density::TextureDensity;
+pub use voxel_grid::VoxelGrid;
+
+/// Density of a medium varying through space, relative to the density of the medium.
+pub trait DensityField: Debug + Send + Sync {
+    /// Returns the density at `pt`, never negative.
+    fn density(&self, pt: Vector3) -> f64;
+
+    /// Returns a bound of the density everywhere, the tighter the faster the medium
+    /// is sampled.
+    fn max_density(&self) -> f64;
+}
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use crate::{Vector3, texture::Texture, volume::DensityField};
+
+/// Density following the luminance of a texture, such as Perlin turbulence, clamped
+/// to [0, 1]. The texture is looked up with the point only, so only solid textures
+/// make sense.
+#[derive(Debug)]
+pub struct TextureDensity {
+    texture: Arc<dyn Texture>,
+}
+
+impl TextureDensity {
+    pub fn new(texture: Arc<dyn Texture>) -> Self {
+        Self { texture }
+    }
+}
+
+impl DensityField for TextureDensity {
+    fn density(&self, pt: Vector3) -> f64 {
+        self.texture.value(0.0, 0.0, pt).luminance().clamp(0.0, 1.0)
+    }
+
+    fn max_density(&self) -> f64 {
+        1.0
+    }
+}
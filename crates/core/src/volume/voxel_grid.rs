@@ -0,0 +1,104 @@
+use crate::{Axis, AxisAlignedBoundingBox, Vector3, volume::DensityField};
+
+/// Density sampled on a regular 3D grid of voxels spanning a bounding box, like
+/// simulated smoke, and interpolated trilinearly between voxel centers. The density
+/// is 0 outside the box.
+///
+/// # Examples
+///
+/// ```
+/// use caustic_core::{AxisAlignedBoundingBox, Vector3, volume::{DensityField, VoxelGrid}};
+///
+/// let bbox = AxisAlignedBoundingBox::new_from_points(Vector3::ZERO, Vector3::new(2.0, 1.0, 1.0));
+/// let grid = VoxelGrid::new([2, 1, 1], bbox, vec![0.0, 1.0]);
+/// assert_eq!(grid.density(Vector3::new(1.0, 0.5, 0.5)), 0.5);
+/// assert_eq!(grid.max_density(), 1.0);
+/// ```
+#[derive(Debug)]
+pub struct VoxelGrid {
+    resolution: [usize; 3],
+    bbox: AxisAlignedBoundingBox,
+    /// Densities with x varying fastest, then y, then z
+    values: Vec<f64>,
+    max_density: f64,
+}
+
+impl VoxelGrid {
+    /// Creates the grid from `resolution` voxels along x, y and z, and their
+    /// densities with x varying fastest, then y, then z. Negative densities are
+    /// treated as 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resolution is 0 along an axis or the number of densities doesn't
+    /// match it.
+    pub fn new(resolution: [usize; 3], bbox: AxisAlignedBoundingBox, values: Vec<f64>) -> Self {
+        assert!(resolution.iter().all(|&n| n > 0), "empty voxel grid");
+        assert_eq!(
+            values.len(),
+            resolution.iter().product::<usize>(),
+            "voxel count doesn't match the resolution"
+        );
+        let values: Vec<f64> = values.into_iter().map(|v| v.max(0.0)).collect();
+        let max_density = values.iter().copied().fold(0.0, f64::max);
+        Self {
+            resolution,
+            bbox,
+            values,
+            max_density,
+        }
+    }
+
+    pub fn resolution(&self) -> [usize; 3] {
+        self.resolution
+    }
+
+    pub fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        &self.bbox
+    }
+
+    fn value(&self, x: usize, y: usize, z: usize) -> f64 {
+        let [nx, ny, _] = self.resolution;
+        self.values[(z * ny + y) * nx + x]
+    }
+}
+
+impl DensityField for VoxelGrid {
+    fn density(&self, pt: Vector3) -> f64 {
+        // lower voxel and interpolation weight along each axis, voxel centers being
+        // at half voxels
+        let mut lower = [0; 3];
+        let mut weight = [0.0; 3];
+        for (i, axis) in [Axis::X, Axis::Y, Axis::Z].into_iter().enumerate() {
+            let interval = self.bbox.axis_interval(axis);
+            let value = pt.axis_value(axis);
+            if !interval.contains(value) {
+                return 0.0;
+            }
+            let n = self.resolution[i];
+            let f = ((value - interval.min) / interval.size() * n as f64 - 0.5)
+                .clamp(0.0, (n - 1) as f64);
+            lower[i] = (f as usize).min(n.saturating_sub(2));
+            weight[i] = f - lower[i] as f64;
+        }
+
+        let mut density = 0.0;
+        for corner in 0..8 {
+            let mut voxel = [0; 3];
+            let mut corner_weight = 1.0;
+            for i in 0..3 {
+                let upper = (corner >> i) & 1 == 1;
+                voxel[i] = (lower[i] + upper as usize).min(self.resolution[i] - 1);
+                corner_weight *= if upper { weight[i] } else { 1.0 - weight[i] };
+            }
+            if corner_weight > 0.0 {
+                density += corner_weight * self.value(voxel[0], voxel[1], voxel[2]);
+            }
+        }
+        density
+    }
+
+    fn max_density(&self) -> f64 {
+        self.max_density
+    }
+}
@@ -53,7 +53,6 @@ pub(crate) static BUILTIN_MODULE_DOCS: LazyLock<HashMap<&'static str, ModuleDocs
         // TODO Mathematical Functions - norm
         // TODO Mathematical Functions - cross
         // TODO 2D Primitives - import
-        // TODO 3D Primitives - import
         // TODO 3D Primitives - surface
 
         // Caustic objects
@@ -85,6 +84,24 @@ pub(crate) static BUILTIN_MODULE_DOCS: LazyLock<HashMap<&'static str, ModuleDocs
                             .to_owned(),
                         default: Some("10".to_owned()),
                     },
+                    ModuleDocsArguments {
+                        name: "adaptive_threshold".to_owned(),
+                        description: "Noise level, as a fraction of white, at which a pixel stops sampling early, samples_per_pixel becomes the maximum (0 disables adaptive sampling)."
+                            .to_owned(),
+                        default: Some("0".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "min_samples_per_pixel".to_owned(),
+                        description: "Samples taken for every pixel before adaptive sampling may stop."
+                            .to_owned(),
+                        default: Some("16".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "sampler".to_owned(),
+                        description: "Source of the sample values: \"random\", or the low-discrepancy \"sobol\", \"halton\" or \"blue_noise\", which reduce noise at low sample counts."
+                            .to_owned(),
+                        default: Some("\"random\"".to_owned()),
+                    },
                     ModuleDocsArguments {
                         name: "max_depth".to_owned(),
                         description: "Maximum number of ray bounces in the scene.".to_owned(),
@@ -126,17 +143,161 @@ pub(crate) static BUILTIN_MODULE_DOCS: LazyLock<HashMap<&'static str, ModuleDocs
                     },
                     ModuleDocsArguments {
                         name: "background".to_owned(),
-                        description: "Background color as [r, g, b] (values 0-1).".to_owned(),
+                        description: "Background color as [r, g, b] (values 0-1), an equirectangular environment image created with image(), \"gradient\" for a white to blue gradient or \"sky\" for an analytic daylight sky."
+                            .to_owned(),
                         default: Some("[0, 0, 0]".to_owned()),
                     },
+                    ModuleDocsArguments {
+                        name: "sun_direction".to_owned(),
+                        description: "Direction towards the sun as [x, y, z] when background is \"sky\"."
+                            .to_owned(),
+                        default: Some("[1, 1, 1]".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "turbidity".to_owned(),
+                        description: "Haziness of the atmosphere when background is \"sky\", from 2 (clear) to 10 (hazy)."
+                            .to_owned(),
+                        default: Some("3".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "exposure".to_owned(),
+                        description: "Exposure adjustment in stops, each stop doubles the brightness."
+                            .to_owned(),
+                        default: Some("0".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "tone_mapping".to_owned(),
+                        description:
+                            "Tone mapping operator: \"none\", \"reinhard\", \"aces\" or \"uncharted2\"."
+                                .to_owned(),
+                        default: Some("\"none\"".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "spectral".to_owned(),
+                        description: "Trace a single wavelength of light per path, so dispersive dielectrics split white light into its colors."
+                            .to_owned(),
+                        default: Some("false".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "shutter_open".to_owned(),
+                        description: "Time at which the shutter opens. Objects moving with motion() while the shutter is open are blurred.".to_owned(),
+                        default: Some("0".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "shutter_close".to_owned(),
+                        description: "Time at which the shutter closes.".to_owned(),
+                        default: Some("1".to_owned()),
+                    },
                 ],
                 examples: vec![
                     "camera();".to_owned(),
                     "camera(aspect_ratio=16.0/9.0, image_width=1200);".to_owned(),
                     "camera(look_from=[0, 2, 5], look_at=[0, 0, 0], vertical_fov=60);".to_owned(),
                     "camera(samples_per_pixel=100, max_depth=50, defocus_angle=0.6);".to_owned(),
+                    "camera(samples_per_pixel=1000, adaptive_threshold=0.01);".to_owned(),
+                    "camera(samples_per_pixel=16, sampler=\"sobol\");".to_owned(),
                     "camera(background=[0, 0, 0], look_from=[3, 3, 2], look_at=[0, 0, -1]);"
                         .to_owned(),
+                    "camera(exposure=1, tone_mapping=\"aces\");".to_owned(),
+                    "camera(background=image(\"sky.hdr\"));".to_owned(),
+                    "camera(background=\"sky\", sun_direction=[1, 0, 0.3], turbidity=4);".to_owned(),
+                    "camera(samples_per_pixel=500, spectral=true);".to_owned(),
+                    "camera(shutter_open=0.25, shutter_close=0.75);".to_owned(),
+                ],
+            },
+        );
+
+        map.insert(
+            "point_light",
+            ModuleDocs {
+                description: "Adds a light shining equally in every direction from a point. The light has no geometry, it isn't seen directly.".to_owned(),
+                arguments: vec![
+                    ModuleDocsArguments {
+                        name: "position".to_owned(),
+                        description: "position of the light.".to_owned(),
+                        default: Some("[0, 0, 0]".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "c".to_owned(),
+                        description: "intensity as RGB vector [r,g,b], the light falling on a surface facing the light at distance 1. Falls off with the square of the distance."
+                            .to_owned(),
+                        default: Some("white".to_owned()),
+                    },
+                ],
+                examples: vec![
+                    "point_light(position=[0, 0, 10], c=[100, 100, 100]);".to_owned(),
+                ],
+            },
+        );
+
+        map.insert(
+            "spot_light",
+            ModuleDocs {
+                description: "Adds a point light shining in a cone. The light has no geometry, it isn't seen directly.".to_owned(),
+                arguments: vec![
+                    ModuleDocsArguments {
+                        name: "position".to_owned(),
+                        description: "position of the light.".to_owned(),
+                        default: Some("[0, 0, 0]".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "direction".to_owned(),
+                        description: "direction the light points in.".to_owned(),
+                        default: Some("[0, 0, -1]".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "c".to_owned(),
+                        description: "intensity as RGB vector [r,g,b], as for point_light."
+                            .to_owned(),
+                        default: Some("white".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "cutoff".to_owned(),
+                        description: "angle from direction, in degrees, where the light ends."
+                            .to_owned(),
+                        default: Some("30".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "falloff_start".to_owned(),
+                        description: "angle from direction, in degrees, where the light starts to fade out towards cutoff."
+                            .to_owned(),
+                        default: Some("0.75 * cutoff".to_owned()),
+                    },
+                ],
+                examples: vec![
+                    "spot_light(position=[0, 0, 10], c=[200, 200, 200], cutoff=25);".to_owned(),
+                    "spot_light(position=[5, 0, 5], direction=[-1, 0, -1], cutoff=40, falloff_start=10);"
+                        .to_owned(),
+                ],
+            },
+        );
+
+        map.insert(
+            "sun",
+            ModuleDocs {
+                description: "Adds light from the sun, arriving from the same direction everywhere in the scene. The sun's disc is seen by reflections and rays leaving the scene.".to_owned(),
+                arguments: vec![
+                    ModuleDocsArguments {
+                        name: "direction".to_owned(),
+                        description: "direction from the scene towards the sun.".to_owned(),
+                        default: Some("[-1, 1, 1]".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "c".to_owned(),
+                        description: "light falling on a surface facing the sun, as RGB vector [r,g,b]."
+                            .to_owned(),
+                        default: Some("white".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "angular_diameter".to_owned(),
+                        description: "size of the sun's disc in degrees, larger values soften the shadows (0=sharp shadows)."
+                            .to_owned(),
+                        default: Some("0.53".to_owned()),
+                    },
+                ],
+                examples: vec![
+                    "sun();".to_owned(),
+                    "sun(direction=[1, 0.5, 2], c=[3, 2.8, 2.5], angular_diameter=2);".to_owned(),
                 ],
             },
         );
@@ -189,20 +350,158 @@ pub(crate) static BUILTIN_MODULE_DOCS: LazyLock<HashMap<&'static str, ModuleDocs
             },
         );
 
+        map.insert(
+            "pbr",
+            ModuleDocs {
+                description: "Creates a physically based material following the glTF metallic-roughness model, with GGX microfacet reflections."
+                    .to_owned(),
+                arguments: vec![
+                    ModuleDocsArguments {
+                        name: "c".to_owned(),
+                        description: "base color as RGB vector [r,g,b] with values 0-1, single grayscale value, or texture."
+                            .to_owned(),
+                        default: Some("white".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "metallic".to_owned(),
+                        description: "metallic factor (0=dielectric, 1=metal), or texture read from its blue channel."
+                            .to_owned(),
+                        default: Some("0".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "roughness".to_owned(),
+                        description: "roughness (0=mirror, 1=fully rough), or texture read from its green channel."
+                            .to_owned(),
+                        default: Some("0.5".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "t".to_owned(),
+                        description: "texture for the base color, used when c is not given.".to_owned(),
+                        default: None,
+                    },
+                ],
+                examples: vec![
+                    "pbr([1, 0.78, 0.34], metallic=1, roughness=0.3);".to_owned(),
+                    "pbr(c=[0.1, 0.2, 0.8], roughness=0.6);".to_owned(),
+                    "pbr(t=checker(scale=1, even=[1,1,1], odd=[0,0,0]), roughness=0.2);".to_owned(),
+                ],
+            },
+        );
+
+        map.insert(
+            "subsurface",
+            ModuleDocs {
+                description: "Translucent material where light scatters inside the object before leaving it, like wax, skin or marble. The object must be closed. Dense materials need a high max_depth on the camera, each scattering inside being a bounce."
+                    .to_owned(),
+                arguments: vec![
+                    ModuleDocsArguments {
+                        name: "scattering".to_owned(),
+                        description: "scattering coefficient as RGB vector [r,g,b] or number, the fraction of light scattered per unit of distance."
+                            .to_owned(),
+                        default: Some("10".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "absorption".to_owned(),
+                        description: "absorption coefficient as RGB vector [r,g,b] or number, the fraction of light absorbed per unit of distance."
+                            .to_owned(),
+                        default: Some("0".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "g".to_owned(),
+                        description: "asymmetry of the scattering, from backward (-1) to forward (1), 0 scattering evenly in all directions."
+                            .to_owned(),
+                        default: Some("0".to_owned()),
+                    },
+                ],
+                examples: vec![
+                    "subsurface(scattering=[8, 8, 7], absorption=[0.01, 0.02, 0.05]) sphere(5);".to_owned(),
+                    "subsurface(scattering=[5, 2, 1], absorption=[0.1, 0.5, 0.8], g=0.8) cube(10);".to_owned(),
+                    "subsurface(absorption=[0.1, 0.4, 0.9]) clearcoat() sphere(5);".to_owned(),
+                ],
+            },
+        );
+
+        map.insert(
+            "clearcoat",
+            ModuleDocs {
+                description: "Adds a glossy dielectric coat over the enclosing material, like car paint or varnish. The coat reflects more at grazing angles and the material shows through it."
+                    .to_owned(),
+                arguments: vec![
+                    ModuleDocsArguments {
+                        name: "n".to_owned(),
+                        description: "refractive index of the coat.".to_owned(),
+                        default: Some("1.5".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "roughness".to_owned(),
+                        description: "roughness of the coat (0=mirror, 1=fully rough).".to_owned(),
+                        default: Some("0".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "c".to_owned(),
+                        description: "tint of the coat as RGB vector [r,g,b], the color of white light going through it and back."
+                            .to_owned(),
+                        default: Some("white".to_owned()),
+                    },
+                ],
+                examples: vec![
+                    "color([0, 0.3, 0.8]) clearcoat() cube(10);".to_owned(),
+                    "metal([0.8, 0.1, 0.1], 0.3) clearcoat(roughness=0.05) sphere(5);".to_owned(),
+                    "lambertian([0.6, 0.4, 0.2]) clearcoat(c=[1, 0.9, 0.6]) cube(10);".to_owned(),
+                ],
+            },
+        );
+
         map.insert(
             "dielectric",
             ModuleDocs {
                 description:
-                    "Creates a dielectric (glass-like) material with a given refractive index."
+                    "Creates a dielectric (glass-like) material with a given refractive index, optionally tinted, rough and dispersive. Dispersion shows with camera(spectral=true)."
                         .to_owned(),
-                arguments: vec![ModuleDocsArguments {
-                    name: "n".to_owned(),
-                    description: "refractive index of the dielectric material.".to_owned(),
-                    default: None,
-                }],
+                arguments: vec![
+                    ModuleDocsArguments {
+                        name: "n".to_owned(),
+                        description: "refractive index of the dielectric material, or the name of a dispersive glass: \"bk7\", \"sf11\" or \"diamond\"."
+                            .to_owned(),
+                        default: None,
+                    },
+                    ModuleDocsArguments {
+                        name: "cauchy".to_owned(),
+                        description: "dispersive refractive index [a, b] following Cauchy's equation n = a + b / λ², λ in micrometers, instead of n."
+                            .to_owned(),
+                        default: None,
+                    },
+                    ModuleDocsArguments {
+                        name: "sellmeier".to_owned(),
+                        description: "dispersive refractive index [b1, b2, b3, c1, c2, c3] following the Sellmeier equation, λ in micrometers, instead of n."
+                            .to_owned(),
+                        default: None,
+                    },
+                    ModuleDocsArguments {
+                        name: "c".to_owned(),
+                        description: "tint as RGB vector [r,g,b], the fraction of light going through `distance` inside the material."
+                            .to_owned(),
+                        default: Some("clear".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "distance".to_owned(),
+                        description: "distance inside the material over which the tint `c` applies, thicker parts are darker."
+                            .to_owned(),
+                        default: Some("1".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "roughness".to_owned(),
+                        description: "surface roughness (0=smooth, 1=frosted).".to_owned(),
+                        default: Some("0".to_owned()),
+                    },
+                ],
                 examples: vec![
                     "dielectric(1.5);".to_owned(),
                     "dielectric(n=1.5);".to_owned(),
+                    "dielectric(1.5, c=[0.3, 0.6, 0.4], distance=2);".to_owned(),
+                    "dielectric(1.5, roughness=0.3);".to_owned(),
+                    "dielectric(\"sf11\");".to_owned(),
+                    "dielectric(cauchy=[1.5046, 0.0042]);".to_owned(),
                 ],
             },
         );
@@ -554,6 +853,61 @@ pub(crate) static BUILTIN_MODULE_DOCS: LazyLock<HashMap<&'static str, ModuleDocs
         },
     );
 
+        map.insert(
+            "import",
+            ModuleDocs {
+                description: "Imports a model file. STL (ASCII and binary), OFF and Wavefront OBJ files are supported. Materials from MTL files referenced by an OBJ file are used in place of the current material. A file is only loaded once, importing it again, for example in a loop, shares its geometry.".to_owned(),
+                arguments: vec![
+                    ModuleDocsArguments {
+                        name: "file".to_owned(),
+                        description: "filename of the model, relative to the current file.".to_owned(),
+                        default: None,
+                    },
+                    ModuleDocsArguments {
+                        name: "convexity".to_owned(),
+                        description: "ignored, accepted for compatibility.".to_owned(),
+                        default: Some("1".to_owned()),
+                    },
+                ],
+                examples: vec![
+                    "import(\"part.stl\");".to_owned(),
+                    "import(\"teapot.obj\");".to_owned(),
+                ],
+            },
+        );
+
+        map.insert(
+            "medium",
+            ModuleDocs {
+                description: "Fills its child object with a participating medium, like fog, smoke or clouds, in place of its surface. The object must be convex. Without a field the density is uniform."
+                    .to_owned(),
+                arguments: vec![
+                    ModuleDocsArguments {
+                        name: "density".to_owned(),
+                        description: "fraction of light scattered per unit of distance, times the field.".to_owned(),
+                        default: Some("1".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "c".to_owned(),
+                        description: "color of the medium as RGB vector [r,g,b] or a texture.".to_owned(),
+                        default: Some("white".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "field".to_owned(),
+                        description: "density field between 0 and 1, a texture such as perlin_turbulence(), or the filename of a Mitsuba .vol voxel grid relative to the current file, its bounding box in OpenSCAD coordinates."
+                            .to_owned(),
+                        default: None,
+                    },
+                ],
+                examples: vec![
+                    "medium(density=0.5, c=[0.8, 0.8, 0.8]) cube(10);".to_owned(),
+                    "medium(density=5, field=perlin_turbulence(scale=0, turbulence_depth=7)) sphere(5);"
+                        .to_owned(),
+                    "medium(density=20, field=\"smoke.vol\") cube(10);".to_owned(),
+                ],
+            },
+        );
+
         // Transformations
         map.insert(
             "translate",
@@ -582,7 +936,7 @@ pub(crate) static BUILTIN_MODULE_DOCS: LazyLock<HashMap<&'static str, ModuleDocs
                         description:
                             "angle in degrees, or [x, y, z] for rotation around each axis."
                                 .to_owned(),
-                        default: None,
+                        default: Some("0".to_owned()),
                     },
                     ModuleDocsArguments {
                         name: "v".to_owned(),
@@ -607,7 +961,7 @@ pub(crate) static BUILTIN_MODULE_DOCS: LazyLock<HashMap<&'static str, ModuleDocs
                     description:
                         "scaling factors [x, y, z] or [x, y], or single value for uniform scaling."
                             .to_owned(),
-                    default: None,
+                    default: Some("[1, 1, 1]".to_owned()),
                 }],
                 examples: vec![
                     "scale([2, 1, 1]) { ... }".to_owned(),
@@ -617,6 +971,43 @@ pub(crate) static BUILTIN_MODULE_DOCS: LazyLock<HashMap<&'static str, ModuleDocs
             },
         );
 
+        map.insert(
+            "motion",
+            ModuleDocs {
+                description: "Moves its child elements while the camera shutter is open, for motion blur. At the start time they are where they are, and at the end time where translate(translate) rotate(rotate) scale(scale) would place them. Rotations are interpolated along the shortest way around."
+                    .to_owned(),
+                arguments: vec![
+                    ModuleDocsArguments {
+                        name: "translate".to_owned(),
+                        description: "vector to move along [x, y, z].".to_owned(),
+                        default: Some("[0, 0, 0]".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "rotate".to_owned(),
+                        description: "angle in degrees around the z axis, or [x, y, z] for rotation around each axis."
+                            .to_owned(),
+                        default: Some("[0, 0, 0]".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "scale".to_owned(),
+                        description: "scaling factors [x, y, z], or single value for uniform scaling."
+                            .to_owned(),
+                        default: Some("1".to_owned()),
+                    },
+                    ModuleDocsArguments {
+                        name: "time".to_owned(),
+                        description: "start and end times of the motion [start, end], on the clock of the camera shutter."
+                            .to_owned(),
+                        default: Some("[0, 1]".to_owned()),
+                    },
+                ],
+                examples: vec![
+                    "motion(translate=[2, 0, 0]) sphere(1);".to_owned(),
+                    "motion(rotate=[0, 0, 90], time=[0.5, 1]) cube(2, center=true);".to_owned(),
+                ],
+            },
+        );
+
         map.insert(
             "resize",
             ModuleDocs {
@@ -648,7 +1039,7 @@ pub(crate) static BUILTIN_MODULE_DOCS: LazyLock<HashMap<&'static str, ModuleDocs
                     name: "v".to_owned(),
                     description: "normal vector of the mirror plane [x, y, z] or [x, y]."
                         .to_owned(),
-                    default: None,
+                    default: Some("[1, 0, 0]".to_owned()),
                 }],
                 examples: vec![
                     "mirror([1, 0, 0]) { ... }".to_owned(),
@@ -665,7 +1056,7 @@ pub(crate) static BUILTIN_MODULE_DOCS: LazyLock<HashMap<&'static str, ModuleDocs
                 arguments: vec![ModuleDocsArguments {
                     name: "m".to_owned(),
                     description: "4x4 transformation matrix.".to_owned(),
-                    default: None,
+                    default: Some("identity".to_owned()),
                 }],
                 examples: vec![
                     "multmatrix([[1,0,0,10], [0,1,0,20], [0,0,1,30], [0,0,0,1]]) { ... }"
@@ -746,7 +1137,7 @@ pub(crate) static BUILTIN_MODULE_DOCS: LazyLock<HashMap<&'static str, ModuleDocs
         map.insert(
             "difference",
             ModuleDocs {
-                description: "Subtracts all children after the first from the first child."
+                description: "Subtracts all children after the first from the first child. The cut surfaces keep the material of the child that cut them."
                     .to_owned(),
                 arguments: vec![],
                 examples: vec![
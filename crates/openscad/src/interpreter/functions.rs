@@ -4,6 +4,7 @@ use caustic_core::{
     Color,
     texture::{CheckerTexture, ImageTexture, PerlinTurbulenceTexture, SolidColor, Texture},
 };
+use rand_mt::Mt64;
 
 use crate::{
     Message, MessageLevel, Position, Result,
@@ -555,8 +556,10 @@ impl Interpreter {
             todo!("value_count required");
         };
 
-        let seed_value = if let Some(arg) = arguments.get("seed_value") {
-            Some(arg.item.to_number()?)
+        // a seeded call always returns the same values, an unseeded call continues
+        // the interpreter's sequence
+        let mut seeded_rng = if let Some(arg) = arguments.get("seed_value") {
+            Some(Mt64::new(arg.item.to_number()? as i64 as u64))
         } else {
             None
         };
@@ -567,8 +570,8 @@ impl Interpreter {
 
         let mut items = vec![];
         for _ in 0..value_count {
-            let rand_value = if let Some(seed_value) = seed_value {
-                todo!("rands with seed {seed_value}");
+            let rand_value = if let Some(rng) = &mut seeded_rng {
+                rng.next_u64()
             } else {
                 self.rng.next_u64()
             };
@@ -9,6 +9,8 @@ use std::{cell::RefCell, collections::HashMap, sync::Arc};
 
 use caustic_core::{
     Camera, CameraBuilder, Color, Node, Random, SceneData, Vector3,
+    background::SolidBackground,
+    light::{Light, Lights},
     material::{Lambertian, Material},
     object::BoundingVolumeHierarchy,
 };
@@ -103,13 +105,24 @@ impl Function {
     }
 }
 
+/// Node loaded by `import()`, instanced by each import of its file.
+struct ImportedNode {
+    prototype: Arc<dyn Node>,
+    /// Whether the node has the material current when the file was first imported,
+    /// which instances replace with the material current when they are imported
+    default_material: bool,
+}
+
 struct Interpreter {
     _modules: HashMap<String, Module>,
 
     camera: Option<Arc<Camera>>,
     world: Vec<Arc<dyn Node>>,
     lights: Vec<Arc<dyn Node>>,
+    light_sources: Vec<Arc<dyn Light>>,
     material_stack: Vec<Arc<dyn Material>>,
+    /// Nodes of each imported file, shared by every import of the file
+    imports: HashMap<String, Vec<ImportedNode>>,
     variables: RefCell<Vec<HashMap<String, Value>>>,
     functions: HashMap<String, Function>,
     random: Arc<dyn Random>,
@@ -152,7 +165,9 @@ impl Interpreter {
             camera: None,
             world: vec![],
             lights: vec![],
+            light_sources: vec![],
             material_stack: vec![],
+            imports: HashMap::new(),
             random,
             rng: Mt64::new_unseeded(),
             messages: vec![],
@@ -178,7 +193,7 @@ impl Interpreter {
             camera_builder.samples_per_pixel = 10;
             camera_builder.max_depth = 50;
             camera_builder.defocus_angle = 0.0;
-            camera_builder.background = Color::new(0.7, 0.8, 1.0);
+            camera_builder.background = Arc::new(SolidBackground::new(Color::new(0.7, 0.8, 1.0)));
             camera_builder.look_at = Vector3::new(0.0, 0.0, 0.0);
             camera_builder.look_from = Vector3::new(-50.0, 70.0, -50.0);
             camera_builder.up = Vector3::new(0.0, 1.0, 0.0);
@@ -188,10 +203,13 @@ impl Interpreter {
         let scene_data = SceneData {
             camera,
             world: Arc::new(BoundingVolumeHierarchy::new(&self.world)),
-            lights: if self.lights.is_empty() {
-                None
-            } else {
-                Some(Arc::new(BoundingVolumeHierarchy::new(&self.lights)))
+            lights: Lights {
+                geometry: if self.lights.is_empty() {
+                    None
+                } else {
+                    Some(Arc::new(BoundingVolumeHierarchy::new(&self.lights)))
+                },
+                sources: self.light_sources,
             },
         };
 
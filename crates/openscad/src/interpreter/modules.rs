@@ -1,16 +1,27 @@
 use std::sync::Arc;
 
 use caustic_core::{
-    CameraBuilder, Color, Node, Vector3,
-    material::{Dielectric, DiffuseLight, Lambertian, Material, Metal},
-    object::{BoxPrimitive, ConeFrustum, Disc, Group, Quad, Rotate, Scale, Sphere, Translate},
+    CameraBuilder, Color, Matrix3x3, Matrix4x4, Node, Vector3,
+    background::{EnvironmentMap, GradientBackground, SkyBackground, SolidBackground},
+    import::{ImportOptions, load_obj, load_off, load_stl, load_vol},
+    light::{PointLight, SpotLight, SunLight},
+    material::{
+        Clearcoat, Dielectric, DiffuseLight, Lambertian, Material, Metal, MetallicRoughness,
+        RefractiveIndex, Subsurface,
+    },
+    object::{
+        AnimatedTransform, BoxPrimitive, ConeFrustum, ConstantMedium, Csg, CsgOperation, Disc,
+        Group, HeterogeneousMedium, Instance, Keyframe, Quad, Sphere, Transform, TriangleMesh,
+    },
+    texture::{ImageTexture, SolidColor, Texture},
+    volume::{DensityField, TextureDensity},
 };
 
 use crate::{
     Message, MessageLevel, Position, Result,
-    interpreter::Interpreter,
+    interpreter::{ImportedNode, Interpreter},
     parser::{CallArgument, CallArgumentWithPosition, ModuleIdWithPosition, StatementWithPosition},
-    value::Value,
+    value::{Value, ValueWithPosition},
 };
 
 impl Interpreter {
@@ -34,11 +45,24 @@ impl Interpreter {
         } else if module_id.item == "metal" {
             let m = self.create_metal(arguments)?;
             self.material_stack.push(m);
+        } else if module_id.item == "pbr" {
+            let m = self.create_pbr(arguments)?;
+            self.material_stack.push(m);
+        } else if module_id.item == "clearcoat" {
+            let m = self.create_clearcoat(arguments)?;
+            self.material_stack.push(m);
+        } else if module_id.item == "subsurface" {
+            let m = self.create_subsurface(arguments)?;
+            self.material_stack.push(m);
         } else if module_id.item == "diffuse_light" {
             let m = self.create_diffuse_light(arguments)?;
             self.material_stack.push(m);
         } else if module_id.item == "for" {
             return self.process_for_loop(arguments, child_statements);
+        } else if module_id.item == "difference" {
+            return self.create_csg(CsgOperation::Difference, child_statements);
+        } else if module_id.item == "intersection" {
+            return self.create_csg(CsgOperation::Intersection, child_statements);
         }
 
         let child_nodes = self.process_child_statements(child_statements)?;
@@ -51,17 +75,31 @@ impl Interpreter {
                 .create_cylinder(arguments, child_nodes)
                 .map(|n| vec![n]),
             "quad" => self.create_quad(arguments, child_nodes).map(|n| vec![n]),
-            "translate" => self
-                .create_translate(arguments, child_nodes)
-                .map(|n| vec![n]),
-            "rotate" => self.create_rotate(arguments, child_nodes).map(|n| vec![n]),
-            "scale" => self.create_scale(arguments, child_nodes).map(|n| vec![n]),
+            "translate" => self.create_translate(arguments, child_nodes),
+            "rotate" => self.create_rotate(arguments, child_nodes),
+            "scale" => self.create_scale(arguments, child_nodes),
+            "mirror" => self.create_mirror(arguments, child_nodes),
+            "multmatrix" => self.create_multmatrix(arguments, child_nodes),
+            "motion" => self.create_motion(arguments, child_nodes),
+            "import" => self.create_import(arguments, child_nodes, module_position),
+            "union" => Ok(create_union(child_nodes).into_iter().collect()),
+            "medium" => self.create_medium(arguments, child_nodes),
             "camera" => self.create_camera(arguments, child_nodes).map(|_| vec![]),
-            "color" | "lambertian" | "dielectric" | "metal" | "diffuse_light" => {
+            "point_light" => self
+                .create_point_light(arguments, child_nodes, module_position)
+                .map(|_| vec![]),
+            "spot_light" => self
+                .create_spot_light(arguments, child_nodes, module_position)
+                .map(|_| vec![]),
+            "sun" => self
+                .create_sun(arguments, child_nodes, module_position)
+                .map(|_| vec![]),
+            "color" | "lambertian" | "dielectric" | "metal" | "pbr" | "clearcoat"
+            | "subsurface" | "diffuse_light" => {
                 self.material_stack.pop();
                 Ok(child_nodes)
             }
-            "for" => panic!("already handled"),
+            "for" | "difference" | "intersection" => panic!("already handled"),
             "echo" => self
                 .evaluate_echo(arguments, child_nodes, module_position)
                 .map(|_| vec![]),
@@ -267,82 +305,330 @@ impl Interpreter {
         &mut self,
         arguments: &[CallArgumentWithPosition],
         child_nodes: Vec<Arc<dyn Node>>,
-    ) -> Result<Arc<dyn Node>> {
+    ) -> Result<Vec<Arc<dyn Node>>> {
+        // like OpenSCAD, there is nothing to transform
         if child_nodes.is_empty() {
-            todo!("should have children");
+            return Ok(vec![]);
         }
-        let child = Arc::new(Group::from_list(&child_nodes));
-
-        let mut offset = Vector3::new(0.0, 0.0, 0.0);
 
         let arguments = self.convert_args(&["v"], arguments)?;
 
+        let mut matrix = Matrix4x4::IDENTITY;
         if let Some(arg) = arguments.get("v") {
-            offset = arg.item.to_vector3()?;
+            matrix = Matrix4x4::translation(openscad_vector(arg, 0.0)?);
         }
 
-        let translate = Translate::new(child, offset);
-        Ok(Arc::new(translate))
+        Ok(vec![create_transform(child_nodes, &matrix)])
     }
 
     fn create_rotate(
         &mut self,
         arguments: &[CallArgumentWithPosition],
         child_nodes: Vec<Arc<dyn Node>>,
-    ) -> Result<Arc<dyn Node>> {
+    ) -> Result<Vec<Arc<dyn Node>>> {
         if child_nodes.is_empty() {
-            todo!("should have children");
+            return Ok(vec![]);
         }
-        let child = Arc::new(Group::from_list(&child_nodes));
 
         let arguments = self.convert_args(&["a", "v"], arguments)?;
 
-        if let Some(arg) = arguments.get("a") {
-            match &arg.item {
-                Value::Number(_deg_a) => todo!(),
-                Value::Vector { items } => {
-                    let a = Value::values_to_vector3(items)?;
-                    let mut result: Arc<dyn Node> = child;
-                    if a.x != 0.0 {
-                        result = Arc::new(Rotate::rotate_x(result, a.x));
-                    }
-                    if a.y != 0.0 {
-                        result = Arc::new(Rotate::rotate_y(result, a.y));
-                    }
-                    if a.z != 0.0 {
-                        result = Arc::new(Rotate::rotate_z(result, a.z));
+        let mut matrix = Matrix4x4::IDENTITY;
+        if let Some(a) = arguments.get("a") {
+            matrix = openscad_rotation(a, arguments.get("v"))?;
+        }
+
+        Ok(vec![create_transform(child_nodes, &matrix)])
+    }
+
+    fn create_scale(
+        &mut self,
+        arguments: &[CallArgumentWithPosition],
+        child_nodes: Vec<Arc<dyn Node>>,
+    ) -> Result<Vec<Arc<dyn Node>>> {
+        if child_nodes.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let arguments = self.convert_args(&["v"], arguments)?;
+
+        let mut matrix = Matrix4x4::IDENTITY;
+        if let Some(arg) = arguments.get("v") {
+            matrix = openscad_scaling(arg)?;
+        }
+
+        Ok(vec![create_transform(child_nodes, &matrix)])
+    }
+
+    fn create_mirror(
+        &mut self,
+        arguments: &[CallArgumentWithPosition],
+        child_nodes: Vec<Arc<dyn Node>>,
+    ) -> Result<Vec<Arc<dyn Node>>> {
+        if child_nodes.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let arguments = self.convert_args(&["v"], arguments)?;
+
+        // like OpenSCAD, mirror across the yz plane by default
+        let mut normal = Vector3::new(1.0, 0.0, 0.0);
+        if let Some(arg) = arguments.get("v") {
+            normal = openscad_vector(arg, 0.0)?;
+        }
+        // like OpenSCAD, mirroring across a plane without a normal does nothing
+        let matrix = if normal.length() > 0.0 {
+            Matrix4x4::reflection(normal)
+        } else {
+            Matrix4x4::IDENTITY
+        };
+
+        Ok(vec![create_transform(child_nodes, &matrix)])
+    }
+
+    fn create_multmatrix(
+        &mut self,
+        arguments: &[CallArgumentWithPosition],
+        child_nodes: Vec<Arc<dyn Node>>,
+    ) -> Result<Vec<Arc<dyn Node>>> {
+        if child_nodes.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let arguments = self.convert_args(&["m"], arguments)?;
+
+        let Some(arg) = arguments.get("m") else {
+            return Ok(vec![create_transform(child_nodes, &Matrix4x4::IDENTITY)]);
+        };
+        let invalid = || Message {
+            level: MessageLevel::Error,
+            message: "m must be a 4x4 matrix, a list of 3 or 4 rows of 4 numbers".to_string(),
+            position: arg.position.clone(),
+        };
+        let Value::Vector { items: rows } = &arg.item else {
+            return Err(invalid());
+        };
+        if rows.len() != 3 && rows.len() != 4 {
+            return Err(invalid());
+        }
+        // a missing last row is [0, 0, 0, 1], and OpenSCAD ignores it anyway
+        let mut matrix = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        for (row, values) in rows.iter().take(3).enumerate() {
+            match values {
+                Value::Vector { items } if items.len() == 4 => {
+                    for (col, value) in items.iter().enumerate() {
+                        matrix[row][col] = value.to_number()?;
                     }
-                    return Ok(result);
                 }
-                _ => todo!("add error"),
+                _ => return Err(invalid()),
             }
         }
 
-        if let Some(_arg) = arguments.get("v") {
-            todo!();
+        Ok(vec![create_transform(child_nodes, &Matrix4x4::new(matrix))])
+    }
+
+    /// Moves the children from where they are at the start time to where
+    /// `translate(translate) rotate(rotate) scale(scale)` would place them at the end
+    /// time.
+    fn create_motion(
+        &mut self,
+        arguments: &[CallArgumentWithPosition],
+        child_nodes: Vec<Arc<dyn Node>>,
+    ) -> Result<Vec<Arc<dyn Node>>> {
+        if child_nodes.is_empty() {
+            return Ok(vec![]);
         }
+        let child = Arc::new(Group::from_list(&child_nodes));
+
+        let arguments = self.convert_args(&["translate", "rotate", "scale", "time"], arguments)?;
 
-        todo!();
+        let mut matrix = Matrix4x4::IDENTITY;
+        if let Some(arg) = arguments.get("scale") {
+            matrix = openscad_scaling(arg)?;
+        }
+        if let Some(arg) = arguments.get("rotate") {
+            matrix = &openscad_rotation(arg, None)? * &matrix;
+        }
+        if let Some(arg) = arguments.get("translate") {
+            matrix = &Matrix4x4::translation(openscad_vector(arg, 0.0)?) * &matrix;
+        }
+
+        let (mut start_time, mut end_time) = (0.0, 1.0);
+        if let Some(arg) = arguments.get("time") {
+            match &arg.item {
+                Value::Vector { items } if items.len() == 2 => {
+                    start_time = items[0].to_number()?;
+                    end_time = items[1].to_number()?;
+                }
+                _ => {
+                    return Err(Message {
+                        level: MessageLevel::Error,
+                        message: "time must be a list of 2 numbers [start, end]".to_string(),
+                        position: arg.position.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(vec![Arc::new(AnimatedTransform::new(
+            child,
+            Keyframe::identity(start_time),
+            Keyframe::from_matrix(end_time, &to_renderer_transform(&matrix)),
+        ))])
     }
 
-    fn create_scale(
+    fn create_import(
         &mut self,
         arguments: &[CallArgumentWithPosition],
         child_nodes: Vec<Arc<dyn Node>>,
-    ) -> Result<Arc<dyn Node>> {
+        module_position: Position,
+    ) -> Result<Vec<Arc<dyn Node>>> {
+        if !child_nodes.is_empty() {
+            return Err(unexpected_children("import", module_position));
+        }
+
+        let arguments = self.convert_args(&["file", "convexity"], arguments)?;
+
+        let Some(arg) = arguments.get("file") else {
+            return Err(Message {
+                level: MessageLevel::Error,
+                message: "missing file argument".to_string(),
+                position: module_position,
+            });
+        };
+        let position = &arg.position;
+        let filename = arg.item.to_unescaped_string()?;
+        let extension = filename
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_lowercase())
+            .unwrap_or_default();
+
+        let material = self.current_material();
+        if !self.imports.contains_key(&filename) {
+            let mut options = ImportOptions::new(material.clone());
+            // models are in OpenSCAD coordinates, see "Axis Conversion" in the README
+            options.transform =
+                Matrix3x3::new([[-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]]);
+            let source = position.source.as_ref();
+
+            let result = match extension.as_str() {
+                "obj" => load_obj(source, &filename, &options),
+                "stl" => load_stl(source, &filename, &options)
+                    .map(|mesh| vec![Arc::new(mesh) as Arc<dyn Node>]),
+                "off" => load_off(source, &filename, &options)
+                    .map(|mesh| vec![Arc::new(mesh) as Arc<dyn Node>]),
+                _ => {
+                    return Err(Message {
+                        level: MessageLevel::Error,
+                        message: format!("unsupported import format \"{filename}\""),
+                        position: position.clone(),
+                    });
+                }
+            };
+
+            let nodes = result.map_err(|err| Message {
+                level: MessageLevel::Error,
+                message: format!("failed to import \"{filename}\": {err:?}"),
+                position: position.clone(),
+            })?;
+            let imported = nodes
+                .into_iter()
+                .map(|prototype| ImportedNode {
+                    default_material: prototype
+                        .as_any()
+                        .downcast_ref::<TriangleMesh>()
+                        .is_some_and(|mesh| Arc::ptr_eq(mesh.material(), &material)),
+                    prototype,
+                })
+                .collect();
+            self.imports.insert(filename.clone(), imported);
+        }
+
+        // each import is an instance of the same geometry, so importing a file in a
+        // loop only loads it once
+        Ok(self.imports[&filename]
+            .iter()
+            .map(|imported| {
+                let instance = Instance::new(imported.prototype.clone(), Matrix4x4::IDENTITY);
+                let instance = if imported.default_material {
+                    instance.with_material(material.clone())
+                } else {
+                    instance
+                };
+                Arc::new(instance) as Arc<dyn Node>
+            })
+            .collect())
+    }
+
+    /// Combines the children with `operation`, each child statement being one operand
+    /// even when it creates several objects, like a `for` loop.
+    fn create_csg(
+        &mut self,
+        operation: CsgOperation,
+        child_statements: &[StatementWithPosition],
+    ) -> Result<Vec<Arc<dyn Node>>> {
+        let mut operands = vec![];
+        for statement in child_statements {
+            let nodes = self.process_statement(statement)?;
+            operands.extend(create_union(nodes));
+        }
+        Ok(match operands.len() {
+            0 => vec![],
+            1 => operands,
+            _ => vec![Arc::new(Csg::new(operation, operands))],
+        })
+    }
+
+    fn create_medium(
+        &mut self,
+        arguments: &[CallArgumentWithPosition],
+        child_nodes: Vec<Arc<dyn Node>>,
+    ) -> Result<Vec<Arc<dyn Node>>> {
+        // there is no boundary to fill
         if child_nodes.is_empty() {
-            todo!("should have children");
+            return Ok(vec![]);
         }
-        let child = Arc::new(Group::from_list(&child_nodes));
+        let boundary = Arc::new(Group::from_list(&child_nodes));
 
-        let arguments = self.convert_args(&["v"], arguments)?;
+        let arguments = self.convert_args(&["density", "c", "field"], arguments)?;
 
-        if let Some(arg) = arguments.get("v") {
-            let v = arg.item.to_vector3()?;
-            return Ok(Arc::new(Scale::new(child, v.x, v.y, v.z)));
+        let mut density = 1.0;
+        if let Some(arg) = arguments.get("density") {
+            density = arg.item.to_number()?;
         }
 
-        todo!("missing arg");
+        let mut texture: Arc<dyn Texture> = Arc::new(SolidColor::new(Color::WHITE));
+        if let Some(arg) = arguments.get("c") {
+            texture = arg.item.to_texture()?;
+        }
+
+        let Some(arg) = arguments.get("field") else {
+            return Ok(vec![Arc::new(ConstantMedium::new_from_texture(
+                boundary, density, texture,
+            ))]);
+        };
+        let density_field: Arc<dyn DensityField> = match &arg.item {
+            Value::String(filename) => {
+                let grid =
+                    load_vol(arg.position.source.as_ref(), filename).map_err(|err| Message {
+                        level: MessageLevel::Error,
+                        message: format!("failed to import \"{filename}\": {err:?}"),
+                        position: arg.position.clone(),
+                    })?;
+                Arc::new(OpenScadDensity(Arc::new(grid)))
+            }
+            other => Arc::new(TextureDensity::new(other.to_texture()?)),
+        };
+        Ok(vec![Arc::new(HeterogeneousMedium::new_from_texture(
+            boundary,
+            density,
+            density_field,
+            texture,
+        ))])
     }
 
     fn create_camera(
@@ -359,6 +645,9 @@ impl Interpreter {
                 "image_width",
                 "image_height",
                 "samples_per_pixel",
+                "adaptive_threshold",
+                "min_samples_per_pixel",
+                "sampler",
                 "max_depth",
                 "vertical_fov",
                 "look_from",
@@ -368,6 +657,13 @@ impl Interpreter {
                 "focus_distance",
                 "background",
                 "aspect_ratio",
+                "exposure",
+                "tone_mapping",
+                "sun_direction",
+                "turbidity",
+                "spectral",
+                "shutter_open",
+                "shutter_close",
             ],
             arguments,
         )?;
@@ -391,6 +687,14 @@ impl Interpreter {
             camera_builder.samples_per_pixel = arg.item.to_number()? as u32;
         }
 
+        if let Some(arg) = arguments.get("adaptive_threshold") {
+            camera_builder.adaptive_threshold = arg.item.to_number()?;
+        }
+
+        if let Some(arg) = arguments.get("min_samples_per_pixel") {
+            camera_builder.min_samples_per_pixel = arg.item.to_number()? as u32;
+        }
+
         if let Some(arg) = arguments.get("max_depth") {
             camera_builder.max_depth = arg.item.to_number()? as u32;
         }
@@ -432,7 +736,86 @@ impl Interpreter {
         }
 
         if let Some(arg) = arguments.get("background") {
-            camera_builder.background = arg.item.to_color()?;
+            match &arg.item {
+                Value::String(name) => {
+                    camera_builder.background = match name.as_str() {
+                        "gradient" => Arc::new(GradientBackground::default()),
+                        "sky" => {
+                            let mut sun_direction = Vector3::new(-1.0, 1.0, 1.0);
+                            if let Some(arg) = arguments.get("sun_direction") {
+                                sun_direction = arg.item.to_vector3()?;
+                            }
+                            let mut turbidity = 3.0;
+                            if let Some(arg) = arguments.get("turbidity") {
+                                turbidity = arg.item.to_number()?;
+                            }
+                            Arc::new(SkyBackground::new(sun_direction, turbidity))
+                        }
+                        _ => {
+                            return Err(Message {
+                                level: MessageLevel::Error,
+                                message: format!("unknown background \"{name}\""),
+                                position: arg.position.clone(),
+                            });
+                        }
+                    };
+                }
+                Value::Texture(texture) => {
+                    let Some(image_texture) = texture.as_any().downcast_ref::<ImageTexture>()
+                    else {
+                        return Err(Message {
+                            level: MessageLevel::Error,
+                            message: "background texture must be an image".to_owned(),
+                            position: arg.position.clone(),
+                        });
+                    };
+                    camera_builder.background =
+                        Arc::new(EnvironmentMap::new(image_texture.image()));
+                }
+                other => {
+                    camera_builder.background = Arc::new(SolidBackground::new(other.to_color()?))
+                }
+            }
+        }
+
+        if let Some(arg) = arguments.get("exposure") {
+            camera_builder.exposure = arg.item.to_number()?;
+        }
+
+        if let Some(arg) = arguments.get("tone_mapping") {
+            camera_builder.tone_mapping =
+                arg.item
+                    .to_unescaped_string()?
+                    .parse()
+                    .map_err(|message| Message {
+                        level: MessageLevel::Error,
+                        message,
+                        position: arg.position.clone(),
+                    })?;
+        }
+
+        if let Some(arg) = arguments.get("spectral") {
+            camera_builder.spectral = arg.item.to_boolean()?;
+        }
+
+        if let Some(arg) = arguments.get("shutter_open") {
+            camera_builder.shutter_open = arg.item.to_number()?;
+        }
+
+        if let Some(arg) = arguments.get("shutter_close") {
+            camera_builder.shutter_close = arg.item.to_number()?;
+        }
+
+        if let Some(arg) = arguments.get("sampler") {
+            camera_builder.sampler =
+                arg.item
+                    .to_unescaped_string()?
+                    .parse()
+                    .map_err(|message| Message {
+                        level: MessageLevel::Error,
+                        message,
+                        position: arg.position.clone(),
+                    })?;
         }
 
         self.camera = Some(Arc::new(camera_builder.build()));
@@ -490,6 +873,120 @@ impl Interpreter {
         todo!("missing arg");
     }
 
+    fn create_point_light(
+        &mut self,
+        arguments: &[CallArgumentWithPosition],
+        child_nodes: Vec<Arc<dyn Node>>,
+        module_position: Position,
+    ) -> Result<()> {
+        if !child_nodes.is_empty() {
+            return Err(unexpected_children("point_light", module_position));
+        }
+
+        let arguments = self.convert_args(&["position", "c"], arguments)?;
+
+        let mut position = Vector3::ZERO;
+        let mut color = Color::WHITE;
+
+        if let Some(arg) = arguments.get("position") {
+            position = arg.item.to_vector3()?;
+        }
+
+        if let Some(arg) = arguments.get("c") {
+            color = arg.item.to_color()?;
+        }
+
+        self.light_sources
+            .push(Arc::new(PointLight::new(position, color)));
+        Ok(())
+    }
+
+    fn create_spot_light(
+        &mut self,
+        arguments: &[CallArgumentWithPosition],
+        child_nodes: Vec<Arc<dyn Node>>,
+        module_position: Position,
+    ) -> Result<()> {
+        if !child_nodes.is_empty() {
+            return Err(unexpected_children("spot_light", module_position));
+        }
+
+        let arguments = self.convert_args(
+            &["position", "direction", "c", "cutoff", "falloff_start"],
+            arguments,
+        )?;
+
+        let mut position = Vector3::ZERO;
+        // [0, 0, -1] in OpenSCAD coordinates
+        let mut direction = Vector3::new(0.0, -1.0, 0.0);
+        let mut color = Color::WHITE;
+        let mut cutoff = 30.0;
+
+        if let Some(arg) = arguments.get("position") {
+            position = arg.item.to_vector3()?;
+        }
+
+        if let Some(arg) = arguments.get("direction") {
+            direction = arg.item.to_vector3()?;
+        }
+
+        if let Some(arg) = arguments.get("c") {
+            color = arg.item.to_color()?;
+        }
+
+        if let Some(arg) = arguments.get("cutoff") {
+            cutoff = arg.item.to_number()?;
+        }
+
+        let mut falloff_start = cutoff * 0.75;
+        if let Some(arg) = arguments.get("falloff_start") {
+            falloff_start = arg.item.to_number()?;
+        }
+
+        self.light_sources.push(Arc::new(SpotLight::new(
+            position,
+            direction,
+            color,
+            cutoff,
+            falloff_start,
+        )));
+        Ok(())
+    }
+
+    fn create_sun(
+        &mut self,
+        arguments: &[CallArgumentWithPosition],
+        child_nodes: Vec<Arc<dyn Node>>,
+        module_position: Position,
+    ) -> Result<()> {
+        if !child_nodes.is_empty() {
+            return Err(unexpected_children("sun", module_position));
+        }
+
+        let arguments = self.convert_args(&["direction", "c", "angular_diameter"], arguments)?;
+
+        // [-1, 1, 1] in OpenSCAD coordinates
+        let mut direction = Vector3::new(1.0, 1.0, 1.0);
+        let mut color = Color::WHITE;
+        let mut angular_diameter = 0.53;
+
+        if let Some(arg) = arguments.get("direction") {
+            direction = arg.item.to_vector3()?;
+        }
+
+        if let Some(arg) = arguments.get("c") {
+            color = arg.item.to_color()?;
+        }
+
+        if let Some(arg) = arguments.get("angular_diameter") {
+            angular_diameter = arg.item.to_number()?;
+        }
+
+        self.light_sources
+            .push(Arc::new(SunLight::new(direction, color, angular_diameter)));
+        Ok(())
+    }
+
     fn create_lambertian(
         &mut self,
         arguments: &[CallArgumentWithPosition],
@@ -513,14 +1010,49 @@ impl Interpreter {
         &mut self,
         arguments: &[CallArgumentWithPosition],
     ) -> Result<Arc<dyn Material>> {
-        let arguments = self.convert_args(&["n"], arguments)?;
+        let arguments = self.convert_args(
+            &["n", "c", "distance", "roughness", "cauchy", "sellmeier"],
+            arguments,
+        )?;
 
-        if let Some(arg) = arguments.get("n") {
-            let refraction_index = arg.item.to_number()?;
-            Ok(Arc::new(Dielectric::new(refraction_index)))
+        let refraction_index = if let Some(arg) = arguments.get("n") {
+            match &arg.item {
+                Value::String(name) => name.parse().map_err(|message| Message {
+                    level: MessageLevel::Error,
+                    message,
+                    position: arg.position.clone(),
+                })?,
+                other => RefractiveIndex::Constant(other.to_number()?),
+            }
+        } else if let Some(arg) = arguments.get("cauchy") {
+            let [a, b] = vector_numbers(arg, "cauchy expects [a, b]")?;
+            RefractiveIndex::Cauchy { a, b }
+        } else if let Some(arg) = arguments.get("sellmeier") {
+            let [b1, b2, b3, c1, c2, c3] =
+                vector_numbers(arg, "sellmeier expects [b1, b2, b3, c1, c2, c3]")?;
+            RefractiveIndex::Sellmeier {
+                b: [b1, b2, b3],
+                c: [c1, c2, c3],
+            }
         } else {
             todo!("missing arg");
+        };
+        let mut dielectric = Dielectric::new_from_index(refraction_index);
+
+        if let Some(arg) = arguments.get("c") {
+            let tint = arg.item.to_color()?;
+            let mut distance = 1.0;
+            if let Some(arg) = arguments.get("distance") {
+                distance = arg.item.to_number()?;
+            }
+            dielectric = dielectric.with_absorption(tint, distance);
+        }
+
+        if let Some(arg) = arguments.get("roughness") {
+            dielectric = dielectric.with_roughness(arg.item.to_number()?);
         }
+
+        Ok(Arc::new(dielectric))
     }
 
     fn create_metal(
@@ -543,6 +1075,80 @@ impl Interpreter {
         Ok(Arc::new(Metal::new(color, fuzz)))
     }
 
+    fn create_pbr(&mut self, arguments: &[CallArgumentWithPosition]) -> Result<Arc<dyn Material>> {
+        let arguments = self.convert_args(&["c", "metallic", "roughness", "t"], arguments)?;
+
+        let mut base_color: Arc<dyn Texture> = Arc::new(SolidColor::new(Color::WHITE));
+        let mut metallic: Arc<dyn Texture> = Arc::new(SolidColor::new(Color::BLACK));
+        let mut roughness: Arc<dyn Texture> = Arc::new(SolidColor::new(Color::WHITE * 0.5));
+
+        if let Some(arg) = arguments.get("c") {
+            base_color = arg.item.to_texture()?;
+        } else if let Some(arg) = arguments.get("t") {
+            base_color = arg.item.to_texture()?;
+        }
+
+        if let Some(arg) = arguments.get("metallic") {
+            metallic = arg.item.to_texture()?;
+        }
+
+        if let Some(arg) = arguments.get("roughness") {
+            roughness = arg.item.to_texture()?;
+        }
+
+        Ok(Arc::new(MetallicRoughness::new(
+            base_color, metallic, roughness,
+        )))
+    }
+
+    fn create_clearcoat(
+        &mut self,
+        arguments: &[CallArgumentWithPosition],
+    ) -> Result<Arc<dyn Material>> {
+        let arguments = self.convert_args(&["n", "roughness", "c"], arguments)?;
+
+        let mut refraction_index = 1.5;
+        if let Some(arg) = arguments.get("n") {
+            refraction_index = arg.item.to_number()?;
+        }
+
+        let mut clearcoat = Clearcoat::new(self.current_material(), refraction_index);
+
+        if let Some(arg) = arguments.get("roughness") {
+            clearcoat = clearcoat.with_roughness(arg.item.to_number()?);
+        }
+
+        if let Some(arg) = arguments.get("c") {
+            clearcoat = clearcoat.with_tint(arg.item.to_color()?);
+        }
+
+        Ok(Arc::new(clearcoat))
+    }
+
+    fn create_subsurface(
+        &mut self,
+        arguments: &[CallArgumentWithPosition],
+    ) -> Result<Arc<dyn Material>> {
+        let arguments = self.convert_args(&["scattering", "absorption", "g"], arguments)?;
+
+        let mut scattering = Color::WHITE * 10.0;
+        if let Some(arg) = arguments.get("scattering") {
+            scattering = arg.item.to_color()?;
+        }
+
+        let mut absorption = Color::BLACK;
+        if let Some(arg) = arguments.get("absorption") {
+            absorption = arg.item.to_color()?;
+        }
+
+        let mut subsurface = Subsurface::new(scattering, absorption);
+        if let Some(arg) = arguments.get("g") {
+            subsurface = subsurface.with_anisotropy(arg.item.to_number()?);
+        }
+
+        Ok(Arc::new(subsurface))
+    }
+
     fn create_diffuse_light(
         &mut self,
         arguments: &[CallArgumentWithPosition],
@@ -558,3 +1164,150 @@ impl Interpreter {
         Ok(Arc::new(DiffuseLight::new_from_color(color)))
     }
 }
+
+/// Returns the `N` numbers of a vector argument, or an error with `message`.
+fn vector_numbers<const N: usize>(arg: &ValueWithPosition, message: &str) -> Result<[f64; N]> {
+    let error = || Message {
+        level: MessageLevel::Error,
+        message: message.to_owned(),
+        position: arg.position.clone(),
+    };
+    let Value::Vector { items } = &arg.item else {
+        return Err(error());
+    };
+    let numbers = items
+        .iter()
+        .map(|item| item.to_number())
+        .collect::<std::result::Result<Vec<f64>, _>>()?;
+    numbers.try_into().map_err(|_| error())
+}
+
+/// Looks up a density field laid out in OpenSCAD coordinates, like voxel files, see
+/// "Axis Conversion" in the README.
+#[derive(Debug)]
+struct OpenScadDensity(Arc<dyn DensityField>);
+
+impl DensityField for OpenScadDensity {
+    fn density(&self, pt: Vector3) -> f64 {
+        self.0.density(Vector3::new(-pt.x, pt.z, pt.y))
+    }
+
+    fn max_density(&self) -> f64 {
+        self.0.max_density()
+    }
+}
+
+/// Converts points from OpenSCAD coordinates to ours and back, see "Axis Conversion"
+/// in the README.
+const AXIS_CONVERSION: Matrix4x4 = Matrix4x4::new([
+    [-1.0, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+]);
+
+/// Converts a transformation in OpenSCAD coordinates to the same transformation in
+/// ours.
+fn to_renderer_transform(matrix: &Matrix4x4) -> Matrix4x4 {
+    &(&AXIS_CONVERSION * matrix) * &AXIS_CONVERSION
+}
+
+/// Transforms the children by `matrix`, in OpenSCAD coordinates. A single child that
+/// is already transformed, or an instance, gets the product of both matrices instead,
+/// so a chain of transformations is a single node.
+fn create_transform(child_nodes: Vec<Arc<dyn Node>>, matrix: &Matrix4x4) -> Arc<dyn Node> {
+    let matrix = to_renderer_transform(matrix);
+    let child: Arc<dyn Node> = match child_nodes.as_slice() {
+        [child] => {
+            if let Some(transform) = child.as_any().downcast_ref::<Transform>() {
+                return Arc::new(Transform::new(
+                    transform.object().clone(),
+                    &matrix * transform.matrix(),
+                ));
+            }
+            if let Some(instance) = child.as_any().downcast_ref::<Instance>() {
+                let moved =
+                    Instance::new(instance.prototype().clone(), &matrix * instance.matrix());
+                return Arc::new(match instance.material() {
+                    Some(material) => moved.with_material(material.clone()),
+                    None => moved,
+                });
+            }
+            child.clone()
+        }
+        _ => Arc::new(Group::from_list(&child_nodes)),
+    };
+    Arc::new(Transform::new(child, matrix))
+}
+
+/// Returns the error for children given to a module that doesn't use them.
+fn unexpected_children(module: &str, position: Position) -> Message {
+    Message {
+        level: MessageLevel::Error,
+        message: format!("{module}() doesn't take children"),
+        position,
+    }
+}
+
+/// Returns the union of the nodes, `None` if there are none.
+fn create_union(mut nodes: Vec<Arc<dyn Node>>) -> Option<Arc<dyn Node>> {
+    match nodes.len() {
+        0 => None,
+        1 => nodes.pop(),
+        _ => Some(Arc::new(Csg::union(nodes))),
+    }
+}
+
+/// Returns a [x, y, z] or [x, y] vector in OpenSCAD coordinates, unlike
+/// [`Value::to_vector3`], `z` defaulting to `default_z`.
+fn openscad_vector(arg: &ValueWithPosition, default_z: f64) -> Result<Vector3> {
+    match &arg.item {
+        Value::Vector { items } if items.len() == 2 || items.len() == 3 => {
+            let z = match items.get(2) {
+                Some(z) => z.to_number()?,
+                None => default_z,
+            };
+            Ok(Vector3::new(
+                items[0].to_number()?,
+                items[1].to_number()?,
+                z,
+            ))
+        }
+        _ => Err(Message {
+            level: MessageLevel::Error,
+            message: "expected a vector [x, y, z] or [x, y]".to_string(),
+            position: arg.position.clone(),
+        }),
+    }
+}
+
+/// Returns the rotation of `rotate(a, v)` in OpenSCAD coordinates: by `a` degrees
+/// around `v`, or the z axis without it, or for `a = [x, y, z]` around the x axis
+/// first, then y and z.
+fn openscad_rotation(a: &ValueWithPosition, v: Option<&ValueWithPosition>) -> Result<Matrix4x4> {
+    let x_axis = Vector3::new(1.0, 0.0, 0.0);
+    let y_axis = Vector3::new(0.0, 1.0, 0.0);
+    let z_axis = Vector3::new(0.0, 0.0, 1.0);
+    match (&a.item, v) {
+        (Value::Number(angle), Some(v)) => {
+            Ok(Matrix4x4::rotation(openscad_vector(v, 0.0)?, *angle))
+        }
+        (Value::Number(angle), None) => Ok(Matrix4x4::rotation(z_axis, *angle)),
+        _ => {
+            let angles = openscad_vector(a, 0.0)?;
+            Ok(
+                &(&Matrix4x4::rotation(z_axis, angles.z) * &Matrix4x4::rotation(y_axis, angles.y))
+                    * &Matrix4x4::rotation(x_axis, angles.x),
+            )
+        }
+    }
+}
+
+/// Returns the scaling of `scale(v)` in OpenSCAD coordinates, uniform for a number.
+fn openscad_scaling(v: &ValueWithPosition) -> Result<Matrix4x4> {
+    let factors = match &v.item {
+        Value::Number(factor) => Vector3::new(*factor, *factor, *factor),
+        _ => openscad_vector(v, 1.0)?,
+    };
+    Ok(Matrix4x4::scaling(factors))
+}
@@ -3,8 +3,17 @@ mod tests {
     use std::sync::Arc;
 
     use caustic_core::{
-        object::{BoundingVolumeHierarchy, Disc},
+        Axis, Color, Interval, Node, Ray, RenderContext, Vector3,
+        material::{
+            Clearcoat, Dielectric, Isotropic, Lambertian, Material, MetallicRoughness,
+            RefractiveIndex, Subsurface,
+        },
+        object::{
+            AnimatedTransform, BoundingVolumeHierarchy, ConstantMedium, Csg, CsgOperation, Disc,
+            HeterogeneousMedium, Sphere, Transform,
+        },
         random_new,
+        texture::{CheckerTexture, SolidColor, Texture},
     };
 
     use crate::{
@@ -55,11 +64,513 @@ mod tests {
             .as_any()
             .downcast_ref::<BoundingVolumeHierarchy>()
             .unwrap();
-        let left = bvh.get_left();
-        let disc = left.as_any().downcast_ref::<Disc>().unwrap();
+        assert_eq!(bvh.primitives().len(), 1);
+        let disc = bvh.primitives()[0].as_any().downcast_ref::<Disc>().unwrap();
         assert_eq!(disc.get_radius(), 20.0);
     }
 
+    #[test]
+    fn test_light_sources() {
+        let results = interpret(
+            "point_light(position=[0, 0, 5]); spot_light(position=[0, 0, 5], cutoff=20); sun(direction=[0, 0, 1], angular_diameter=0);",
+        );
+        assert_eq!(results.messages.len(), 0);
+
+        let scene_data = results.scene_data.unwrap();
+        assert!(scene_data.lights.geometry.is_none());
+        let random = random_new();
+        let sources = &scene_data.lights.sources;
+        assert_eq!(sources.len(), 3);
+
+        // OpenSCAD z is our y
+        let sample = sources[0].sample(&*random, &Vector3::ZERO).unwrap();
+        assert_eq!(sample.direction, Vector3::new(0.0, 1.0, 0.0));
+        assert_eq!(sample.distance, 5.0);
+
+        // the spot points down, fading out from 0.75 * cutoff
+        let below = |degrees: f64| Vector3::new(5.0 * degrees.to_radians().tan(), 0.0, 0.0);
+        let radiance = |degrees: f64| {
+            sources[1]
+                .sample(&*random, &below(degrees))
+                .map_or(0.0, |sample| sample.radiance.r * sample.distance.powi(2))
+        };
+        assert!((radiance(0.0) - 1.0).abs() < 1e-9);
+        assert!((radiance(14.9) - 1.0).abs() < 1e-9);
+        assert!(radiance(15.5) < 1.0 && radiance(15.5) > 0.0);
+        assert_eq!(radiance(20.5), 0.0);
+
+        let sample = sources[2].sample(&*random, &Vector3::ZERO).unwrap();
+        assert_eq!(sample.direction, Vector3::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn test_light_source_defaults() {
+        let results = interpret("spot_light(); sun(angular_diameter=0);");
+        assert_eq!(results.messages.len(), 0);
+
+        let scene_data = results.scene_data.unwrap();
+        let random = random_new();
+        let sources = &scene_data.lights.sources;
+        // the spot points down, towards OpenSCAD -z
+        assert!(
+            sources[0]
+                .sample(&*random, &Vector3::new(0.0, -1.0, 0.0))
+                .is_some()
+        );
+        assert!(
+            sources[0]
+                .sample(&*random, &Vector3::new(0.0, 0.0, -1.0))
+                .is_none()
+        );
+        // the sun is towards OpenSCAD [-1, 1, 1]
+        let sample = sources[1].sample(&*random, &Vector3::ZERO).unwrap();
+        assert!((sample.direction - Vector3::new(1.0, 1.0, 1.0).unit()).length() < 1e-12);
+    }
+
+    #[test]
+    fn test_light_sources_without_children() {
+        assert_output_trim(
+            "point_light() sphere(r=1);",
+            "point_light() doesn't take children",
+        );
+        assert_output_trim(
+            "spot_light() sphere(r=1);",
+            "spot_light() doesn't take children",
+        );
+        assert_output_trim("sun() sphere(r=1);", "sun() doesn't take children");
+    }
+
+    #[test]
+    fn test_dielectric_options() {
+        let material = single_material(
+            "dielectric(1.5, c=[0.3, 0.6, 0.4], distance=2, roughness=0.2) sphere(r=1);",
+        );
+        let dielectric = material.as_any().downcast_ref::<Dielectric>().unwrap();
+        assert_eq!(
+            dielectric.refraction_index(),
+            RefractiveIndex::Constant(1.5)
+        );
+        // the tint is what goes through the distance
+        let absorption = dielectric.absorption();
+        assert!((absorption.g - -0.6f64.ln() / 2.0).abs() < 1e-12);
+        assert!((absorption.b - -0.4f64.ln() / 2.0).abs() < 1e-12);
+        assert_eq!(dielectric.roughness(), 0.2);
+
+        let material = single_material("dielectric(1.33) sphere(r=1);");
+        let dielectric = material.as_any().downcast_ref::<Dielectric>().unwrap();
+        assert_eq!(dielectric.absorption(), Color::BLACK);
+        assert_eq!(dielectric.roughness(), 0.0);
+    }
+
+    #[test]
+    fn test_dispersive_dielectric() {
+        let results = interpret("camera(spectral=true);");
+        assert_eq!(results.messages.len(), 0);
+        assert!(results.scene_data.unwrap().camera.is_spectral());
+        assert!(
+            !interpret("camera();")
+                .scene_data
+                .unwrap()
+                .camera
+                .is_spectral()
+        );
+
+        let refraction_index = |expr: &str| {
+            let material = single_material(expr);
+            material
+                .as_any()
+                .downcast_ref::<Dielectric>()
+                .unwrap()
+                .refraction_index()
+        };
+        assert_eq!(
+            refraction_index("dielectric(\"sf11\") sphere(r=1);"),
+            RefractiveIndex::SF11
+        );
+        let cauchy = refraction_index("dielectric(cauchy=[1.5046, 0.0042]) sphere(r=1);");
+        assert_eq!(
+            cauchy,
+            RefractiveIndex::Cauchy {
+                a: 1.5046,
+                b: 0.0042
+            }
+        );
+        assert!((cauchy.at(500.0) - 1.5214).abs() < 1e-12);
+        let sellmeier = refraction_index(
+            "dielectric(sellmeier=[1.03961212, 0.231792344, 1.01046945, 0.00600069867, 0.0200179144, 103.560653]) sphere(r=1);",
+        );
+        assert_eq!(sellmeier, RefractiveIndex::BK7);
+        assert!((sellmeier.at(587.56) - 1.5168).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_dielectric_unknown_glass() {
+        assert_output_trim(
+            "dielectric(\"flint\") sphere(r=1);",
+            "unknown glass \"flint\"",
+        );
+    }
+
+    #[test]
+    fn test_dielectric_cauchy_expects_two_numbers() {
+        assert_output_trim(
+            "dielectric(cauchy=[1.5]) sphere(r=1);",
+            "cauchy expects [a, b]",
+        );
+    }
+
+    /// Returns the only object of the scene.
+    fn single_node(expr: &str) -> Arc<dyn Node> {
+        let results = interpret(expr);
+        assert_eq!(results.messages.len(), 0);
+
+        let scene_data = results.scene_data.unwrap();
+        let bvh = scene_data
+            .world
+            .as_any()
+            .downcast_ref::<BoundingVolumeHierarchy>()
+            .unwrap();
+        assert_eq!(bvh.primitives().len(), 1);
+        bvh.primitives()[0].clone()
+    }
+
+    /// Returns the material of the only object of the scene, which must be at the
+    /// origin.
+    fn single_material(expr: &str) -> Arc<dyn Material> {
+        let ctx = RenderContext {
+            random: random_new(),
+        };
+        let ray = Ray::new(Vector3::new(0.0, 0.0, -10.0), Vector3::new(0.0, 0.0, 1.0));
+        single_node(expr)
+            .hit(&ctx, &ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap()
+            .material
+    }
+
+    /// Returns the value of a texture that is a single color.
+    fn solid_value(texture: &Arc<dyn Texture>) -> Color {
+        assert!(texture.as_any().is::<SolidColor>());
+        texture.value(0.0, 0.0, Vector3::ZERO)
+    }
+
+    #[test]
+    fn test_subsurface() {
+        let material =
+            single_material("subsurface(scattering=[5, 2, 1], absorption=0.1, g=0.8) sphere(r=1);");
+        let subsurface = material.as_any().downcast_ref::<Subsurface>().unwrap();
+        assert_eq!(subsurface.scattering(), Color::new(5.0, 2.0, 1.0));
+        assert_eq!(subsurface.absorption(), Color::new(0.1, 0.1, 0.1));
+        assert_eq!(subsurface.anisotropy(), 0.8);
+
+        // the coat goes over the subsurface material
+        let material = single_material("subsurface() clearcoat() sphere(r=1);");
+        let clearcoat = material.as_any().downcast_ref::<Clearcoat>().unwrap();
+        let subsurface = clearcoat
+            .base()
+            .as_any()
+            .downcast_ref::<Subsurface>()
+            .unwrap();
+        assert_eq!(subsurface.scattering(), Color::WHITE * 10.0);
+        assert_eq!(subsurface.absorption(), Color::BLACK);
+        assert_eq!(subsurface.anisotropy(), 0.0);
+    }
+
+    #[test]
+    fn test_clearcoat() {
+        let material = single_material(
+            "color([0, 0.3, 0.8]) clearcoat(roughness=0.05, c=[1, 0.9, 0.8]) sphere(r=1);",
+        );
+        let clearcoat = material.as_any().downcast_ref::<Clearcoat>().unwrap();
+        assert!(clearcoat.base().as_any().is::<Lambertian>());
+        assert_eq!(clearcoat.refraction_index(), 1.5);
+        assert_eq!(clearcoat.roughness(), 0.05);
+        assert_eq!(clearcoat.tint(), Color::new(1.0, 0.9, 0.8));
+
+        let material = single_material("clearcoat(n=1.6) sphere(r=1);");
+        let clearcoat = material.as_any().downcast_ref::<Clearcoat>().unwrap();
+        assert_eq!(clearcoat.refraction_index(), 1.6);
+        assert_eq!(clearcoat.roughness(), 0.0);
+        assert_eq!(clearcoat.tint(), Color::WHITE);
+    }
+
+    #[test]
+    fn test_pbr() {
+        let material =
+            single_material("pbr(c=[1, 0.8, 0.3], metallic=1, roughness=0.3) sphere(r=1);");
+        let pbr = material
+            .as_any()
+            .downcast_ref::<MetallicRoughness>()
+            .unwrap();
+        assert_eq!(solid_value(pbr.base_color()), Color::new(1.0, 0.8, 0.3));
+        assert_eq!(solid_value(pbr.metallic()).b, 1.0);
+        assert_eq!(solid_value(pbr.roughness()).g, 0.3);
+
+        let material = single_material(
+            "pbr(t=checker(scale=1, even=[1, 1, 1], odd=[0, 0, 0]), roughness=checker(scale=1, even=0.2, odd=0.8)) sphere(r=1);",
+        );
+        let pbr = material
+            .as_any()
+            .downcast_ref::<MetallicRoughness>()
+            .unwrap();
+        assert!(pbr.base_color().as_any().is::<CheckerTexture>());
+        assert!(pbr.roughness().as_any().is::<CheckerTexture>());
+        // not metallic by default
+        assert_eq!(solid_value(pbr.metallic()).b, 0.0);
+    }
+
+    #[test]
+    fn test_medium() {
+        let node = single_node("medium(density=0.5, c=[0.8, 0.8, 0.8]) cube(1);");
+        let medium = node.as_any().downcast_ref::<ConstantMedium>().unwrap();
+        assert_eq!(medium.density(), 0.5);
+        let phase_function = medium.phase_function().as_any();
+        let isotropic = phase_function.downcast_ref::<Isotropic>().unwrap();
+        assert_eq!(solid_value(isotropic.texture()), Color::new(0.8, 0.8, 0.8));
+
+        let node = single_node(
+            "medium(density=5, field=perlin_turbulence(scale=0, turbulence_depth=7)) sphere(r=1);",
+        );
+        let medium = node.as_any().downcast_ref::<HeterogeneousMedium>().unwrap();
+        assert_eq!(medium.density(), 5.0);
+        // textures are fields of densities between 0 and 1
+        assert_eq!(medium.density_field().max_density(), 1.0);
+        let phase_function = medium.phase_function().as_any();
+        let isotropic = phase_function.downcast_ref::<Isotropic>().unwrap();
+        assert_eq!(solid_value(isotropic.texture()), Color::WHITE);
+    }
+
+    #[test]
+    fn test_medium_without_children() {
+        let node = single_node("medium(density=0.5); sphere(r=1);");
+        assert!(node.as_any().is::<Sphere>());
+    }
+
+    #[test]
+    fn test_nested_transforms_collapse() {
+        let node =
+            single_node("translate([1, 2, 3]) rotate([0, 0, 90]) scale([2, 1, 1]) sphere(r=1);");
+        let transform = node.as_any().downcast_ref::<Transform>().unwrap();
+        assert!(transform.object().as_any().is::<Sphere>());
+
+        // the sphere is 2 wide along OpenSCAD y after the rotation, around [1, 2, 3]
+        // which is [-1, 3, 2] for us
+        let bbox = node.bounding_box();
+        let expected = [
+            (Axis::X, -1.0, 1.0),
+            (Axis::Y, 3.0, 1.0),
+            (Axis::Z, 2.0, 2.0),
+        ];
+        for (axis, center, half_size) in expected {
+            let interval = bbox.axis_interval(axis);
+            assert!(
+                (interval.min - (center - half_size)).abs() < 1e-9,
+                "{axis:?} {interval:?}"
+            );
+            assert!(
+                (interval.max - (center + half_size)).abs() < 1e-9,
+                "{axis:?} {interval:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rotate_axis_order() {
+        // around x first, moving +y to +z, then around z leaving it there
+        let node = single_node("rotate([90, 0, 90]) translate([0, 5, 0]) sphere(r=1);");
+        let center = node.bounding_box().centroid();
+        // OpenSCAD +z is +y for us
+        assert!((center.y - 5.0).abs() < 1e-9, "{center:?}");
+
+        let node = single_node("rotate(a=90, v=[1, 0, 0]) translate([0, 5, 0]) sphere(r=1);");
+        let center = node.bounding_box().centroid();
+        assert!((center.y - 5.0).abs() < 1e-9, "{center:?}");
+    }
+
+    #[test]
+    fn test_mirror_and_multmatrix() {
+        let node = single_node("mirror([1, 0, 0]) translate([3, 0, 0]) sphere(r=1);");
+        // OpenSCAD x = -3 is x = 3 for us
+        assert!((node.bounding_box().centroid().x - 3.0).abs() < 1e-9);
+
+        let node = single_node(
+            "multmatrix([[1, 0, 0, 10], [0, 1, 0, 20], [0, 0, 1, 30], [0, 0, 0, 1]]) sphere(r=1);",
+        );
+        let center = node.bounding_box().centroid();
+        assert!((center - Vector3::new(-10.0, 30.0, 20.0)).length() < 1e-9);
+    }
+
+    #[test]
+    fn test_transforms_without_children() {
+        let results = interpret(
+            "translate([1, 0, 0]); rotate(90); scale(2); mirror(); multmatrix(); sphere(r=1);",
+        );
+        assert_eq!(results.messages.len(), 0);
+        let scene_data = results.scene_data.unwrap();
+        let bvh = scene_data
+            .world
+            .as_any()
+            .downcast_ref::<BoundingVolumeHierarchy>()
+            .unwrap();
+        assert_eq!(bvh.primitives().len(), 1);
+    }
+
+    #[test]
+    fn test_rotate_and_scale_in_openscad_coordinates() {
+        let center = |expr: &str| single_node(expr).bounding_box().centroid();
+        let close = |a: Vector3, b: Vector3| (a - b).length() < 1e-9;
+
+        // OpenSCAD (0, 3, 0) is (0, 0, 3) for us
+        let c = center("rotate([0, 0, 90]) translate([3, 0, 0]) sphere(r=1);");
+        assert!(close(c, Vector3::new(0.0, 0.0, 3.0)), "{c:?}");
+        // OpenSCAD (0, 0, 3) is (0, 3, 0) for us
+        let c = center("rotate([90, 0, 0]) translate([0, 3, 0]) sphere(r=1);");
+        assert!(close(c, Vector3::new(0.0, 3.0, 0.0)), "{c:?}");
+
+        // OpenSCAD x = 6 is x = -6 for us, and the sphere is 4 wide along it
+        let node = single_node("scale([2, 1, 1]) translate([3, 0, 0]) sphere(r=1);");
+        let c = node.bounding_box().centroid();
+        assert!(close(c, Vector3::new(-6.0, 0.0, 0.0)), "{c:?}");
+        let x = node.bounding_box().axis_interval(Axis::X);
+        assert!((x.max - x.min - 4.0).abs() < 1e-3, "{x:?}");
+    }
+
+    #[test]
+    fn test_transform_defaults() {
+        // without arguments the transformations keep the sphere at x = 3 for us
+        for transform in ["rotate()", "scale()", "multmatrix()"] {
+            let node = single_node(&format!("{transform} translate([-3, 0, 0]) sphere(r=1);"));
+            assert!((node.bounding_box().centroid().x - 3.0).abs() < 1e-9);
+        }
+        // mirror() mirrors x like OpenSCAD
+        let node = single_node("mirror() translate([-3, 0, 0]) sphere(r=1);");
+        assert!((node.bounding_box().centroid().x + 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_multmatrix_bad_matrix() {
+        assert_output_trim(
+            "multmatrix([1, 2, 3]) sphere(r=1);",
+            "m must be a 4x4 matrix, a list of 3 or 4 rows of 4 numbers",
+        );
+    }
+
+    #[test]
+    fn test_motion() {
+        let results = interpret(
+            "camera(shutter_open=0, shutter_close=0.5);
+             motion(translate=[4, 0, 0], rotate=[0, 0, 30]) sphere(r=1);",
+        );
+        assert_eq!(results.messages.len(), 0);
+
+        let scene_data = results.scene_data.unwrap();
+        let bvh = scene_data
+            .world
+            .as_any()
+            .downcast_ref::<BoundingVolumeHierarchy>()
+            .unwrap();
+        assert_eq!(bvh.primitives().len(), 1);
+        let motion = &bvh.primitives()[0];
+        assert!(motion.as_any().is::<AnimatedTransform>());
+        // the bounding box covers the sphere from x = 0 to x = 4, which is -x for us
+        let x = motion.bounding_box().axis_interval(Axis::X);
+        assert!(x.min <= -5.0 && x.min > -5.5, "{x:?}");
+        assert!(x.max >= 1.0 && x.max < 1.5, "{x:?}");
+    }
+
+    #[test]
+    fn test_motion_bad_time() {
+        assert_output_trim(
+            "motion(translate=[1, 0, 0], time=1) sphere(r=1);",
+            "time must be a list of 2 numbers [start, end]",
+        );
+    }
+
+    #[test]
+    fn test_motion_without_children() {
+        let node = single_node("motion(translate=[1, 0, 0]); sphere(r=1);");
+        assert!(node.as_any().is::<Sphere>());
+    }
+
+    #[test]
+    fn test_csg() {
+        let node = single_node("difference() { cube(10, center=true); sphere(r=6); }");
+        let csg = node.as_any().downcast_ref::<Csg>().unwrap();
+        assert_eq!(csg.operation(), CsgOperation::Difference);
+        assert_eq!(csg.children().len(), 2);
+
+        // the loop is a single operand, the union of the spheres
+        let node = single_node(
+            "intersection() { cube(10); for (x = [0:10:20]) translate([x, 0, 0]) sphere(r=3); }",
+        );
+        let csg = node.as_any().downcast_ref::<Csg>().unwrap();
+        assert_eq!(csg.operation(), CsgOperation::Intersection);
+        assert_eq!(csg.children().len(), 2);
+        let spheres = csg.children()[1].as_any().downcast_ref::<Csg>().unwrap();
+        assert_eq!(spheres.operation(), CsgOperation::Union);
+        assert_eq!(spheres.children().len(), 2);
+
+        // a single child needs no combining
+        let node = single_node("union() sphere(r=1);");
+        assert!(node.as_any().is::<Sphere>());
+    }
+
+    #[test]
+    fn test_import_unsupported_format() {
+        assert_output_trim(
+            "import(\"model.dxf\");",
+            "unsupported import format \"model.dxf\"",
+        );
+    }
+
+    #[test]
+    fn test_import_missing_file() {
+        assert_output_trim("import();", "missing file argument");
+        assert_output_trim(
+            "import(\"model.stl\");",
+            "failed to import \"model.stl\": Io(\"file not found \\\"model.stl\\\"\")",
+        );
+    }
+
+    #[test]
+    fn test_import_with_children() {
+        assert_output_trim(
+            "import(\"cube.obj\") sphere(r=1);",
+            "import() doesn't take children",
+        );
+    }
+
+    #[test]
+    fn test_camera_unknown_tone_mapping() {
+        assert_output_trim(
+            "camera(tone_mapping=\"filmic\");",
+            "unknown tone mapping \"filmic\"",
+        );
+    }
+
+    #[test]
+    fn test_camera_unknown_sampler() {
+        assert_output_trim(
+            "camera(sampler=\"stratified\");",
+            "unknown sampler \"stratified\"",
+        );
+    }
+
+    #[test]
+    fn test_camera_background_texture_must_be_image() {
+        assert_output_trim(
+            "camera(background=checker());",
+            "background texture must be an image",
+        );
+    }
+
+    #[test]
+    fn test_camera_unknown_background() {
+        assert_output_trim(
+            "camera(background=\"night\");",
+            "unknown background \"night\"",
+        );
+    }
+
     // -- special variables ----------------------------
 
     #[test]
@@ -326,6 +837,19 @@ mod tests {
         assert_eq!(0, result.messages.len());
     }
 
+    #[test]
+    fn test_rands_with_seed() {
+        assert_output_trim(
+            "
+                a = rands(0, 10, 2, 42);
+                b = rands(0, 10, 2, 42);
+                c = rands(0, 10, 2, 43);
+                echo(a[0] == b[0] && a[1] == b[1], a[0] == c[0]);
+            ",
+            "true, false",
+        );
+    }
+
     // -- function ----------------------------
 
     #[test]
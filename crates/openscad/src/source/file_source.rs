@@ -8,6 +8,7 @@ use std::{
 use caustic_core::{
     Image,
     image::{ImageError, ImageImage},
+    import::ImportError,
 };
 
 use crate::source::Source;
@@ -60,6 +61,15 @@ impl Source for FileSource {
         ImageImage::load_file(image_filename)
     }
 
+    fn get_bytes(&self, filename: &str) -> Result<Vec<u8>, ImportError> {
+        let dir = self.filename_path.parent().ok_or(ImportError::Io(format!(
+            "source file \"{:?}\" has no parent",
+            self.filename_path
+        )))?;
+        fs::read(dir.join(filename))
+            .map_err(|err| ImportError::Io(format!("Failed to read \"{filename}\": {err}")))
+    }
+
     fn get_filename(&self) -> &str {
         &self.filename
     }
@@ -2,7 +2,12 @@
 mod file_source;
 mod string_source;
 
-use caustic_core::{Image, image::ImageError, line_number_at_offset};
+use caustic_core::{
+    Image,
+    image::ImageError,
+    import::{ImportError, ImportSource},
+    line_number_at_offset,
+};
 #[cfg(not(target_arch = "wasm32"))]
 pub use file_source::FileSource;
 use std::{any::Any, fmt::Debug, sync::Arc};
@@ -12,6 +17,7 @@ pub trait Source: Debug {
     fn get_filename(&self) -> &str;
     fn get_code(&self) -> &str;
     fn get_image(&self, filename: &str) -> Result<Arc<dyn Image>, ImageError>;
+    fn get_bytes(&self, filename: &str) -> Result<Vec<u8>, ImportError>;
     fn as_any(&self) -> &dyn Any;
 
     fn equals(&self, other: &dyn Source) -> bool {
@@ -29,3 +35,13 @@ impl PartialEq for dyn Source {
         self.equals(other)
     }
 }
+
+impl ImportSource for Box<dyn Source> {
+    fn get_bytes(&self, filename: &str) -> Result<Vec<u8>, ImportError> {
+        Source::get_bytes(self.as_ref(), filename)
+    }
+
+    fn get_image(&self, filename: &str) -> Result<Arc<dyn Image>, ImageError> {
+        Source::get_image(self.as_ref(), filename)
+    }
+}
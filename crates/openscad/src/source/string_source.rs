@@ -1,6 +1,6 @@
 use std::{any::Any, sync::Arc};
 
-use caustic_core::{Image, image::ImageError};
+use caustic_core::{Image, image::ImageError, import::ImportError};
 
 use crate::source::Source;
 
@@ -30,6 +30,11 @@ impl Source for StringSource {
         todo!("get_image {filename}")
     }
 
+    fn get_bytes(&self, filename: &str) -> Result<Vec<u8>, ImportError> {
+        // code from a string has no directory to look for files in
+        Err(ImportError::Io(format!("file not found \"{filename}\"")))
+    }
+
     fn get_filename(&self) -> &str {
         "string"
     }
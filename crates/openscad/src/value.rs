@@ -1,6 +1,9 @@
 use std::{fmt::Display, sync::Arc};
 
-use caustic_core::{Color, Vector3, texture::Texture};
+use caustic_core::{
+    Color, Vector3,
+    texture::{SolidColor, Texture},
+};
 
 use crate::WithPosition;
 
@@ -63,6 +66,14 @@ impl Value {
         }
     }
 
+    /// Returns the texture, or a solid texture of the color for colors and numbers.
+    pub fn to_texture(&self) -> Result<Arc<dyn Texture>> {
+        match self {
+            Value::Texture(texture) => Ok(texture.clone()),
+            _ => Ok(Arc::new(SolidColor::new(self.to_color()?))),
+        }
+    }
+
     pub fn to_boolean(&self) -> Result<bool> {
         match self {
             Value::Boolean(b) => Ok(*b),
@@ -3,13 +3,19 @@
 pub mod language_server;
 pub mod types;
 
-use std::{any::Any, cell::RefCell, fmt::Debug, sync::Arc};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    fmt::Debug,
+    sync::Arc,
+};
 
 use caustic_core::{
-    Color as CoreColor, Image, RenderContext, SceneData, image::ImageError, random_new,
+    Color as CoreColor, Image, ProgressiveRenderer, Random, RenderContext, SceneData, SeededRandom,
+    image::ImageError, import::ImportError, random_new,
 };
 use caustic_openscad::{run_openscad, source::Source};
-use js_sys::Uint8ClampedArray;
+use js_sys::{Uint8Array, Uint8ClampedArray};
 use serde::{Deserialize, Serialize};
 use tsify::Tsify;
 use wasm_bindgen::prelude::*;
@@ -20,6 +26,23 @@ pub use language_server::WasmLspServer;
 
 thread_local! {
 static LOADED_SCENE_DATA: RefCell<Option<SceneData>> = const { RefCell::new(None) };
+static PROGRESSIVE_RENDERER: RefCell<Option<ProgressiveRenderer>> = const { RefCell::new(None) };
+static SEED: Cell<Option<u32>> = const { Cell::new(None) };
+}
+
+/// Sets the seed used by subsequent loads and renders, or `None` to go back to
+/// unseeded random numbers. Seeded renders are identical on every run.
+#[wasm_bindgen]
+pub fn set_seed(seed: Option<u32>) {
+    SEED.with(|s| s.set(seed));
+    PROGRESSIVE_RENDERER.with(|renderer| *renderer.borrow_mut() = None);
+}
+
+fn new_random() -> Arc<dyn Random> {
+    match SEED.with(|s| s.get()) {
+        Some(seed) => Arc::new(SeededRandom::new(seed as u64)),
+        None => random_new(),
+    }
 }
 
 #[wasm_bindgen(typescript_custom_section)]
@@ -28,6 +51,7 @@ export interface WasmSource {
     get_filename(): string;
     get_code(): string;
     get_image(filename: string): WasmImage;
+    get_bytes(filename: string): Uint8Array;
 }
 "#;
 
@@ -43,6 +67,9 @@ extern "C" {
 
     #[wasm_bindgen(method, catch)]
     pub fn get_image(this: &WasmSource, filename: &str) -> Result<WasmImage, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    pub fn get_bytes(this: &WasmSource, filename: &str) -> Result<Uint8Array, JsValue>;
 }
 
 // Add this wrapper struct
@@ -97,6 +124,13 @@ impl Source for WasmSourceAdapter {
         Ok(Arc::new(image_adapter))
     }
 
+    fn get_bytes(&self, filename: &str) -> Result<Vec<u8>, ImportError> {
+        let bytes = self.wasm_source.get_bytes(filename).map_err(|err| {
+            ImportError::Io(format!("getting file from JavaScript failed: {err:?}"))
+        })?;
+        Ok(bytes.to_vec())
+    }
+
     fn get_filename(&self) -> &str {
         &self.filename
     }
@@ -183,10 +217,10 @@ impl Debug for WasmImageAdapter {
 #[wasm_bindgen]
 pub fn load_openscad(wasm_source: WasmSource) -> Result<LoadResults, JsValue> {
     let source: Arc<Box<dyn Source>> = Arc::new(Box::new(WasmSourceAdapter::new(wasm_source)?));
-    let random = random_new();
-    let results = run_openscad(source, random);
+    let results = run_openscad(source, new_random());
     let messages = results.messages.iter().map(|m| m.into()).collect();
 
+    PROGRESSIVE_RENDERER.with(|renderer| *renderer.borrow_mut() = None);
     let loaded = match results.scene_data {
         Some(scene_data) => {
             LOADED_SCENE_DATA.with(|data| *data.borrow_mut() = Some(scene_data));
@@ -216,7 +250,7 @@ pub fn render(xmin: u32, xmax: u32, ymin: u32, ymax: u32) -> Result<Vec<Color>,
     LOADED_SCENE_DATA.with(|data| {
         if let Some(scene_data) = data.borrow().as_ref() {
             let ctx = Arc::new(RenderContext {
-                random: random_new(),
+                random: new_random(),
             });
             let mut results: Vec<Color> = vec![];
 
@@ -227,7 +261,7 @@ pub fn render(xmin: u32, xmax: u32, ymin: u32, ymax: u32) -> Result<Vec<Color>,
                         x,
                         y,
                         &*scene_data.world,
-                        scene_data.lights.clone(),
+                        &scene_data.lights,
                     );
                     let color = Color::from(pixel_color);
                     results.push(color);
@@ -241,6 +275,36 @@ pub fn render(xmin: u32, xmax: u32, ymin: u32, ymax: u32) -> Result<Vec<Color>,
     })
 }
 
+/// Adds `samples` samples to every pixel of the loaded scene and returns the
+/// whole image refined so far, row-major.
+#[wasm_bindgen]
+pub fn render_progressive_pass(samples: u32) -> Result<Vec<Color>, JsValue> {
+    PROGRESSIVE_RENDERER.with(|renderer| {
+        let mut renderer = renderer.borrow_mut();
+        if renderer.is_none() {
+            *renderer = LOADED_SCENE_DATA.with(|data| {
+                data.borrow().as_ref().map(|scene_data| {
+                    ProgressiveRenderer::new(
+                        scene_data.camera.clone(),
+                        scene_data.world.clone(),
+                        scene_data.lights.clone(),
+                    )
+                })
+            });
+        }
+
+        if let Some(renderer) = renderer.as_mut() {
+            let ctx = RenderContext {
+                random: new_random(),
+            };
+            renderer.render_pass(&ctx, samples);
+            Ok(renderer.image().into_iter().map(Color::from).collect())
+        } else {
+            Err(JsValue::from_str("Scene data not loaded"))
+        }
+    })
+}
+
 #[derive(Tsify, Serialize, Deserialize)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 #[serde(rename_all = "camelCase")]
@@ -4,7 +4,23 @@ use crate::repository::project_repository::CONTENT_TYPE_OPENSCAD;
 
 pub mod google;
 
+/// Model formats readable by OpenSCAD `import()`, most of which `mime_guess` does not know
+const MODEL_MIME_TYPES: &[(&str, &str)] = &[
+    (".stl", "model/stl"),
+    (".off", "model/x-off"),
+    (".obj", "model/obj"),
+    (".mtl", "model/mtl"),
+];
+
 pub fn mime_type_from_path(path: &str) -> Result<String> {
+    let lower_path = path.to_lowercase();
+    if let Some((_, mime_type)) = MODEL_MIME_TYPES
+        .iter()
+        .find(|(ext, _)| lower_path.ends_with(ext))
+    {
+        return Ok(mime_type.to_string());
+    }
+
     let guess = mime_guess::from_path(path).first();
     if let Some(mime_type) = guess {
         Ok(mime_type.to_string())
@@ -1,6 +1,6 @@
 import { getCameraInfo, initWasm, loadOpenscad, Source, type CameraInfo, type WasmMessage } from '../wasm';
 import { RenderWorkerPool, type RenderCallbackFn } from '../RenderWorkerPool';
-import type { ImageWorkingFile, TextWorkingFile, WorkingFile } from '../types';
+import type { BinaryWorkingFile, ImageWorkingFile, TextWorkingFile, WorkingFile } from '../types';
 import { type Project } from '../api';
 import { computed, signal } from '@preact/signals-react';
 import {
@@ -140,7 +140,7 @@ export class ProjectStore {
                         originalContents: contents,
                         contents,
                     } satisfies TextWorkingFile;
-                } else {
+                } else if (contentType?.startsWith('image/')) {
                     const contents = await response.blob();
                     const imageData = await getImageDataFromBlob(contents);
 
@@ -151,6 +151,13 @@ export class ProjectStore {
                         height: imageData.height,
                         pixels: imageData.data,
                     } satisfies ImageWorkingFile;
+                } else {
+                    const contents = new Uint8Array(await response.arrayBuffer());
+                    return {
+                        ...f,
+                        type: 'binary',
+                        contents,
+                    } satisfies BinaryWorkingFile;
                 }
             })
         );
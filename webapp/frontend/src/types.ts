@@ -51,4 +51,9 @@ export interface ImageWorkingFile extends ProjectFile {
     pixels: ImageDataArray;
 }
 
-export type WorkingFile = TextWorkingFile | ImageWorkingFile;
+export interface BinaryWorkingFile extends ProjectFile {
+    type: 'binary';
+    contents: Uint8Array;
+}
+
+export type WorkingFile = TextWorkingFile | ImageWorkingFile | BinaryWorkingFile;
@@ -8,7 +8,7 @@ import type {
     WasmSource,
     WasmMessage,
 } from './wasm/debug/caustic_wasm';
-import init, { load_openscad, get_camera_info, render } from './wasm/debug/caustic_wasm.js';
+import init, { load_openscad, get_camera_info, render, set_seed } from './wasm/debug/caustic_wasm.js';
 export { WasmLspServer } from './wasm/debug/caustic_wasm.js';
 
 export type { CameraInfo, Color, WasmMessage };
@@ -29,6 +29,10 @@ export function renderBlock(xmin: number, xmax: number, ymin: number, ymax: numb
     return render(xmin, xmax, ymin, ymax);
 }
 
+export function setSeed(seed: number | undefined): void {
+    set_seed(seed);
+}
+
 export class Source implements WasmSource {
     public constructor(
         private readonly main: TextWorkingFile,
@@ -53,6 +57,20 @@ export class Source implements WasmSource {
         }
         return new Image(file);
     }
+
+    public get_bytes(filename: string): Uint8Array {
+        const file = this.files.find((f) => f.filename === filename);
+        if (!file) {
+            throw new Error('file not found');
+        }
+        if (file.type === 'text') {
+            return new TextEncoder().encode(file.contents);
+        }
+        if (file.type === 'binary') {
+            return file.contents;
+        }
+        throw new Error('expected file of type text or binary');
+    }
 }
 
 export class Image implements WasmImage {